@@ -1,15 +1,128 @@
+use crate::core::dictation::SpokenPhrase;
+use crate::core::error::FlashspanError;
+use crate::session::recover_lock;
+use crate::sound_packs::SoundPack;
 use log::{error, info, warn};
-use rodio::{Decoder, DeviceSinkBuilder, Player};
+use rodio::{Decoder, DeviceSinkBuilder, Player, Source};
+use serde::{Deserialize, Serialize};
 use std::io::Cursor;
-use std::sync::OnceLock;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::{Sender, channel};
+use std::sync::{Arc, Mutex, OnceLock};
 
-static AUDIO_SENDER: OnceLock<Result<Sender<&'static [u8]>, String>> = OnceLock::new();
+/// Event kinds with a built-in sound, which a sound pack may replace.
+pub const SOUND_KINDS: [&str; 3] = ["beep", "applause", "buzzer"];
 
-fn get_audio_sender() -> Result<&'static Sender<&'static [u8]>, String> {
+/// Highest master volume or gain, in percent.
+pub const MAX_LEVEL: u32 = 100;
+
+/// Which gain a sound plays at: the cue with each flash, or the result feedback.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SoundGroup {
+    /// The beep, or the spoken number in dictation sessions.
+    Beep,
+    /// Applause and buzzer.
+    Feedback,
+}
+
+fn group_of(kind: &str) -> SoundGroup {
+    if kind == "beep" {
+        SoundGroup::Beep
+    } else {
+        SoundGroup::Feedback
+    }
+}
+
+/// Playback levels in percent, saved with the settings. `master` scales every sound on top of
+/// the gain of its group.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioLevels {
+    pub master: u32,
+    pub beep: u32,
+    pub feedback: u32,
+}
+
+impl AudioLevels {
+    pub const FULL: Self = Self {
+        master: MAX_LEVEL,
+        beep: MAX_LEVEL,
+        feedback: MAX_LEVEL,
+    };
+
+    pub fn validate(&self) -> Result<(), FlashspanError> {
+        for (field, value) in [
+            ("master", self.master),
+            ("beep", self.beep),
+            ("feedback", self.feedback),
+        ] {
+            if value > MAX_LEVEL {
+                return Err(FlashspanError::out_of_range(field, 0, MAX_LEVEL));
+            }
+        }
+        Ok(())
+    }
+
+    /// Cap each level at [`MAX_LEVEL`], e.g. after reading a hand-edited settings file.
+    pub fn clamped(self) -> Self {
+        Self {
+            master: self.master.min(MAX_LEVEL),
+            beep: self.beep.min(MAX_LEVEL),
+            feedback: self.feedback.min(MAX_LEVEL),
+        }
+    }
+
+    fn master_volume(&self) -> f32 {
+        self.master as f32 / MAX_LEVEL as f32
+    }
+
+    fn gain(&self, group: SoundGroup) -> f32 {
+        let percent = match group {
+            SoundGroup::Beep => self.beep,
+            SoundGroup::Feedback => self.feedback,
+        };
+        percent as f32 / MAX_LEVEL as f32
+    }
+}
+
+impl Default for AudioLevels {
+    fn default() -> Self {
+        Self::FULL
+    }
+}
+
+/// Encoded audio for one sound: compiled in, or read from the selected pack.
+#[derive(Clone)]
+enum SoundData {
+    Embedded(&'static [u8]),
+    Pack(Arc<[u8]>),
+}
+
+impl AsRef<[u8]> for SoundData {
+    fn as_ref(&self) -> &[u8] {
+        match self {
+            Self::Embedded(data) => data,
+            Self::Pack(data) => data,
+        }
+    }
+}
+
+/// Work for the audio worker thread, so callers never wait on decoding or playback.
+enum AudioJob {
+    Sound(SoundData, SoundGroup),
+    /// Dictation: clips are loaded and stretched on the worker.
+    Phrase(SpokenPhrase),
+    /// New levels; the master volume also applies to sounds already playing.
+    Levels(AudioLevels),
+    /// Drop everything queued or playing.
+    Cut,
+}
+
+static AUDIO_SENDER: OnceLock<Result<Sender<AudioJob>, String>> = OnceLock::new();
+
+fn get_audio_sender() -> Result<&'static Sender<AudioJob>, String> {
     let value = AUDIO_SENDER.get_or_init(|| {
-        let (tx, rx) = channel::<&'static [u8]>();
+        let (tx, rx) = channel::<AudioJob>();
 
         match std::thread::Builder::new()
             .name("audio-worker".into())
@@ -17,14 +130,10 @@ fn get_audio_sender() -> Result<&'static Sender<&'static [u8]>, String> {
                 Ok(stream) => {
                     info!("audio worker initialized output sink");
                     let player = Player::connect_new(stream.mixer());
-                    while let Ok(data) = rx.recv() {
-                        let cursor = Cursor::new(data);
-                        match Decoder::try_from(cursor) {
-                            Ok(src) => {
-                                player.append(src);
-                            }
-                            Err(e) => error!("audio decode error: {}", e),
-                        }
+                    let mut levels = levels();
+                    player.set_volume(levels.master_volume());
+                    while let Ok(job) = rx.recv() {
+                        run_job(&player, &mut levels, job);
                     }
                     info!("audio worker receiver loop ended");
                 }
@@ -41,37 +150,134 @@ fn get_audio_sender() -> Result<&'static Sender<&'static [u8]>, String> {
     value.as_ref().map_err(|e| e.clone())
 }
 
-fn play_bytes(data: &'static [u8]) -> Result<(), String> {
+fn run_job(player: &Player, levels: &mut AudioLevels, job: AudioJob) {
+    match job {
+        AudioJob::Sound(data, group) => match Decoder::try_from(Cursor::new(data)) {
+            Ok(src) => player.append(src.amplify(levels.gain(group))),
+            Err(e) => error!("audio decode error: {}", e),
+        },
+        AudioJob::Phrase(phrase) => match crate::dictation::render(&phrase) {
+            Ok(buffer) => {
+                // A phrase that overran its flash gives way to the next number rather than
+                // pushing it later.
+                clear_player(player);
+                player.append(buffer.amplify(levels.gain(SoundGroup::Beep)));
+            }
+            Err(e) => error!("dictation failed: {}", e),
+        },
+        AudioJob::Levels(new_levels) => {
+            *levels = new_levels;
+            player.set_volume(new_levels.master_volume());
+        }
+        AudioJob::Cut => clear_player(player),
+    }
+}
+
+fn clear_player(player: &Player) {
+    if !player.empty() {
+        // Clearing also pauses the player.
+        player.clear();
+        player.play();
+    }
+}
+
+/// Whether the worker has been started; level changes and cuts before then need no job.
+fn worker_running() -> bool {
+    matches!(AUDIO_SENDER.get(), Some(Ok(_)))
+}
+
+fn send_job(job: AudioJob) -> Result<(), FlashspanError> {
     let sender = match get_audio_sender() {
         Ok(s) => s,
         Err(e) => {
             warn!("audio sender init failed: {}", e);
-            return Err(e);
+            return Err(FlashspanError::Audio { detail: e });
         }
     };
 
-    sender
-        .send(data)
-        .map_err(|e| format!("audio send error: {}", e))
+    sender.send(job).map_err(|e| FlashspanError::Audio {
+        detail: format!("send failed: {}", e),
+    })
+}
+
+fn embedded_sound(kind: &str) -> Option<&'static [u8]> {
+    match kind {
+        "beep" => Some(include_bytes!("../../src/assets/beep.wav")),
+        "applause" => Some(include_bytes!("../../src/assets/applause.wav")),
+        "buzzer" => Some(include_bytes!("../../src/assets/buzzer.wav")),
+        _ => None,
+    }
+}
+
+static SOUND_PACK: Mutex<Option<SoundPack>> = Mutex::new(None);
+
+/// Replace the active sound pack; `None` restores the built-in sounds.
+pub fn set_sound_pack(pack: Option<SoundPack>) {
+    *recover_lock(&SOUND_PACK, "sound pack") = pack;
+}
+
+pub fn sound_pack_id() -> Option<String> {
+    recover_lock(&SOUND_PACK, "sound pack")
+        .as_ref()
+        .map(|pack| pack.id.clone())
+}
+
+/// The selected pack's sound for `kind`, else the built-in one.
+fn sound_for(kind: &str) -> Option<SoundData> {
+    let from_pack = recover_lock(&SOUND_PACK, "sound pack")
+        .as_ref()
+        .and_then(|pack| pack.sound(kind));
+    from_pack
+        .map(SoundData::Pack)
+        .or_else(|| embedded_sound(kind).map(SoundData::Embedded))
+}
+
+static LEVELS: Mutex<AudioLevels> = Mutex::new(AudioLevels::FULL);
+
+pub fn levels() -> AudioLevels {
+    *recover_lock(&LEVELS, "audio levels")
+}
+
+/// Apply new levels. Returns once they are queued for the worker.
+pub fn set_levels(levels: AudioLevels) {
+    *recover_lock(&LEVELS, "audio levels") = levels;
+    if worker_running() {
+        let _ = send_job(AudioJob::Levels(levels));
+    }
+}
+
+/// Stop queued and playing sounds, e.g. applause left over when a new session starts.
+pub fn cut() {
+    if worker_running() {
+        let _ = send_job(AudioJob::Cut);
+    }
+}
+
+/// Read a number aloud (dictation sessions). Returns once the phrase is queued.
+pub fn speak(phrase: SpokenPhrase) -> Result<(), FlashspanError> {
+    if !is_enabled() {
+        return Ok(());
+    }
+    send_job(AudioJob::Phrase(phrase))
 }
 
 #[tauri::command]
-pub fn play_sound_kind(kind: &str) -> Result<(), String> {
+pub fn play_sound_kind(kind: &str) -> Result<(), FlashspanError> {
     play_kind(kind)
 }
 
 /// Play a sound from Rust (same mapping as the Tauri command).
-pub fn play_kind(kind: &str) -> Result<(), String> {
+pub fn play_kind(kind: &str) -> Result<(), FlashspanError> {
     if !is_enabled() {
         info!("sound disabled; skipping play_kind({})", kind);
         return Ok(());
     }
 
-    let res = match kind {
-        "beep" => play_bytes(include_bytes!("../../src/assets/beep.wav")),
-        "applause" => play_bytes(include_bytes!("../../src/assets/applause.wav")),
-        "buzzer" => play_bytes(include_bytes!("../../src/assets/buzzer.wav")),
-        _ => Err("unknown sound kind".to_string()),
+    let res = match sound_for(kind) {
+        Some(data) => send_job(AudioJob::Sound(data, group_of(kind))),
+        None => Err(FlashspanError::UnknownSound {
+            kind: kind.to_string(),
+        }),
     };
 
     if let Err(ref e) = res {
@@ -83,8 +289,12 @@ pub fn play_kind(kind: &str) -> Result<(), String> {
 
 static SOUND_ENABLED: AtomicBool = AtomicBool::new(true);
 
+/// Muting also silences whatever is still playing.
 pub fn set_enabled(v: bool) {
     SOUND_ENABLED.store(v, Ordering::SeqCst);
+    if !v {
+        cut();
+    }
 }
 
 pub fn is_enabled() -> bool {
@@ -119,4 +329,48 @@ mod tests {
         let dec = Decoder::try_from(cur);
         assert!(dec.is_ok(), "buzzer.wav should decode as audio");
     }
+
+    #[test]
+    fn every_sound_kind_has_a_built_in_sound() {
+        for kind in super::SOUND_KINDS {
+            assert!(
+                super::embedded_sound(kind).is_some(),
+                "{} has no default",
+                kind
+            );
+        }
+        assert!(super::embedded_sound("fanfare").is_none());
+    }
+
+    #[test]
+    fn levels_are_validated_and_clamped() {
+        use super::{AudioLevels, MAX_LEVEL, SoundGroup, group_of};
+
+        let levels = AudioLevels {
+            master: 50,
+            beep: 20,
+            feedback: MAX_LEVEL,
+        };
+        assert!(levels.validate().is_ok());
+        assert_eq!(levels.master_volume(), 0.5);
+        assert_eq!(levels.gain(group_of("beep")), 0.2);
+        assert_eq!(levels.gain(group_of("buzzer")), 1.0);
+        assert_eq!(group_of("applause"), SoundGroup::Feedback);
+
+        let loud = AudioLevels {
+            beep: 150,
+            ..levels
+        };
+        assert_eq!(
+            loud.validate().unwrap_err().to_string(),
+            "beep must be between 0 and 100"
+        );
+        assert_eq!(
+            loud.clamped(),
+            AudioLevels {
+                beep: 100,
+                ..levels
+            }
+        );
+    }
 }
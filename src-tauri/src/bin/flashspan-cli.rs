@@ -0,0 +1,601 @@
+//! Terminal trainer: flashes a session in the terminal and checks the typed answer.
+//!
+//! Runs the same plan and executor as the desktop app, so it works over SSH and on machines
+//! without a webview. Flags mirror the `SessionConfigInput` fields:
+//!
+//! ```text
+//! flashspan-cli --digits-per-number 2 --total-numbers 10 --number-duration-s 0.6 --seed 42
+//! ```
+
+use app_lib::core::answer::{ValidationResult, parse_answer_list_text, parse_answer_text};
+use app_lib::core::decimal::{format_fixed, parse_fixed};
+use app_lib::core::diagnosis::diagnose;
+use app_lib::core::engine::build_session_plan;
+use app_lib::core::error::FlashspanError;
+use app_lib::core::scoring::ScoringConfig;
+use app_lib::core::types::{
+    CarryPolicy, Checkpoint, CheckpointSchedule, ClearScreen, DigitRangeInput, OperationMode,
+    SessionComplete, SessionConfigInput, SessionPaused, SessionResumed, ShowNumber, ShowPair,
+    TimingSchedule,
+};
+use app_lib::core::validate::{normalize_session_config, validate_config};
+use app_lib::session::{CheckpointGate, SessionEmitter, SessionState, run_session_plan};
+use std::collections::VecDeque;
+use std::io::{self, BufRead, Write};
+use std::process::ExitCode;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+const USAGE: &str = "\
+Usage: flashspan-cli [OPTIONS]
+
+Session options (same fields as the app's session settings):
+  --digits-per-number N           digits per flashed number (default 1)
+  --number-duration-s SECONDS     how long each number shows (default 0.5)
+  --delay-between-numbers-s SECONDS
+                                  blank gap between numbers (default 0)
+  --total-numbers N               numbers per session (default 5)
+  --allow-negative-numbers        mix in subtractions
+  --formula-mode MODE             any | direct | five_complement | ten_complement | mixed
+  --carry-policy POLICY           any | no_carries | at_most:N | in_column:N
+  --operation OP                  addition | multiplication | division
+  --second-operand-digits N       width of the multiplier or divisor
+  --decimal-places N              fixed decimal places (addition only)
+  --timing-schedule SCHEDULE      constant | linear_ramp:END_S | steps:EVERY:CHANGE_S
+                                  | per_index:S,S,...
+  --digit-range MIN-MAX           mixed term widths, e.g. 2-4
+  --digit-pattern W,W,...         explicit term widths, e.g. 3,3,2,4
+  --checkpoints SCHEDULE          ask for the subtotal: every:N | after:P,P,...
+
+Other options:
+  --seed N                        reproduce the same numbers
+  -h, --help                      show this help
+";
+
+#[derive(Debug)]
+struct CliArgs {
+    config: SessionConfigInput,
+    seed: Option<u64>,
+}
+
+enum Command {
+    Run(CliArgs),
+    Help,
+}
+
+fn default_config() -> SessionConfigInput {
+    // Matches the app's starting settings.
+    SessionConfigInput {
+        digits_per_number: 1,
+        number_duration_s: 0.5,
+        delay_between_numbers_s: 0.0,
+        total_numbers: 5,
+        ..Default::default()
+    }
+}
+
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Command, FlashspanError> {
+    let mut config = default_config();
+    let mut seed = None;
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        if arg == "-h" || arg == "--help" {
+            return Ok(Command::Help);
+        }
+        let Some(flag) = arg.strip_prefix("--") else {
+            return Err(FlashspanError::invalid_args(format!(
+                "unexpected argument `{}`",
+                arg
+            )));
+        };
+        if flag == "allow-negative-numbers" {
+            config.allow_negative_numbers = true;
+            continue;
+        }
+
+        // Accept both `--flag value` and `--flag=value`.
+        let (name, value) = match flag.split_once('=') {
+            Some((name, value)) => (name, value.to_string()),
+            None => {
+                let value = args.next().ok_or_else(|| {
+                    FlashspanError::invalid_args(format!("--{} needs a value", flag))
+                })?;
+                (flag, value)
+            }
+        };
+
+        match name {
+            "digits-per-number" => config.digits_per_number = number(name, &value)?,
+            "number-duration-s" => config.number_duration_s = number(name, &value)?,
+            "delay-between-numbers-s" => config.delay_between_numbers_s = number(name, &value)?,
+            "total-numbers" => config.total_numbers = number(name, &value)?,
+            "formula-mode" => config.formula_mode = keyword(name, &value)?,
+            "carry-policy" => config.carry_policy = carry_policy(&value)?,
+            "operation" => config.operation = keyword(name, &value)?,
+            "second-operand-digits" => config.second_operand_digits = number(name, &value)?,
+            "decimal-places" => config.decimal_places = number(name, &value)?,
+            "timing-schedule" => config.timing_schedule = timing_schedule(&value)?,
+            "digit-range" => config.digit_range = Some(digit_range(&value)?),
+            "digit-pattern" => config.digit_pattern = list(name, &value)?,
+            "checkpoints" => config.checkpoints = checkpoints(&value)?,
+            "seed" => seed = Some(number(name, &value)?),
+            _ => {
+                return Err(FlashspanError::invalid_args(format!(
+                    "unknown option --{}",
+                    name
+                )));
+            }
+        }
+    }
+
+    Ok(Command::Run(CliArgs { config, seed }))
+}
+
+fn bad_value(name: &str, value: &str) -> FlashspanError {
+    FlashspanError::invalid_args(format!("invalid value `{}` for --{}", value, name))
+}
+
+fn number<T: std::str::FromStr>(name: &str, value: &str) -> Result<T, FlashspanError> {
+    value.trim().parse().map_err(|_| bad_value(name, value))
+}
+
+fn list<T: std::str::FromStr>(name: &str, value: &str) -> Result<Vec<T>, FlashspanError> {
+    value.split(',').map(|part| number(name, part)).collect()
+}
+
+/// A snake_case enum value such as `five_complement`; dashes are accepted for underscores.
+fn keyword<T: serde::de::DeserializeOwned>(name: &str, value: &str) -> Result<T, FlashspanError> {
+    let value_json = serde_json::Value::String(value.trim().replace('-', "_"));
+    serde_json::from_value(value_json).map_err(|_| bad_value(name, value))
+}
+
+fn carry_policy(value: &str) -> Result<CarryPolicy, FlashspanError> {
+    let name = "carry-policy";
+    let (kind, arg) = value.split_once(':').unwrap_or((value, ""));
+    match (kind.replace('-', "_").as_str(), arg) {
+        ("any", "") => Ok(CarryPolicy::Any),
+        ("no_carries", "") => Ok(CarryPolicy::NoCarries),
+        ("at_most", n) => Ok(CarryPolicy::AtMost {
+            max: number(name, n)?,
+        }),
+        ("in_column", n) => Ok(CarryPolicy::InColumn {
+            column: number(name, n)?,
+        }),
+        _ => Err(bad_value(name, value)),
+    }
+}
+
+fn timing_schedule(value: &str) -> Result<TimingSchedule, FlashspanError> {
+    let name = "timing-schedule";
+    let mut parts = value.split(':');
+    let kind = parts.next().unwrap_or_default().replace('-', "_");
+    let args: Vec<&str> = parts.collect();
+    match (kind.as_str(), args.as_slice()) {
+        ("constant", []) => Ok(TimingSchedule::Constant),
+        ("linear_ramp", [end]) => Ok(TimingSchedule::LinearRamp {
+            end_duration_s: number(name, end)?,
+        }),
+        ("steps", [every, change]) => Ok(TimingSchedule::Steps {
+            every: number(name, every)?,
+            change_s: number(name, change)?,
+        }),
+        ("per_index", [durations]) => Ok(TimingSchedule::PerIndex {
+            durations_s: list(name, durations)?,
+        }),
+        _ => Err(bad_value(name, value)),
+    }
+}
+
+fn checkpoints(value: &str) -> Result<CheckpointSchedule, FlashspanError> {
+    let name = "checkpoints";
+    let (kind, arg) = value.split_once(':').unwrap_or((value, ""));
+    match (kind.replace('-', "_").as_str(), arg) {
+        ("off", "") => Ok(CheckpointSchedule::Off),
+        ("every", n) => Ok(CheckpointSchedule::Every {
+            every: number(name, n)?,
+        }),
+        ("after", positions) => Ok(CheckpointSchedule::After {
+            positions: list(name, positions)?,
+        }),
+        _ => Err(bad_value(name, value)),
+    }
+}
+
+fn digit_range(value: &str) -> Result<DigitRangeInput, FlashspanError> {
+    let name = "digit-range";
+    let (min, max) = value
+        .split_once('-')
+        .ok_or_else(|| bad_value(name, value))?;
+    Ok(DigitRangeInput {
+        min: number(name, min)?,
+        max: number(name, max)?,
+    })
+}
+
+/// Five-row block glyphs for the characters a flash can contain.
+fn glyph(c: char) -> [&'static str; 5] {
+    match c {
+        '0' => ["█████", "█   █", "█   █", "█   █", "█████"],
+        '1' => ["  █  ", " ██  ", "  █  ", "  █  ", " ███ "],
+        '2' => ["█████", "    █", "█████", "█    ", "█████"],
+        '3' => ["█████", "    █", " ████", "    █", "█████"],
+        '4' => ["█   █", "█   █", "█████", "    █", "    █"],
+        '5' => ["█████", "█    ", "█████", "    █", "█████"],
+        '6' => ["█████", "█    ", "█████", "█   █", "█████"],
+        '7' => ["█████", "    █", "   █ ", "  █  ", "  █  "],
+        '8' => ["█████", "█   █", "█████", "█   █", "█████"],
+        '9' => ["█████", "█   █", "█████", "    █", "█████"],
+        '-' => ["     ", "     ", "█████", "     ", "     "],
+        '.' => ["  ", "  ", "  ", "  ", "██"],
+        '×' => ["     ", "█   █", " ███ ", "█   █", "     "],
+        '÷' => ["  █  ", "     ", "█████", "     ", "  █  "],
+        _ => ["   ", "   ", "   ", "   ", "   "],
+    }
+}
+
+/// `text` drawn in block glyphs, one line per glyph row.
+fn big_text(text: &str) -> String {
+    let glyphs: Vec<[&str; 5]> = text.chars().map(glyph).collect();
+    (0..5)
+        .map(|row| {
+            let line: Vec<&str> = glyphs.iter().map(|g| g[row]).collect();
+            format!("    {}", line.join("  "))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Draws each step on a cleared terminal screen, and reads checkpoint answers.
+struct TerminalEmitter {
+    out: Mutex<io::Stdout>,
+    checkpoints: Arc<CheckpointGate>,
+    /// Set when input ends at a checkpoint.
+    stop: Arc<AtomicBool>,
+}
+
+impl TerminalEmitter {
+    fn draw(&self, text: &str) {
+        let mut out = self.out.lock().unwrap_or_else(|e| e.into_inner());
+        // Clear the screen and home the cursor.
+        let _ = write!(out, "\x1b[2J\x1b[H\n\n{}\n", text);
+        let _ = out.flush();
+    }
+}
+
+impl SessionEmitter for TerminalEmitter {
+    fn clear_screen(&self, _payload: ClearScreen) {
+        self.draw("");
+    }
+
+    fn countdown_tick(&self, value: String) {
+        self.draw(&big_text(&value));
+    }
+
+    fn show_number(&self, payload: ShowNumber) {
+        self.draw(&big_text(&payload.display));
+    }
+
+    fn show_pair(&self, payload: ShowPair) {
+        let sign = match payload.operation {
+            OperationMode::Division => '÷',
+            _ => '×',
+        };
+        self.draw(&big_text(&format!(
+            "{} {} {}",
+            payload.left, sign, payload.right
+        )));
+    }
+
+    fn session_complete(&self, _payload: SessionComplete) {
+        self.draw("");
+    }
+
+    fn session_paused(&self, _payload: SessionPaused) {}
+
+    fn session_resumed(&self, _payload: SessionResumed) {}
+
+    fn checkpoint(&self, payload: Checkpoint) {
+        self.draw("");
+        let prompt = format!(
+            "Subtotal after {} of {} numbers: ",
+            payload.index, payload.total
+        );
+        let answer = read_until_valid(&prompt, |text| {
+            let provided = if payload.decimal_places > 0 {
+                parse_fixed(text, payload.decimal_places)?
+            } else {
+                parse_answer_text(text)?
+            };
+            self.checkpoints.answer(payload.session_id, provided)
+        });
+        match answer {
+            Ok(Some(result)) => self.draw(&result.validation.message()),
+            _ => self.stop.store(true, Ordering::SeqCst),
+        }
+    }
+
+    fn beep(&self) {
+        let mut out = self.out.lock().unwrap_or_else(|e| e.into_inner());
+        let _ = write!(out, "\x07");
+        let _ = out.flush();
+    }
+}
+
+/// Check one line of typed answer against the finished session.
+fn validate_text(result: &SessionComplete, text: &str) -> Result<ValidationResult, FlashspanError> {
+    if !result.pairs.is_empty() {
+        let provided = parse_answer_list_text(text)?;
+        ValidationResult::for_pairs(&result.pairs, &provided)
+    } else {
+        let provided = if result.decimal_places > 0 {
+            parse_fixed(text, result.decimal_places)?
+        } else {
+            parse_answer_text(text)?
+        };
+        Ok(ValidationResult::for_sum(
+            result.sum,
+            provided,
+            result.decimal_places,
+        ))
+    }
+}
+
+/// Flash one session and return the result, or `None` if it did not finish.
+fn run(args: CliArgs) -> Result<Option<SessionComplete>, FlashspanError> {
+    let (config, effective) = normalize_session_config(args.config);
+    validate_config(&config)?;
+    let plan = build_session_plan(1, config, effective, args.seed);
+
+    let emitter = TerminalEmitter {
+        out: Mutex::new(io::stdout()),
+        checkpoints: Arc::new(CheckpointGate::default()),
+        stop: Arc::new(AtomicBool::new(false)),
+    };
+    let recent_results = Arc::new(Mutex::new(VecDeque::new()));
+    run_session_plan(
+        &emitter,
+        plan,
+        Arc::new(Mutex::new(SessionState::Idle)),
+        Arc::clone(&emitter.stop),
+        Arc::new(AtomicBool::new(false)),
+        Arc::clone(&recent_results),
+        Arc::new(Mutex::new(None)),
+        Arc::clone(&emitter.checkpoints),
+        || emitter.beep(),
+    );
+
+    let result = recent_results
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .pop_back();
+    Ok(result)
+}
+
+/// Prompt until the answer parses; `None` at end of input.
+fn read_answer(result: &SessionComplete) -> io::Result<Option<ValidationResult>> {
+    let prompt = if result.pairs.is_empty() {
+        "Answer: ".to_string()
+    } else {
+        format!(
+            "Answers ({} problems, separated by spaces): ",
+            result.pairs.len()
+        )
+    };
+
+    read_until_valid(&prompt, |text| validate_text(result, text))
+}
+
+/// Prompt until `parse` accepts the line; `None` at end of input.
+fn read_until_valid<T>(
+    prompt: &str,
+    mut parse: impl FnMut(&str) -> Result<T, FlashspanError>,
+) -> io::Result<Option<T>> {
+    let stdin = io::stdin();
+    let mut line = String::new();
+    loop {
+        print!("{}", prompt);
+        io::stdout().flush()?;
+        line.clear();
+        if stdin.lock().read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        match parse(&line) {
+            Ok(value) => return Ok(Some(value)),
+            Err(e) => println!("{}", e),
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(Command::Run(args)) => args,
+        Ok(Command::Help) => {
+            print!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    let result = match run(args) {
+        Ok(Some(result)) => result,
+        Ok(None) => return ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::from(2);
+        }
+    };
+
+    let asked_at = Instant::now();
+    match read_answer(&result) {
+        Ok(Some(mut validation)) => {
+            let answer_ms = asked_at.elapsed().as_millis() as u64;
+            validation.answer_ms = Some(answer_ms);
+            for checkpoint in &result.checkpoints {
+                let answered = &checkpoint.validation;
+                println!(
+                    "Checkpoint {}: {} for {} ({})",
+                    checkpoint.index,
+                    format_fixed(answered.provided_sum, result.decimal_places),
+                    format_fixed(answered.expected_sum, result.decimal_places),
+                    if answered.correct { "correct" } else { "wrong" }
+                );
+            }
+            println!("{}", validation.message());
+            if !validation.correct && result.pairs.is_empty() {
+                let diagnosis = diagnose(&result.numbers, result.sum, validation.provided_sum);
+                if let Some(top) = diagnosis.first() {
+                    println!("{}", top.describe(&result.numbers, result.decimal_places));
+                }
+            }
+            let score =
+                ScoringConfig::default().score(&result.config, &result.numbers, &validation);
+            println!(
+                "Score: {} points (answered in {:.1}s)",
+                score,
+                answer_ms as f64 / 1000.0
+            );
+            println!(
+                "Seed: {} (--seed {} replays this session)",
+                result.seed, result.seed
+            );
+            if validation.correct {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            }
+        }
+        Ok(None) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("failed to read answer: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use app_lib::core::types::FormulaMode;
+
+    fn parse(args: &[&str]) -> Result<CliArgs, FlashspanError> {
+        match parse_args(args.iter().map(|a| a.to_string()))? {
+            Command::Run(args) => Ok(args),
+            Command::Help => panic!("unexpected help"),
+        }
+    }
+
+    #[test]
+    fn flags_map_onto_config_fields() {
+        let args = parse(&[
+            "--digits-per-number",
+            "3",
+            "--total-numbers=12",
+            "--number-duration-s",
+            "0.7",
+            "--allow-negative-numbers",
+            "--formula-mode",
+            "five-complement",
+            "--carry-policy",
+            "at_most:1",
+            "--timing-schedule",
+            "steps:5:-0.1",
+            "--digit-range",
+            "2-4",
+            "--checkpoints",
+            "every:4",
+            "--seed",
+            "42",
+        ])
+        .unwrap();
+
+        assert_eq!(args.config.digits_per_number, 3);
+        assert_eq!(args.config.total_numbers, 12);
+        assert_eq!(args.config.number_duration_s, 0.7);
+        assert!(args.config.allow_negative_numbers);
+        assert_eq!(args.config.formula_mode, FormulaMode::FiveComplement);
+        assert_eq!(args.config.carry_policy, CarryPolicy::AtMost { max: 1 });
+        assert_eq!(
+            args.config.timing_schedule,
+            TimingSchedule::Steps {
+                every: 5,
+                change_s: -0.1
+            }
+        );
+        assert_eq!(
+            args.config.digit_range,
+            Some(DigitRangeInput { min: 2, max: 4 })
+        );
+        assert_eq!(
+            args.config.checkpoints,
+            CheckpointSchedule::Every { every: 4 }
+        );
+        assert_eq!(args.seed, Some(42));
+        // Unset fields keep the app's defaults.
+        assert_eq!(args.config.delay_between_numbers_s, 0.0);
+    }
+
+    #[test]
+    fn bad_flags_are_rejected() {
+        assert!(parse(&["--total-numbers"]).is_err());
+        assert!(parse(&["--total-numbers", "many"]).is_err());
+        assert!(parse(&["--operation", "exponent"]).is_err());
+        assert!(parse(&["--carry-policy", "at_most"]).is_err());
+        assert!(parse(&["--no-such-flag", "1"]).is_err());
+        assert!(parse(&["stray"]).is_err());
+        assert!(matches!(
+            parse_args(["--help".to_string()]),
+            Ok(Command::Help)
+        ));
+    }
+
+    #[test]
+    fn big_text_draws_every_glyph_row() {
+        let drawn = big_text("-12.5");
+        assert_eq!(drawn.lines().count(), 5);
+        assert!(
+            drawn
+                .lines()
+                .all(|l| l.chars().count() == drawn.lines().next().unwrap().chars().count())
+        );
+    }
+
+    #[test]
+    fn validate_text_checks_a_seeded_plan() {
+        let args = parse(&[
+            "--number-duration-s",
+            "0.1",
+            "--total-numbers",
+            "2",
+            "--seed",
+            "7",
+        ])
+        .unwrap();
+        let (config, effective) = normalize_session_config(args.config);
+        let plan = build_session_plan(1, config, effective, args.seed);
+        let result = SessionComplete {
+            session_id: 1,
+            numbers: plan.numbers_generated.clone(),
+            sum: plan.expected_sum,
+            decimal_places: 0,
+            pairs: Vec::new(),
+            started_at_ms: 0,
+            completed_at_ms: 0,
+            completed_at: None,
+            config: plan.config_snapshot.clone(),
+            profile_id: None,
+            seed: plan.seed,
+            checkpoints: Vec::new(),
+        };
+
+        let right = validate_text(&result, &plan.expected_sum.to_string()).unwrap();
+        assert!(right.correct);
+        let wrong = validate_text(&result, &(plan.expected_sum + 1).to_string()).unwrap();
+        assert_eq!(wrong.delta, 1);
+        assert!(validate_text(&result, "twelve").is_err());
+    }
+}
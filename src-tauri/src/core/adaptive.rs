@@ -0,0 +1,216 @@
+use crate::core::types::{
+    AdaptiveAdjustment, AdaptiveDirection, AdaptiveLevel, AdaptivePolicy, AdaptiveState,
+    SessionConfig,
+};
+use crate::core::validate::validate_config;
+
+impl AdaptiveLevel {
+    pub fn of(config: &SessionConfig) -> Self {
+        Self {
+            number_duration_ms: config.number_duration_ms,
+            digits_per_number: config.digits_per_number,
+            total_numbers: config.total_numbers,
+        }
+    }
+}
+
+impl AdaptivePolicy {
+    /// Count one validated answer and, once a streak reaches its threshold, move `config` one
+    /// step harder or easier.
+    pub fn apply(
+        &self,
+        state: &mut AdaptiveState,
+        config: &mut SessionConfig,
+        correct: bool,
+    ) -> AdaptiveAdjustment {
+        let previous = AdaptiveLevel::of(config);
+
+        let direction = if correct {
+            state.correct_streak += 1;
+            state.wrong_streak = 0;
+            if state.correct_streak >= self.harden_after_correct {
+                state.correct_streak = 0;
+                AdaptiveDirection::Harder
+            } else {
+                AdaptiveDirection::Hold
+            }
+        } else {
+            state.wrong_streak += 1;
+            state.correct_streak = 0;
+            if state.wrong_streak >= self.ease_after_wrong {
+                state.wrong_streak = 0;
+                AdaptiveDirection::Easier
+            } else {
+                AdaptiveDirection::Hold
+            }
+        };
+
+        if direction != AdaptiveDirection::Hold {
+            self.step(config, direction == AdaptiveDirection::Harder);
+        }
+
+        AdaptiveAdjustment {
+            correct,
+            direction,
+            state: *state,
+            previous,
+            next: AdaptiveLevel::of(config),
+        }
+    }
+
+    /// Move each setting one step. A change that would make the config invalid (e.g. digits
+    /// past a carry column) is skipped on its own.
+    fn step(&self, config: &mut SessionConfig, harder: bool) {
+        // Shorter flashes are harder.
+        let duration = toward(
+            config.number_duration_ms,
+            self.duration_step_ms,
+            self.min_duration_ms,
+            self.max_duration_ms,
+            !harder,
+        );
+        try_change(config, |c| c.number_duration_ms = duration);
+
+        // Digit ranges and patterns override `digits_per_number`; leave those widths alone.
+        if config.digit_range.is_none() && config.digit_pattern.is_empty() {
+            let digits = toward(
+                config.digits_per_number as u64,
+                self.digits_step as u64,
+                self.min_digits as u64,
+                self.max_digits as u64,
+                harder,
+            ) as u32;
+            try_change(config, |c| c.digits_per_number = digits);
+        }
+
+        let total = toward(
+            config.total_numbers as u64,
+            self.total_numbers_step as u64,
+            self.min_total_numbers as u64,
+            self.max_total_numbers as u64,
+            harder,
+        ) as u32;
+        try_change(config, |c| c.total_numbers = total);
+    }
+}
+
+/// Move `value` by `step` up or down, stopping at the bound it moves towards. A value already
+/// past that bound stays where it is.
+fn toward(value: u64, step: u64, min: u64, max: u64, up: bool) -> u64 {
+    if up {
+        if value >= max {
+            value
+        } else {
+            value.saturating_add(step).min(max)
+        }
+    } else if value <= min {
+        value
+    } else {
+        value.saturating_sub(step).max(min)
+    }
+}
+
+fn try_change(config: &mut SessionConfig, change: impl FnOnce(&mut SessionConfig)) {
+    let mut candidate = config.clone();
+    change(&mut candidate);
+    if validate_config(&candidate).is_ok() {
+        *config = candidate;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::types::{AdaptivePolicyInput, CarryPolicy};
+    use crate::core::validate::normalize_adaptive_policy;
+
+    fn config(duration_ms: u64, digits: u32, total: u32) -> SessionConfig {
+        SessionConfig {
+            digits_per_number: digits,
+            number_duration_ms: duration_ms,
+            delay_between_numbers_ms: 200,
+            total_numbers: total,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn default_staircase_speeds_up_after_two_correct_and_slows_after_one_wrong() {
+        let policy = normalize_adaptive_policy(&AdaptivePolicyInput::default());
+        let mut state = AdaptiveState::default();
+        let mut cfg = config(1000, 3, 10);
+
+        let first = policy.apply(&mut state, &mut cfg, true);
+        assert_eq!(first.direction, AdaptiveDirection::Hold);
+        assert_eq!(first.state.correct_streak, 1);
+        assert_eq!(cfg.number_duration_ms, 1000);
+
+        let second = policy.apply(&mut state, &mut cfg, true);
+        assert_eq!(second.direction, AdaptiveDirection::Harder);
+        assert_eq!(second.next.number_duration_ms, 900);
+        // Digits and totals have no step by default.
+        assert_eq!(second.next.digits_per_number, 3);
+        assert_eq!(second.next.total_numbers, 10);
+
+        let wrong = policy.apply(&mut state, &mut cfg, false);
+        assert_eq!(wrong.direction, AdaptiveDirection::Easier);
+        assert_eq!(wrong.previous.number_duration_ms, 900);
+        assert_eq!(cfg.number_duration_ms, 1000);
+    }
+
+    #[test]
+    fn steps_stop_at_bounds_and_move_every_dimension() {
+        let policy = normalize_adaptive_policy(&AdaptivePolicyInput {
+            harden_after_correct: 1,
+            duration_step_s: 0.5,
+            min_duration_s: 0.4,
+            digits_step: 1,
+            max_digits: 4,
+            total_numbers_step: 5,
+            max_total_numbers: 12,
+            ..Default::default()
+        });
+        let mut state = AdaptiveState::default();
+        let mut cfg = config(1000, 3, 5);
+
+        policy.apply(&mut state, &mut cfg, true);
+        assert_eq!(AdaptiveLevel::of(&cfg).number_duration_ms, 500);
+        assert_eq!((cfg.digits_per_number, cfg.total_numbers), (4, 10));
+
+        policy.apply(&mut state, &mut cfg, true);
+        assert_eq!(cfg.number_duration_ms, 400);
+        assert_eq!((cfg.digits_per_number, cfg.total_numbers), (4, 12));
+    }
+
+    #[test]
+    fn value_outside_bounds_is_not_pulled_the_wrong_way() {
+        let policy = normalize_adaptive_policy(&AdaptivePolicyInput {
+            max_duration_s: 2.0,
+            ..Default::default()
+        });
+        let mut state = AdaptiveState::default();
+        // Slower than the policy's slowest: easing must not speed it up.
+        let mut cfg = config(5000, 3, 10);
+        policy.apply(&mut state, &mut cfg, false);
+        assert_eq!(cfg.number_duration_ms, 5000);
+    }
+
+    #[test]
+    fn changes_that_break_the_config_are_skipped() {
+        let policy = normalize_adaptive_policy(&AdaptivePolicyInput {
+            ease_after_wrong: 1,
+            digits_step: 1,
+            ..Default::default()
+        });
+        let mut state = AdaptiveState::default();
+        let mut cfg = SessionConfig {
+            carry_policy: CarryPolicy::InColumn { column: 2 },
+            ..config(1000, 3, 10)
+        };
+
+        let adj = policy.apply(&mut state, &mut cfg, false);
+        // Dropping to 2 digits would leave carry column 2 outside the term.
+        assert_eq!(adj.next.digits_per_number, 3);
+        assert_eq!(adj.next.number_duration_ms, 1100);
+    }
+}
@@ -0,0 +1,482 @@
+//! Statistics over stored session results, for progress charts.
+//!
+//! The input is one [`SessionSample`] per session: history records on the desktop, or the
+//! results the web build kept (the JSON shape is the same). Everything in the report is ready
+//! to plot: series are sorted, rates are fractions from 0 to 1, deltas are in display units
+//! rather than fixed-point, and trend periods are contiguous, with `None` where a period had
+//! no answers.
+
+use super::dictation::DictationLanguage;
+use super::error::FlashspanError;
+use super::types::{
+    CarryPolicy, DigitRange, FormulaMode, OperationMode, SessionConfigEffective, TimingScheduleMs,
+};
+use super::validate::normalize_timing_schedule;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A flash speed counts as passed at this accuracy or better...
+pub const PASS_ACCURACY: f64 = 0.8;
+/// ...over at least this many answered sessions.
+pub const PASS_MIN_SESSIONS: u32 = 3;
+/// Upper edges of the answer-time histogram buckets; the last bucket is open-ended.
+pub const ANSWER_TIME_EDGES_MS: [u64; 9] = [
+    1_000, 2_000, 3_000, 5_000, 8_000, 13_000, 20_000, 30_000, 60_000,
+];
+/// Widest UTC offset in use (UTC+14 and UTC-12 both fit).
+pub const MAX_UTC_OFFSET_MINUTES: i32 = 14 * 60;
+/// Trends keep this many of the latest periods: a year of days, five years of weeks.
+pub const MAX_DAILY_POINTS: usize = 366;
+pub const MAX_WEEKLY_POINTS: usize = 261;
+
+const DAY_MS: i64 = 86_400_000;
+const WEEK_DAYS: i64 = 7;
+/// 1970-01-01 was a Thursday; weeks start on Monday.
+const EPOCH_WEEKDAY_FROM_MONDAY: i64 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SampleAnswer {
+    pub correct: bool,
+    pub delta: i64,
+    pub submitted_at_ms: u64,
+}
+
+/// The parts of a stored session the statistics need.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionSample {
+    pub completed_at_ms: u64,
+    pub config: SessionConfigEffective,
+    /// `None` until an answer is submitted.
+    #[serde(default)]
+    pub answer: Option<SampleAnswer>,
+}
+
+/// Sessions are grouped by everything that makes them harder except flash speed, which is
+/// what [`ConfigStats::by_duration`] breaks down. The timing schedule is kept as its shape,
+/// e.g. a ramp's end duration, so sessions at different base speeds still share a key.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct ConfigKey {
+    pub operation: OperationMode,
+    pub digits_per_number: u32,
+    pub total_numbers: u32,
+    pub decimal_places: u32,
+    pub allow_negative_numbers: bool,
+    pub formula_mode: FormulaMode,
+    pub carry_policy: CarryPolicy,
+    pub digit_range: Option<DigitRange>,
+    pub digit_pattern: Vec<u32>,
+    pub timing_schedule: TimingScheduleMs,
+    pub dictation: Option<DictationLanguage>,
+}
+
+impl ConfigKey {
+    fn of(config: &SessionConfigEffective) -> Self {
+        Self {
+            operation: config.operation,
+            digits_per_number: config.digits_per_number,
+            total_numbers: config.total_numbers,
+            decimal_places: config.decimal_places,
+            allow_negative_numbers: config.allow_negative_numbers,
+            formula_mode: config.formula_mode,
+            carry_policy: config.carry_policy,
+            digit_range: config.digit_range,
+            digit_pattern: config.digit_pattern.clone(),
+            timing_schedule: normalize_timing_schedule(&config.timing_schedule).0,
+            dictation: config.dictation,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SessionStats {
+    pub completed_at_ms: u64,
+    pub config: ConfigKey,
+    pub number_duration_ms: u64,
+    pub correct: Option<bool>,
+    pub abs_delta: Option<f64>,
+    /// From the end of the session to the answer.
+    pub answer_ms: Option<u64>,
+}
+
+impl SessionStats {
+    fn of(sample: &SessionSample) -> Self {
+        let scale = 10f64.powi(sample.config.decimal_places as i32);
+        Self {
+            completed_at_ms: sample.completed_at_ms,
+            config: ConfigKey::of(&sample.config),
+            number_duration_ms: (sample.config.number_duration_s * 1000.0).round() as u64,
+            correct: sample.answer.map(|a| a.correct),
+            abs_delta: sample.answer.map(|a| a.delta.unsigned_abs() as f64 / scale),
+            answer_ms: sample
+                .answer
+                .map(|a| a.submitted_at_ms.saturating_sub(sample.completed_at_ms)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct TimeBucket {
+    pub from_ms: u64,
+    /// Exclusive; `None` for the last, open-ended bucket.
+    pub to_ms: Option<u64>,
+    pub count: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TimeDistribution {
+    /// Every bucket, including empty ones, so charts keep a fixed axis.
+    pub buckets: Vec<TimeBucket>,
+    pub median_ms: Option<u64>,
+    pub p90_ms: Option<u64>,
+}
+
+impl TimeDistribution {
+    fn of(times: &[u64]) -> Self {
+        let mut buckets: Vec<TimeBucket> = std::iter::once(0)
+            .chain(ANSWER_TIME_EDGES_MS)
+            .zip(ANSWER_TIME_EDGES_MS.map(Some).into_iter().chain([None]))
+            .map(|(from_ms, to_ms)| TimeBucket {
+                from_ms,
+                to_ms,
+                count: 0,
+            })
+            .collect();
+        for &t in times {
+            let index = ANSWER_TIME_EDGES_MS.partition_point(|&edge| edge <= t);
+            buckets[index].count += 1;
+        }
+
+        let mut sorted = times.to_vec();
+        sorted.sort_unstable();
+        Self {
+            buckets,
+            median_ms: percentile(&sorted, 0.5),
+            p90_ms: percentile(&sorted, 0.9),
+        }
+    }
+}
+
+/// Nearest-rank percentile of sorted values.
+fn percentile(sorted: &[u64], p: f64) -> Option<u64> {
+    let rank = (p * sorted.len() as f64).ceil() as usize;
+    sorted.get(rank.max(1) - 1).copied()
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DurationStats {
+    pub number_duration_ms: u64,
+    pub answered: u32,
+    pub accuracy: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ConfigStats {
+    pub config: ConfigKey,
+    pub sessions: u32,
+    pub answered: u32,
+    pub accuracy: Option<f64>,
+    pub mean_abs_delta: Option<f64>,
+    pub answer_time: TimeDistribution,
+    /// Fastest flash first.
+    pub by_duration: Vec<DurationStats>,
+    /// Shortest flash with [`PASS_ACCURACY`] over at least [`PASS_MIN_SESSIONS`] answers.
+    pub fastest_passed_duration_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TrendPoint {
+    /// Local midnight starting the day or week (Monday), as UTC milliseconds.
+    pub start_ms: i64,
+    pub sessions: u32,
+    pub answered: u32,
+    pub accuracy: Option<f64>,
+    pub mean_abs_delta: Option<f64>,
+    pub median_answer_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AnalyticsReport {
+    /// Oldest first.
+    pub sessions: Vec<SessionStats>,
+    /// Most played first.
+    pub configs: Vec<ConfigStats>,
+    pub daily: Vec<TrendPoint>,
+    pub weekly: Vec<TrendPoint>,
+}
+
+#[derive(Default)]
+struct Tally {
+    sessions: u32,
+    answered: u32,
+    correct: u32,
+    abs_delta_sum: f64,
+    answer_times: Vec<u64>,
+}
+
+impl Tally {
+    fn add(&mut self, stats: &SessionStats) {
+        self.sessions += 1;
+        if let (Some(correct), Some(abs_delta), Some(answer_ms)) =
+            (stats.correct, stats.abs_delta, stats.answer_ms)
+        {
+            self.answered += 1;
+            self.correct += u32::from(correct);
+            self.abs_delta_sum += abs_delta;
+            self.answer_times.push(answer_ms);
+        }
+    }
+
+    fn accuracy(&self) -> Option<f64> {
+        (self.answered > 0).then(|| self.correct as f64 / self.answered as f64)
+    }
+
+    fn mean_abs_delta(&self) -> Option<f64> {
+        (self.answered > 0).then(|| self.abs_delta_sum / self.answered as f64)
+    }
+}
+
+fn config_stats(config: ConfigKey, sessions: &[&SessionStats]) -> ConfigStats {
+    let mut total = Tally::default();
+    let mut by_duration: BTreeMap<u64, Tally> = BTreeMap::new();
+    for stats in sessions {
+        total.add(stats);
+        by_duration
+            .entry(stats.number_duration_ms)
+            .or_default()
+            .add(stats);
+    }
+
+    let by_duration: Vec<DurationStats> = by_duration
+        .into_iter()
+        .filter_map(|(number_duration_ms, tally)| {
+            Some(DurationStats {
+                number_duration_ms,
+                answered: tally.answered,
+                accuracy: tally.accuracy()?,
+            })
+        })
+        .collect();
+    let fastest_passed_duration_ms = by_duration
+        .iter()
+        .find(|d| d.answered >= PASS_MIN_SESSIONS && d.accuracy >= PASS_ACCURACY)
+        .map(|d| d.number_duration_ms);
+
+    ConfigStats {
+        config,
+        sessions: total.sessions,
+        answered: total.answered,
+        accuracy: total.accuracy(),
+        mean_abs_delta: total.mean_abs_delta(),
+        answer_time: TimeDistribution::of(&total.answer_times),
+        by_duration,
+        fastest_passed_duration_ms,
+    }
+}
+
+/// One point per period from the first session's to the last's, empty periods included, but
+/// no more than the latest `max_points`. `period_of` maps a local day number to the first
+/// day of its period.
+fn trend(
+    sessions: &[SessionStats],
+    offset_ms: i64,
+    period_days: i64,
+    max_points: usize,
+    period_of: impl Fn(i64) -> i64,
+) -> Vec<TrendPoint> {
+    let mut periods: BTreeMap<i64, Tally> = BTreeMap::new();
+    for stats in sessions {
+        let local_day = (stats.completed_at_ms as i64 + offset_ms).div_euclid(DAY_MS);
+        periods.entry(period_of(local_day)).or_default().add(stats);
+    }
+    let (Some(&first), Some(&last)) = (periods.keys().next(), periods.keys().next_back()) else {
+        return Vec::new();
+    };
+    let first = first.max(last - (max_points as i64 - 1) * period_days);
+
+    (first..=last)
+        .step_by(period_days as usize)
+        .map(|day| {
+            let tally = periods.remove(&day).unwrap_or_default();
+            let mut times = tally.answer_times.clone();
+            times.sort_unstable();
+            TrendPoint {
+                start_ms: day * DAY_MS - offset_ms,
+                sessions: tally.sessions,
+                answered: tally.answered,
+                accuracy: tally.accuracy(),
+                mean_abs_delta: tally.mean_abs_delta(),
+                median_answer_ms: percentile(&times, 0.5),
+            }
+        })
+        .collect()
+}
+
+/// Build every statistic from `samples`. Days and weeks follow the caller's local time,
+/// `utc_offset_minutes` ahead of UTC.
+pub fn analyze(
+    samples: &[SessionSample],
+    utc_offset_minutes: i32,
+) -> Result<AnalyticsReport, FlashspanError> {
+    if utc_offset_minutes.abs() > MAX_UTC_OFFSET_MINUTES {
+        return Err(FlashspanError::out_of_range(
+            "utc_offset_minutes",
+            -MAX_UTC_OFFSET_MINUTES,
+            MAX_UTC_OFFSET_MINUTES,
+        ));
+    }
+    let offset_ms = i64::from(utc_offset_minutes) * 60_000;
+
+    let mut sessions: Vec<SessionStats> = samples.iter().map(SessionStats::of).collect();
+    sessions.sort_by_key(|s| s.completed_at_ms);
+
+    let mut groups: BTreeMap<ConfigKey, Vec<&SessionStats>> = BTreeMap::new();
+    for stats in &sessions {
+        groups.entry(stats.config.clone()).or_default().push(stats);
+    }
+    let mut configs: Vec<ConfigStats> = groups
+        .into_iter()
+        .map(|(config, group)| config_stats(config, &group))
+        .collect();
+    // Stable, so equally played configurations keep the key order.
+    configs.sort_by_key(|c| std::cmp::Reverse(c.sessions));
+
+    let daily = trend(&sessions, offset_ms, 1, MAX_DAILY_POINTS, |day| day);
+    let weekly = trend(&sessions, offset_ms, WEEK_DAYS, MAX_WEEKLY_POINTS, |day| {
+        day - (day + EPOCH_WEEKDAY_FROM_MONDAY).rem_euclid(WEEK_DAYS)
+    });
+    Ok(AnalyticsReport {
+        sessions,
+        configs,
+        daily,
+        weekly,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::types::TimingSchedule;
+
+    // 2024-01-01 (a Monday) 00:00 UTC.
+    const MONDAY_MS: u64 = 1_704_067_200_000;
+
+    fn sample(day: u64, duration_ms: u64, answer: Option<(bool, i64, u64)>) -> SessionSample {
+        let completed_at_ms = MONDAY_MS + day * DAY_MS as u64 + 12 * 3_600_000;
+        SessionSample {
+            completed_at_ms,
+            config: SessionConfigEffective {
+                digits_per_number: 2,
+                number_duration_s: duration_ms as f64 / 1000.0,
+                total_numbers: 5,
+                ..Default::default()
+            },
+            answer: answer.map(|(correct, delta, answer_ms)| SampleAnswer {
+                correct,
+                delta,
+                submitted_at_ms: completed_at_ms + answer_ms,
+            }),
+        }
+    }
+
+    #[test]
+    fn config_stats_find_fastest_passed_duration() {
+        let mut samples = vec![
+            // 400 ms: 2 of 3 correct, below the pass mark.
+            sample(0, 400, Some((true, 0, 1_500))),
+            sample(0, 400, Some((false, 10, 2_500))),
+            sample(0, 400, Some((true, 0, 900))),
+            // 600 ms: 4 of 5 correct passes.
+            sample(1, 600, Some((true, 0, 1_200))),
+            sample(1, 600, Some((true, 0, 4_000))),
+            sample(1, 600, Some((true, 0, 61_000))),
+            sample(1, 600, Some((true, 0, 3_000))),
+            sample(1, 600, Some((false, -3, 2_000))),
+            sample(2, 800, None),
+        ];
+        samples.push(SessionSample {
+            config: SessionConfigEffective {
+                digits_per_number: 3,
+                ..samples[0].config.clone()
+            },
+            ..sample(2, 800, Some((true, 0, 500)))
+        });
+
+        let report = analyze(&samples, 0).unwrap();
+        assert_eq!(report.configs.len(), 2);
+        let stats = &report.configs[0];
+        assert_eq!((stats.sessions, stats.answered), (9, 8));
+        assert_eq!(stats.accuracy, Some(6.0 / 8.0));
+        assert_eq!(stats.mean_abs_delta, Some(13.0 / 8.0));
+        assert_eq!(stats.fastest_passed_duration_ms, Some(600));
+        let durations: Vec<u64> = stats
+            .by_duration
+            .iter()
+            .map(|d| d.number_duration_ms)
+            .collect();
+        assert_eq!(durations, [400, 600]);
+
+        let counts: Vec<u32> = stats.answer_time.buckets.iter().map(|b| b.count).collect();
+        assert_eq!(counts, [1, 2, 2, 2, 0, 0, 0, 0, 0, 1]);
+        assert_eq!(stats.answer_time.buckets[9].to_ms, None);
+        assert_eq!(stats.answer_time.median_ms, Some(2_000));
+        assert_eq!(stats.answer_time.p90_ms, Some(61_000));
+        assert_eq!(report.configs[1].fastest_passed_duration_ms, None);
+    }
+
+    #[test]
+    fn trends_are_contiguous_in_local_time() {
+        let samples = vec![
+            sample(0, 500, Some((true, 0, 1_000))),
+            sample(3, 500, Some((false, 2, 3_000))),
+            sample(8, 500, None),
+        ];
+
+        let report = analyze(&samples, 0).unwrap();
+        assert_eq!(report.daily.len(), 9);
+        assert_eq!(report.daily[0].start_ms, MONDAY_MS as i64);
+        assert_eq!(report.daily[1].sessions, 0);
+        assert_eq!(report.daily[1].accuracy, None);
+        assert_eq!(report.daily[3].accuracy, Some(0.0));
+        let weeks: Vec<(u32, Option<f64>)> = report
+            .weekly
+            .iter()
+            .map(|w| (w.sessions, w.accuracy))
+            .collect();
+        assert_eq!(weeks, [(2, Some(0.5)), (1, None)]);
+
+        // Noon UTC on Monday is already Tuesday at UTC+13, so the first week starts the
+        // local Monday before.
+        let east = analyze(&samples[..1], 13 * 60).unwrap();
+        assert_eq!(east.weekly[0].start_ms, MONDAY_MS as i64 - 13 * 3_600_000);
+        assert!(analyze(&samples, 15 * 60).is_err());
+    }
+
+    #[test]
+    fn configs_split_on_every_setting_but_speed() {
+        let plain = sample(0, 500, Some((true, 0, 1_000)));
+        let mut direct = sample(0, 700, Some((true, 0, 1_000)));
+        direct.config.formula_mode = FormulaMode::Direct;
+        let mut ramp = sample(0, 500, Some((true, 0, 1_000)));
+        ramp.config.timing_schedule = TimingSchedule::LinearRamp {
+            end_duration_s: 0.3,
+        };
+        let mut spoken = sample(0, 500, Some((true, 0, 1_000)));
+        spoken.config.dictation = Some(DictationLanguage::English);
+
+        let report = analyze(&[plain.clone(), direct, ramp, spoken, plain], 0).unwrap();
+        assert_eq!(report.configs.len(), 4);
+        assert_eq!(report.configs[0].sessions, 2);
+    }
+
+    #[test]
+    fn trends_keep_only_the_latest_periods() {
+        let samples = vec![
+            sample(0, 500, Some((true, 0, 1_000))),
+            sample(400, 500, Some((false, 1, 1_000))),
+        ];
+        let report = analyze(&samples, 0).unwrap();
+        assert_eq!(report.daily.len(), MAX_DAILY_POINTS);
+        assert_eq!(report.daily.last().unwrap().accuracy, Some(0.0));
+        assert_eq!(report.weekly.len(), 58);
+    }
+}
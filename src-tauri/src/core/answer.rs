@@ -0,0 +1,314 @@
+//! Parsing and checking submitted answers, shared by the desktop app and the terminal trainer.
+
+use super::decimal::format_fixed;
+use super::error::FlashspanError;
+use super::types::{OperandPair, OperationMode};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationResult {
+    pub expected_sum: i64,
+    pub provided_sum: i64,
+    pub correct: bool,
+    pub delta: i64,
+    /// Scale of the sums and delta in decimal sessions; 0 for integer sessions.
+    pub decimal_places: u32,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pairs: Vec<PairValidation>,
+    /// From the session's last step to the answer, when the app measured it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub answer_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PairValidation {
+    pub index: u32,
+    pub operation: OperationMode,
+    pub left: i64,
+    pub right: i64,
+    pub expected: i64,
+    pub provided: i64,
+    pub correct: bool,
+    pub delta: i64,
+}
+
+/// An answer as submitted: the total, or one answer per pair in multiplication and division
+/// sessions. In decimal sessions the total counts units of 10^-decimal_places.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Submission {
+    #[serde(default)]
+    pub provided_sum: Option<i64>,
+    #[serde(default)]
+    pub provided_answers: Option<Vec<i64>>,
+}
+
+impl Submission {
+    /// Check against a session's total, or against its pairs when it has any.
+    pub fn validate(
+        &self,
+        expected_sum: i64,
+        decimal_places: u32,
+        pairs: &[OperandPair],
+    ) -> Result<ValidationResult, FlashspanError> {
+        if pairs.is_empty() {
+            let provided_sum = self
+                .provided_sum
+                .ok_or_else(|| FlashspanError::invalid_args("missing field `provided_sum`"))?;
+            Ok(ValidationResult::for_sum(
+                expected_sum,
+                provided_sum,
+                decimal_places,
+            ))
+        } else {
+            let provided = self
+                .provided_answers
+                .as_deref()
+                .ok_or_else(|| FlashspanError::invalid_args("missing field `provided_answers`"))?;
+            ValidationResult::for_pairs(pairs, provided)
+        }
+    }
+}
+
+impl ValidationResult {
+    /// Check a running-sum answer; both sums are fixed-point with `decimal_places`.
+    pub fn for_sum(expected_sum: i64, provided_sum: i64, decimal_places: u32) -> Self {
+        let delta = provided_sum.saturating_sub(expected_sum);
+        Self {
+            expected_sum,
+            provided_sum,
+            correct: delta == 0,
+            delta,
+            decimal_places,
+            pairs: Vec::new(),
+            answer_ms: None,
+        }
+    }
+
+    /// Check one answer per multiplication/division pair. The sums total the pairs.
+    pub fn for_pairs(pairs: &[OperandPair], provided: &[i64]) -> Result<Self, FlashspanError> {
+        let pairs = validate_pairs(pairs, provided)?;
+        let expected_sum = pairs
+            .iter()
+            .fold(0i64, |acc, p| acc.saturating_add(p.expected));
+        let provided_sum = pairs
+            .iter()
+            .fold(0i64, |acc, p| acc.saturating_add(p.provided));
+        Ok(Self {
+            expected_sum,
+            provided_sum,
+            correct: pairs.iter().all(|p| p.correct),
+            delta: provided_sum.saturating_sub(expected_sum),
+            decimal_places: 0,
+            pairs,
+            answer_ms: None,
+        })
+    }
+
+    /// Human-readable summary, one fact per line.
+    pub fn message(&self) -> String {
+        let mut lines: Vec<String> = Vec::new();
+        if self.correct {
+            lines.push("Correct ✅".to_string());
+        } else {
+            lines.push("Incorrect".to_string());
+        }
+        if self.pairs.is_empty() {
+            let places = self.decimal_places;
+            lines.push(format!(
+                "Expected answer: {}",
+                format_fixed(self.expected_sum, places)
+            ));
+            if !self.correct {
+                let d = self.delta;
+                lines.push(format!(
+                    "Difference: {}{}",
+                    if d > 0 { "+" } else { "" },
+                    format_fixed(d, places)
+                ));
+            }
+        } else {
+            let right = self.pairs.iter().filter(|p| p.correct).count();
+            lines.push(format!("Score: {}/{}", right, self.pairs.len()));
+            for p in self.pairs.iter().filter(|p| !p.correct) {
+                let sign = match p.operation {
+                    OperationMode::Division => "÷",
+                    _ => "×",
+                };
+                lines.push(format!(
+                    "#{}: {} {} {} = {} (you answered {})",
+                    p.index, p.left, sign, p.right, p.expected, p.provided
+                ));
+            }
+        }
+        lines.join("\n")
+    }
+}
+
+pub fn parse_answer_text(input: &str) -> Result<i64, FlashspanError> {
+    let error = || FlashspanError::InvalidAnswer {
+        hint: "Enter a single integer answer (e.g. 42 or -17).".to_string(),
+    };
+    let cleaned = input.trim().replace(',', "");
+    if cleaned.is_empty() {
+        return Err(error());
+    }
+
+    // Defensive bound: avoid absurd payload sizes.
+    if cleaned.len() > 64 {
+        return Err(error());
+    }
+
+    cleaned.parse::<i64>().map_err(|_| error())
+}
+
+/// Parse one answer per multiplication/division pair, separated by whitespace or `;`.
+/// Commas are kept as digit grouping, as in `parse_answer_text`.
+pub fn parse_answer_list_text(input: &str) -> Result<Vec<i64>, FlashspanError> {
+    let error = || FlashspanError::InvalidAnswer {
+        hint: "Enter one integer answer per problem (e.g. 42 17 96).".to_string(),
+    };
+    let answers = input
+        .split(|c: char| c.is_whitespace() || c == ';')
+        .filter(|part| !part.is_empty())
+        .map(|part| parse_answer_text(part).map_err(|_| error()))
+        .collect::<Result<Vec<i64>, FlashspanError>>()?;
+    if answers.is_empty() {
+        return Err(error());
+    }
+    Ok(answers)
+}
+
+pub fn validate_pairs(
+    pairs: &[OperandPair],
+    provided: &[i64],
+) -> Result<Vec<PairValidation>, FlashspanError> {
+    if provided.len() != pairs.len() {
+        return Err(FlashspanError::AnswerCountMismatch {
+            expected: pairs.len(),
+            provided: provided.len(),
+        });
+    }
+
+    Ok(pairs
+        .iter()
+        .zip(provided)
+        .enumerate()
+        .map(|(i, (pair, &provided))| {
+            let delta = provided.saturating_sub(pair.answer);
+            PairValidation {
+                index: i as u32 + 1,
+                operation: pair.operation,
+                left: pair.left,
+                right: pair.right,
+                expected: pair.answer,
+                provided,
+                correct: delta == 0,
+                delta,
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_answer_text_accepts_commas_and_spaces() {
+        assert_eq!(parse_answer_text("  42 ").unwrap(), 42);
+        assert_eq!(parse_answer_text("1,234").unwrap(), 1234);
+        assert!(parse_answer_text("").is_err());
+        assert!(parse_answer_text("not a number").is_err());
+    }
+
+    #[test]
+    fn parse_answer_text_bounds_check() {
+        // Very long numeric string is rejected by our defensive bound
+        let long = "1".repeat(100);
+        assert!(parse_answer_text(&long).is_err());
+    }
+
+    #[test]
+    fn parse_answer_text_negatives_and_extremes() {
+        // Negative numbers
+        assert_eq!(parse_answer_text("-42").unwrap(), -42);
+        assert_eq!(parse_answer_text("  -17  ").unwrap(), -17);
+        assert_eq!(parse_answer_text("-0").unwrap(), 0);
+
+        // Boundary values
+        assert_eq!(parse_answer_text("9223372036854775807").unwrap(), i64::MAX);
+        assert_eq!(parse_answer_text("-9223372036854775808").unwrap(), i64::MIN);
+
+        // Whitespace and commas
+        assert_eq!(parse_answer_text("  1,234,567  ").unwrap(), 1234567);
+        assert_eq!(parse_answer_text("-9,876").unwrap(), -9876);
+
+        // Rejects non-numeric and empty
+        assert!(parse_answer_text("abc").is_err());
+        assert!(parse_answer_text("12abc34").is_err());
+        assert!(parse_answer_text("   ").is_err());
+    }
+
+    #[test]
+    fn parse_answer_list_text_splits_on_spaces_and_semicolons() {
+        assert_eq!(
+            parse_answer_list_text(" 408; 1,234 -7\n12 ").unwrap(),
+            vec![408, 1234, -7, 12]
+        );
+        assert!(parse_answer_list_text(" ; ").is_err());
+        assert!(parse_answer_list_text("12 x").is_err());
+    }
+
+    #[test]
+    fn validate_pairs_checks_each_answer() {
+        let pairs = [
+            OperandPair {
+                operation: OperationMode::Multiplication,
+                left: 12,
+                right: 34,
+                answer: 408,
+            },
+            OperandPair {
+                operation: OperationMode::Multiplication,
+                left: 7,
+                right: 8,
+                answer: 56,
+            },
+        ];
+        let checked = validate_pairs(&pairs, &[408, 54]).unwrap();
+        assert!(checked[0].correct);
+        assert!(!checked[1].correct);
+        assert_eq!(checked[1].delta, -2);
+        assert_eq!(checked[1].index, 2);
+
+        assert_eq!(
+            validate_pairs(&pairs, &[408]).unwrap_err(),
+            FlashspanError::AnswerCountMismatch {
+                expected: 2,
+                provided: 1
+            }
+        );
+
+        let result = ValidationResult::for_pairs(&pairs, &[408, 54]).unwrap();
+        assert!(!result.correct);
+        assert_eq!((result.expected_sum, result.provided_sum), (464, 462));
+        assert_eq!(
+            result.message(),
+            "Incorrect\nScore: 1/2\n#2: 7 × 8 = 56 (you answered 54)"
+        );
+    }
+
+    #[test]
+    fn sum_message_formats_decimals() {
+        let result = ValidationResult::for_sum(1234, 1250, 2);
+        assert_eq!(result.delta, 16);
+        assert_eq!(
+            result.message(),
+            "Incorrect\nExpected answer: 12.34\nDifference: +0.16"
+        );
+        assert_eq!(
+            ValidationResult::for_sum(-5, -5, 0).message(),
+            "Correct ✅\nExpected answer: -5"
+        );
+    }
+}
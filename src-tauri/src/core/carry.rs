@@ -0,0 +1,266 @@
+use crate::core::formula::{COLUMNS, to_columns};
+use crate::core::generate::random_number_with_constraints;
+use crate::core::types::CarryPolicy;
+use rand::{Rng, RngExt};
+
+/// How many terms to construct while looking for one that satisfies a forcing policy.
+const POLICY_ATTEMPTS: u32 = 32;
+
+impl CarryPolicy {
+    /// Policies that cap carries can dead-end on a sum full of nines unless they may subtract.
+    pub fn requires_negative_numbers(self) -> bool {
+        matches!(self, CarryPolicy::NoCarries | CarryPolicy::AtMost { .. })
+    }
+
+    /// Whether a term whose carries/borrows happened in `columns` satisfies this policy.
+    pub fn accepts(self, columns: &[u32]) -> bool {
+        match self {
+            CarryPolicy::Any => true,
+            CarryPolicy::NoCarries => columns.is_empty(),
+            CarryPolicy::AtMost { max } => columns.len() as u32 <= max,
+            CarryPolicy::InColumn { column } => columns.contains(&column),
+        }
+    }
+
+    /// The carry state a single column must end up in, given the carries chosen so far.
+    /// `None` leaves the column free.
+    fn wanted(self, column: u32, carries_so_far: u32) -> Option<bool> {
+        match self {
+            CarryPolicy::Any => None,
+            CarryPolicy::NoCarries => Some(false),
+            CarryPolicy::AtMost { max } => (carries_so_far >= max).then_some(false),
+            CarryPolicy::InColumn { column: wanted } => (column == wanted).then_some(true),
+        }
+    }
+}
+
+/// Whether one written-arithmetic column step carries (addition) or borrows (subtraction),
+/// and the resulting column digit.
+fn column_step(bead: u8, digit: u8, carry_in: u8, negative: bool) -> (bool, u8) {
+    if negative {
+        let take = digit + carry_in;
+        if bead >= take {
+            (false, bead - take)
+        } else {
+            (true, bead + 10 - take)
+        }
+    } else {
+        let total = bead + digit + carry_in;
+        (total >= 10, total % 10)
+    }
+}
+
+/// Columns (0 = units) in which adding `term` to `running_sum` carries, or in which
+/// subtracting it borrows. Carries that ripple past the term's width are included.
+pub fn carry_columns(running_sum: i128, term: i128) -> Vec<u32> {
+    let columns = to_columns(running_sum);
+    let negative = term < 0;
+    let mut rest = term.unsigned_abs();
+    let mut carry = 0u8;
+    let mut out = Vec::new();
+
+    for (index, &bead) in columns.iter().enumerate() {
+        if rest == 0 && carry == 0 {
+            break;
+        }
+        let digit = (rest % 10) as u8;
+        rest /= 10;
+
+        let (carried, _) = column_step(bead, digit, carry, negative);
+        if carried {
+            out.push(index as u32);
+        }
+        carry = carried as u8;
+    }
+
+    out
+}
+
+/// Build one term from the units column up, picking each digit so that the column's carry
+/// state matches what `policy` asks for. Returns the magnitude and the carry columns, or
+/// `None` when some column has no suitable digit or the result would be negative.
+fn build_term(
+    rng: &mut impl Rng,
+    digits: u32,
+    negative: bool,
+    running_sum: i128,
+    policy: CarryPolicy,
+) -> Option<(u64, Vec<u32>)> {
+    let columns = to_columns(running_sum);
+    let mut carry = 0u8;
+    let mut carried_in: Vec<u32> = Vec::new();
+    let mut magnitude: u64 = 0;
+    let mut place: u64 = 1;
+
+    for column in 0..digits {
+        let bead = columns[column as usize];
+        let lowest = if column + 1 == digits { 1 } else { 0 };
+        let wanted = policy.wanted(column, carried_in.len() as u32);
+
+        let candidates: Vec<(u8, bool)> = (lowest..=9u8)
+            .filter_map(|digit| {
+                let (carried, _) = column_step(bead, digit, carry, negative);
+                wanted
+                    .is_none_or(|w| w == carried)
+                    .then_some((digit, carried))
+            })
+            .collect();
+        if candidates.is_empty() {
+            return None;
+        }
+
+        let (digit, carried) = candidates[rng.random_range(0..candidates.len())];
+        if carried {
+            carried_in.push(column);
+        }
+        carry = carried as u8;
+        magnitude += digit as u64 * place;
+        place = place.saturating_mul(10);
+    }
+
+    // Let a final carry or borrow ripple through the columns above the term.
+    let mut column = digits as usize;
+    while carry == 1 {
+        if column >= COLUMNS {
+            // Borrowing past the top of the board means the sum went negative.
+            return None;
+        }
+        let (carried, _) = column_step(columns[column], 0, 1, negative);
+        if carried {
+            carried_in.push(column as u32);
+        }
+        carry = carried as u8;
+        column += 1;
+    }
+
+    if !policy.accepts(&carried_in) {
+        return None;
+    }
+    Some((magnitude, carried_in))
+}
+
+/// Generate a term whose carries/borrows satisfy `policy`, on top of the running-sum rules of
+/// [`random_number_with_constraints`] (first number non-negative, sum never below zero).
+///
+/// `NoCarries` and `AtMost` are hard limits. `InColumn` is honoured whenever the running sum
+/// makes a carry or borrow in that column possible (the first term, added to zero, never can).
+pub fn random_number_with_carry_policy(
+    rng: &mut impl Rng,
+    digits: u32,
+    allow_negative_numbers: bool,
+    index: u32,
+    running_sum: i128,
+    policy: CarryPolicy,
+) -> (String, i128) {
+    if policy == CarryPolicy::Any {
+        return random_number_with_constraints(
+            rng,
+            digits,
+            allow_negative_numbers,
+            index,
+            running_sum,
+        );
+    }
+
+    let allow_negative_here = allow_negative_numbers && index > 0 && running_sum > 0;
+    let mut policies = vec![policy];
+    match policy {
+        // Settle for an unconstrained term when the forced carry is impossible.
+        CarryPolicy::InColumn { .. } => policies.push(CarryPolicy::Any),
+        // An early carry can force a later one (e.g. a carry into a 9), so fall back to
+        // avoiding carries entirely, which always satisfies the cap.
+        CarryPolicy::AtMost { .. } => policies.push(CarryPolicy::NoCarries),
+        CarryPolicy::Any | CarryPolicy::NoCarries => {}
+    }
+
+    for candidate_policy in policies {
+        for _ in 0..POLICY_ATTEMPTS {
+            let prefer_negative = allow_negative_here && rng.random_bool(0.5);
+            for negative in [prefer_negative, !prefer_negative] {
+                if negative && !allow_negative_here {
+                    continue;
+                }
+                if let Some((magnitude, _)) =
+                    build_term(rng, digits, negative, running_sum, candidate_policy)
+                {
+                    let value = if negative {
+                        -(magnitude as i128)
+                    } else {
+                        magnitude as i128
+                    };
+                    return (value.to_string(), value);
+                }
+            }
+        }
+    }
+
+    // Only reachable when a capping policy runs without negatives, which `validate_config`
+    // rejects.
+    random_number_with_constraints(rng, digits, allow_negative_numbers, index, running_sum)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    #[test]
+    fn carry_columns_counts_additions() {
+        assert!(carry_columns(12, 34).is_empty());
+        assert_eq!(carry_columns(15, 7), vec![0]);
+        // 95 + 7 = 102: units carries, and the carry ripples through the tens.
+        assert_eq!(carry_columns(95, 7), vec![0, 1]);
+        assert_eq!(carry_columns(0, 999), Vec::<u32>::new());
+    }
+
+    #[test]
+    fn carry_columns_counts_borrows() {
+        assert!(carry_columns(58, -23).is_empty());
+        assert_eq!(carry_columns(52, -8), vec![0]);
+        // 100 - 1: borrow in the units ripples through the tens.
+        assert_eq!(carry_columns(100, -1), vec![0, 1]);
+    }
+
+    #[test]
+    fn no_carries_policy_subtracts_when_every_addition_would_carry() {
+        let mut rng = StdRng::seed_from_u64(11);
+        for _ in 0..50 {
+            let (_, value) =
+                random_number_with_carry_policy(&mut rng, 1, true, 1, 9, CarryPolicy::NoCarries);
+            assert!(value < 0, "expected a subtraction from 9, got {value}");
+            assert!(carry_columns(9, value).is_empty());
+        }
+    }
+
+    #[test]
+    fn in_column_policy_carries_in_the_requested_column() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let policy = CarryPolicy::InColumn { column: 1 };
+        let mut running_sum: i128 = 4_321;
+        for index in 1..100u32 {
+            let (_, value) =
+                random_number_with_carry_policy(&mut rng, 3, true, index, running_sum, policy);
+            assert!(
+                carry_columns(running_sum, value).contains(&1),
+                "{running_sum} + {value} should carry or borrow in the tens"
+            );
+            running_sum += value;
+            assert!(running_sum >= 0);
+        }
+    }
+
+    #[test]
+    fn at_most_policy_avoids_forced_second_carry() {
+        // Any carry out of the tens runs into the 9 in the hundreds and carries again.
+        let policy = CarryPolicy::AtMost { max: 1 };
+        for seed in 0..50 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let (_, value) = random_number_with_carry_policy(&mut rng, 6, true, 1, 29_970, policy);
+            assert!(
+                policy.accepts(&carry_columns(29_970, value)),
+                "29970 + {value}"
+            );
+        }
+    }
+}
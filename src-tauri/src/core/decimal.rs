@@ -0,0 +1,127 @@
+//! Fixed-point helpers for decimal sessions.
+//!
+//! Decimal sessions keep every value as an `i64` count of the smallest decimal unit
+//! (e.g. hundredths for two places), so sums and comparisons stay exact.
+
+use super::error::FlashspanError;
+
+/// Most decimal places a session may use.
+pub const MAX_DECIMAL_PLACES: u32 = 6;
+
+/// Format a fixed-point `value` with `places` decimal places, e.g. `(-391, 2)` → `"-3.91"`.
+pub fn format_fixed(value: i64, places: u32) -> String {
+    if places == 0 {
+        return value.to_string();
+    }
+
+    let scale = 10u64.pow(places);
+    let magnitude = value.unsigned_abs();
+    format!(
+        "{}{}.{:0width$}",
+        if value < 0 { "-" } else { "" },
+        magnitude / scale,
+        magnitude % scale,
+        width = places as usize
+    )
+}
+
+/// Parse a typed answer such as `"123.45"` or `"1,234.50"` into a fixed-point value with
+/// `places` decimal places. Commas are digit grouping. Fewer decimals than `places` are padded
+/// with zeros. Extra decimals are accepted only if they are zeros.
+pub fn parse_fixed(input: &str, places: u32) -> Result<i64, FlashspanError> {
+    let error = || FlashspanError::InvalidAnswer {
+        hint: if places == 0 {
+            "Enter a single integer answer (e.g. 42 or -17).".to_string()
+        } else {
+            format!(
+                "Enter a single number with up to {} decimal places (e.g. 12.5 or -3.25).",
+                places
+            )
+        },
+    };
+
+    let cleaned = input.trim().replace(',', "");
+    // Defensive bound: avoid absurd payload sizes.
+    if cleaned.is_empty() || cleaned.len() > 64 {
+        return Err(error());
+    }
+
+    let (negative, unsigned) = match cleaned.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, cleaned.strip_prefix('+').unwrap_or(&cleaned)),
+    };
+    let (whole, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+    if whole.is_empty() && fraction.is_empty() {
+        return Err(error());
+    }
+    if !whole.bytes().all(|b| b.is_ascii_digit()) || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(error());
+    }
+
+    let (kept, extra) = fraction.split_at(fraction.len().min(places as usize));
+    if extra.bytes().any(|b| b != b'0') {
+        return Err(error());
+    }
+
+    // Accumulate negatively so that i64::MIN stays representable.
+    let mut value: i64 = 0;
+    let digits = whole
+        .bytes()
+        .chain(kept.bytes())
+        .chain(std::iter::repeat_n(b'0', places as usize - kept.len()));
+    for b in digits {
+        value = value
+            .checked_mul(10)
+            .and_then(|v| v.checked_sub((b - b'0') as i64))
+            .ok_or_else(error)?;
+    }
+
+    if negative {
+        Ok(value)
+    } else {
+        value.checked_neg().ok_or_else(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_fixed_pads_fraction() {
+        assert_eq!(format_fixed(1237, 2), "12.37");
+        assert_eq!(format_fixed(-391, 2), "-3.91");
+        assert_eq!(format_fixed(5, 2), "0.05");
+        assert_eq!(format_fixed(-5, 3), "-0.005");
+        assert_eq!(format_fixed(42, 0), "42");
+    }
+
+    #[test]
+    fn parse_fixed_accepts_grouping_and_short_fractions() {
+        assert_eq!(parse_fixed("123.45", 2), Ok(12345));
+        assert_eq!(parse_fixed(" 1,234.50 ", 2), Ok(123450));
+        assert_eq!(parse_fixed("1,234.5", 2), Ok(123450));
+        assert_eq!(parse_fixed("-3.91", 2), Ok(-391));
+        assert_eq!(parse_fixed("-.5", 2), Ok(-50));
+        assert_eq!(parse_fixed("7", 2), Ok(700));
+        assert_eq!(parse_fixed("7.", 2), Ok(700));
+        assert_eq!(parse_fixed("2.500", 2), Ok(250));
+    }
+
+    #[test]
+    fn parse_fixed_rejects_bad_input() {
+        assert!(parse_fixed("", 2).is_err());
+        assert!(parse_fixed(".", 2).is_err());
+        assert!(parse_fixed("1.234", 2).is_err());
+        assert!(parse_fixed("1.2.3", 2).is_err());
+        assert!(parse_fixed("12a", 2).is_err());
+        assert!(parse_fixed("1.5", 0).is_err());
+        assert!(parse_fixed("99999999999999999.99", 2).is_err());
+    }
+
+    #[test]
+    fn parse_fixed_round_trips_extremes() {
+        assert_eq!(parse_fixed(&format_fixed(i64::MAX, 2), 2), Ok(i64::MAX));
+        assert_eq!(parse_fixed(&format_fixed(i64::MIN, 2), 2), Ok(i64::MIN));
+    }
+}
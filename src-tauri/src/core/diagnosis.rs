@@ -0,0 +1,288 @@
+//! Likely explanations for a wrong running-sum answer.
+//!
+//! Knowing the flashed numbers, some deltas point at a specific slip: leaving a number out,
+//! counting it twice, flipping its sign, a digit off in one column (usually a carry), or two
+//! digits swapped. [`diagnose`] lists every explanation that fits the delta exactly, most
+//! likely first. An empty list means nothing simple explains the answer.
+
+use super::decimal::format_fixed;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MistakeKind {
+    /// One number left out of the sum.
+    SkippedNumber,
+    /// One number added twice.
+    DoubledNumber,
+    /// One number added with the wrong sign, usually a negative one added.
+    SignFlip,
+    /// One column of the answer off by a digit, usually a dropped or extra carry.
+    ColumnSlip,
+    /// Two digits swapped, in the answer or in one number.
+    Transposition,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MistakeCandidate {
+    pub kind: MistakeKind,
+    /// Indices into the session's `numbers`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub terms: Vec<u32>,
+    /// Digit positions, counted from the smallest unit (0). In the number for a transposition
+    /// with a term, otherwise in the answer.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub digits: Vec<u32>,
+    /// Share of the candidates' combined weight, from 0 to 1.
+    pub likelihood: f64,
+}
+
+impl MistakeCandidate {
+    /// One-line explanation, e.g. `"You may have skipped number #3 (45)."`.
+    pub fn describe(&self, numbers: &[i64], decimal_places: u32) -> String {
+        let term = || {
+            self.terms
+                .first()
+                .and_then(|&i| {
+                    let value = numbers.get(i as usize)?;
+                    Some(format!(
+                        "number #{} ({})",
+                        i + 1,
+                        format_fixed(*value, decimal_places)
+                    ))
+                })
+                .unwrap_or_else(|| "a number".to_string())
+        };
+        let column = |position: u32| {
+            format!(
+                "the {}s column",
+                format_fixed(10i64.pow(position), decimal_places)
+            )
+        };
+
+        match self.kind {
+            MistakeKind::SkippedNumber => format!("You may have skipped {}.", term()),
+            MistakeKind::DoubledNumber => format!("You may have added {} twice.", term()),
+            MistakeKind::SignFlip => format!("You may have flipped the sign of {}.", term()),
+            MistakeKind::ColumnSlip => match self.digits.first() {
+                Some(&position) => format!("You may have slipped in {}.", column(position)),
+                None => "You may have slipped in one column.".to_string(),
+            },
+            MistakeKind::Transposition if self.terms.is_empty() => {
+                "You may have swapped two digits of the answer.".to_string()
+            }
+            MistakeKind::Transposition => format!("You may have swapped two digits of {}.", term()),
+        }
+    }
+}
+
+/// Explanations for answering `provided_sum` when `numbers` add up to `expected_sum`, most
+/// likely first. Values are fixed-point in decimal sessions, which changes nothing here.
+pub fn diagnose(numbers: &[i64], expected_sum: i64, provided_sum: i64) -> Vec<MistakeCandidate> {
+    let Some(delta) = provided_sum.checked_sub(expected_sum) else {
+        return Vec::new();
+    };
+    if delta == 0 {
+        return Vec::new();
+    }
+
+    // (weight, candidate); weights are relative and only matter for ranking.
+    let mut found: Vec<(u32, MistakeCandidate)> = Vec::new();
+    let mut push = |weight: u32, kind: MistakeKind, terms: Vec<u32>, digits: Vec<u32>| {
+        found.push((
+            weight,
+            MistakeCandidate {
+                kind,
+                terms,
+                digits,
+                likelihood: 0.0,
+            },
+        ));
+    };
+
+    for (i, &n) in numbers.iter().enumerate() {
+        let index = vec![i as u32];
+        if delta == -n {
+            push(4, MistakeKind::SkippedNumber, index.clone(), Vec::new());
+        }
+        if delta == n {
+            push(2, MistakeKind::DoubledNumber, index.clone(), Vec::new());
+        }
+        if n.checked_mul(-2) == Some(delta) {
+            // Adding a negative number as positive is the usual flip.
+            push(
+                if n < 0 { 3 } else { 1 },
+                MistakeKind::SignFlip,
+                index.clone(),
+                Vec::new(),
+            );
+        }
+        for (digits, swapped) in transpositions(n) {
+            if swapped.checked_sub(n) == Some(delta) {
+                push(
+                    1,
+                    MistakeKind::Transposition,
+                    index.clone(),
+                    digits.to_vec(),
+                );
+            }
+        }
+    }
+
+    if let Some((digit, position)) = single_column(delta) {
+        push(
+            if digit == 1 { 2 } else { 1 },
+            MistakeKind::ColumnSlip,
+            Vec::new(),
+            vec![position],
+        );
+    }
+
+    if (provided_sum < 0) == (expected_sum < 0) {
+        let swapped = transpositions(expected_sum)
+            .into_iter()
+            .find(|&(_, value)| value == provided_sum);
+        if let Some((digits, _)) = swapped {
+            let adjacent = digits[1] - digits[0] == 1;
+            push(
+                if adjacent { 2 } else { 1 },
+                MistakeKind::Transposition,
+                Vec::new(),
+                digits.to_vec(),
+            );
+        }
+    }
+
+    // Stable, so ties keep the order above: by number, then answer-level slips.
+    found.sort_by_key(|(weight, _)| std::cmp::Reverse(*weight));
+    let total: u32 = found.iter().map(|(weight, _)| weight).sum();
+    found
+        .into_iter()
+        .map(|(weight, mut candidate)| {
+            candidate.likelihood = weight as f64 / total as f64;
+            candidate
+        })
+        .collect()
+}
+
+/// Every value made by swapping two different digits of `n`, with the swapped positions
+/// (lower first). Swaps that would leave a leading zero are skipped.
+fn transpositions(n: i64) -> Vec<([u32; 2], i64)> {
+    let digits: Vec<u8> = n.unsigned_abs().to_string().into_bytes();
+    let len = digits.len();
+    let mut out = Vec::new();
+    for low in 0..len {
+        for high in low + 1..len {
+            // Positions count from the right; `digits` is left to right.
+            let (a, b) = (len - 1 - high, len - 1 - low);
+            if digits[a] == digits[b] || (a == 0 && digits[b] == b'0') {
+                continue;
+            }
+            let mut swapped = digits.clone();
+            swapped.swap(a, b);
+            let Some(magnitude) = std::str::from_utf8(&swapped)
+                .ok()
+                .and_then(|s| s.parse::<i64>().ok())
+            else {
+                continue;
+            };
+            out.push((
+                [low as u32, high as u32],
+                if n < 0 { -magnitude } else { magnitude },
+            ));
+        }
+    }
+    out
+}
+
+/// `delta` as `±digit × 10^position`, for a position past the units.
+fn single_column(delta: i64) -> Option<(u64, u32)> {
+    let mut magnitude = delta.unsigned_abs();
+    let mut position = 0;
+    while magnitude.is_multiple_of(10) {
+        magnitude /= 10;
+        position += 1;
+    }
+    (position > 0 && magnitude < 10).then_some((magnitude, position))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(candidates: &[MistakeCandidate]) -> Vec<(MistakeKind, Vec<u32>, Vec<u32>)> {
+        candidates
+            .iter()
+            .map(|c| (c.kind, c.terms.clone(), c.digits.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn ranks_term_slips_above_column_slips() {
+        let numbers = [45, -17, 30, 8];
+        let sum = 66;
+
+        // Skipping 30 also reads as a slip in the tens column.
+        let skipped = diagnose(&numbers, sum, sum - 30);
+        assert_eq!(
+            kinds(&skipped),
+            [
+                (MistakeKind::SkippedNumber, vec![2], vec![]),
+                (MistakeKind::ColumnSlip, vec![], vec![1]),
+            ]
+        );
+        assert_eq!(skipped[0].likelihood, 4.0 / 5.0);
+
+        let flipped = diagnose(&numbers, sum, sum + 34);
+        assert_eq!(kinds(&flipped), [(MistakeKind::SignFlip, vec![1], vec![])]);
+        assert_eq!(flipped[0].likelihood, 1.0);
+
+        let carry = diagnose(&numbers, sum, sum - 100);
+        assert_eq!(kinds(&carry), [(MistakeKind::ColumnSlip, vec![], vec![2])]);
+
+        assert!(diagnose(&numbers, sum, sum).is_empty());
+        assert!(diagnose(&numbers, sum, sum + 7).is_empty());
+    }
+
+    #[test]
+    fn finds_transposed_digits() {
+        // 54 read as 45.
+        let numbers = [54, 12];
+        let answer = diagnose(&numbers, 66, 57);
+        assert_eq!(
+            kinds(&answer),
+            [(MistakeKind::Transposition, vec![0], vec![0, 1])]
+        );
+
+        // Answer 1243 for 1423: hundreds and tens swapped.
+        let answer = diagnose(&[1400, 23], 1423, 1243);
+        assert_eq!(
+            kinds(&answer),
+            [(MistakeKind::Transposition, vec![], vec![1, 2])]
+        );
+        assert_eq!(
+            answer[0].describe(&[1400, 23], 0),
+            "You may have swapped two digits of the answer."
+        );
+    }
+
+    #[test]
+    fn describes_candidates_in_display_units() {
+        let numbers = [125, 250];
+        let skipped = diagnose(&numbers, 375, 125);
+        assert_eq!(
+            skipped[0].describe(&numbers, 2),
+            "You may have skipped number #2 (2.50)."
+        );
+        let carry = MistakeCandidate {
+            kind: MistakeKind::ColumnSlip,
+            terms: Vec::new(),
+            digits: vec![1],
+            likelihood: 1.0,
+        };
+        assert_eq!(
+            carry.describe(&numbers, 2),
+            "You may have slipped in the 0.10s column."
+        );
+    }
+}
@@ -0,0 +1,426 @@
+//! Spoken numbers for dictation (listening) sessions.
+//!
+//! A number is read as a sequence of short recorded clips ("three", "hundred", "minus", …)
+//! that are concatenated and then time-stretched so the phrase fits the flash duration.
+//! This module holds the reading rules and the stretch; loading and playing clips is
+//! platform-specific.
+
+use serde::{Deserialize, Serialize};
+
+/// Reading rules, and the clip set they use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DictationLanguage {
+    /// "minus one thousand two hundred thirty four point five".
+    English,
+    /// Soroban read-aloud style (読上算): 万-grouped numbers ending in "en", with "hiite"
+    /// before a subtraction and "kuwaete" when adding resumes.
+    Japanese,
+}
+
+impl DictationLanguage {
+    /// Directory name of this language's clips.
+    pub fn code(self) -> &'static str {
+        match self {
+            DictationLanguage::English => "en",
+            DictationLanguage::Japanese => "ja",
+        }
+    }
+
+    /// Every clip the reading rules can ask for; a voice is complete when it has all of them.
+    pub fn clip_names(self) -> &'static [&'static str] {
+        match self {
+            DictationLanguage::English => EN_CLIPS,
+            DictationLanguage::Japanese => JA_CLIPS,
+        }
+    }
+}
+
+/// One number to read aloud during a flash.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpokenPhrase {
+    pub language: DictationLanguage,
+    pub clips: Vec<&'static str>,
+    /// How long the number stays on screen; the phrase is fitted inside it.
+    pub duration_ms: u64,
+}
+
+impl SpokenPhrase {
+    /// `value` is fixed-point with `decimal_places`. `previous` is the number read before
+    /// this one in the same session, which some reading rules depend on.
+    pub fn new(
+        language: DictationLanguage,
+        value: i64,
+        decimal_places: u32,
+        previous: Option<i64>,
+        duration_ms: u64,
+    ) -> Self {
+        let clips = match language {
+            DictationLanguage::English => read_english(value, decimal_places),
+            DictationLanguage::Japanese => read_japanese(value, decimal_places, previous),
+        };
+        Self {
+            language,
+            clips,
+            duration_ms,
+        }
+    }
+
+    /// Length the concatenated clips should be stretched to: most of the flash, so the
+    /// phrase finishes before the next number appears.
+    pub fn target_ms(&self) -> u64 {
+        self.duration_ms * 9 / 10
+    }
+}
+
+const EN_CLIPS: &[&str] = &[
+    "zero",
+    "one",
+    "two",
+    "three",
+    "four",
+    "five",
+    "six",
+    "seven",
+    "eight",
+    "nine",
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+    "twenty",
+    "thirty",
+    "forty",
+    "fifty",
+    "sixty",
+    "seventy",
+    "eighty",
+    "ninety",
+    "hundred",
+    "thousand",
+    "million",
+    "billion",
+    "trillion",
+    "quadrillion",
+    "quintillion",
+    "minus",
+    "point",
+];
+
+const EN_SCALES: [&str; 7] = [
+    "",
+    "thousand",
+    "million",
+    "billion",
+    "trillion",
+    "quadrillion",
+    "quintillion",
+];
+
+fn split_fixed(value: i64, decimal_places: u32) -> (u64, Option<String>) {
+    let magnitude = value.unsigned_abs();
+    if decimal_places == 0 {
+        return (magnitude, None);
+    }
+    let scale = 10u64.pow(decimal_places);
+    let fraction = format!(
+        "{:0width$}",
+        magnitude % scale,
+        width = decimal_places as usize
+    );
+    (magnitude / scale, Some(fraction))
+}
+
+fn read_english(value: i64, decimal_places: u32) -> Vec<&'static str> {
+    let mut clips = Vec::new();
+    if value < 0 {
+        clips.push("minus");
+    }
+    let (whole, fraction) = split_fixed(value, decimal_places);
+
+    if whole == 0 {
+        clips.push(EN_CLIPS[0]);
+    }
+    let mut groups = Vec::new();
+    let mut rest = whole;
+    while rest > 0 {
+        groups.push(rest % 1000);
+        rest /= 1000;
+    }
+    for (scale, &group) in groups.iter().enumerate().rev() {
+        if group == 0 {
+            continue;
+        }
+        let (hundreds, below) = (group / 100, group % 100);
+        if hundreds > 0 {
+            clips.push(EN_CLIPS[hundreds as usize]);
+            clips.push("hundred");
+        }
+        if below >= 20 {
+            clips.push(EN_CLIPS[18 + (below / 10) as usize]);
+            if below % 10 > 0 {
+                clips.push(EN_CLIPS[(below % 10) as usize]);
+            }
+        } else if below > 0 {
+            clips.push(EN_CLIPS[below as usize]);
+        }
+        if scale > 0 {
+            clips.push(EN_SCALES[scale]);
+        }
+    }
+
+    if let Some(fraction) = fraction {
+        clips.push("point");
+        clips.extend(fraction.bytes().map(|d| EN_CLIPS[(d - b'0') as usize]));
+    }
+    clips
+}
+
+const JA_CLIPS: &[&str] = &[
+    "rei", "ichi", "ni", "san", "yon", "go", "roku", "nana", "hachi", "kyuu", "juu", "hyaku",
+    "sanbyaku", "roppyaku", "happyaku", "sen", "sanzen", "hassen", "man", "oku", "chou", "itchou",
+    "hatchou", "jutchou", "kei", "ikkei", "rokkei", "hakkei", "jukkei", "ten", "en", "hiite",
+    "kuwaete",
+];
+
+/// Units of each 4-digit group, from the lowest.
+const JA_UNITS: [&str; 5] = ["", "man", "oku", "chou", "kei"];
+
+/// Group `1..=9999` read before `unit` ("" for the lowest group).
+fn read_japanese_group(group: u64, unit: &'static str, clips: &mut Vec<&'static str>) {
+    let digit = |d: u64| JA_CLIPS[d as usize];
+    let (thousands, hundreds, tens, ones) =
+        (group / 1000, group / 100 % 10, group / 10 % 10, group % 10);
+
+    match thousands {
+        0 => {}
+        1 => clips.push("sen"),
+        3 => clips.push("sanzen"),
+        8 => clips.push("hassen"),
+        d => clips.extend([digit(d), "sen"]),
+    }
+    match hundreds {
+        0 => {}
+        1 => clips.push("hyaku"),
+        3 => clips.push("sanbyaku"),
+        6 => clips.push("roppyaku"),
+        8 => clips.push("happyaku"),
+        d => clips.extend([digit(d), "hyaku"]),
+    }
+    match tens {
+        0 => {}
+        1 => clips.push("juu"),
+        d => clips.extend([digit(d), "juu"]),
+    }
+    if ones > 0 {
+        clips.push(digit(ones));
+    }
+    if unit.is_empty() {
+        return;
+    }
+
+    // 兆 and 京 fuse with a preceding 1, 8 or 10 (一兆 "itchou", 十京 "jukkei", …).
+    let fused = match (unit, clips.last().copied()) {
+        ("chou", Some("ichi")) => Some("itchou"),
+        ("chou", Some("hachi")) => Some("hatchou"),
+        ("chou", Some("juu")) => Some("jutchou"),
+        ("kei", Some("ichi")) => Some("ikkei"),
+        ("kei", Some("roku")) => Some("rokkei"),
+        ("kei", Some("hachi")) => Some("hakkei"),
+        ("kei", Some("juu")) => Some("jukkei"),
+        _ => None,
+    };
+    match fused {
+        Some(clip) => *clips.last_mut().expect("fused with the last clip") = clip,
+        None => clips.push(unit),
+    }
+}
+
+fn read_japanese(value: i64, decimal_places: u32, previous: Option<i64>) -> Vec<&'static str> {
+    let mut clips = Vec::new();
+    if value < 0 {
+        clips.push("hiite");
+    } else if previous.is_some_and(|p| p < 0) {
+        clips.push("kuwaete");
+    }
+    let (whole, fraction) = split_fixed(value, decimal_places);
+
+    if whole == 0 {
+        clips.push("rei");
+    }
+    let mut groups = Vec::new();
+    let mut rest = whole;
+    while rest > 0 {
+        groups.push(rest % 10_000);
+        rest /= 10_000;
+    }
+    for (unit, &group) in groups.iter().enumerate().rev() {
+        if group > 0 {
+            read_japanese_group(group, JA_UNITS[unit], &mut clips);
+        }
+    }
+
+    match fraction {
+        Some(fraction) => {
+            clips.push("ten");
+            clips.extend(fraction.bytes().map(|d| JA_CLIPS[(d - b'0') as usize]));
+        }
+        None => clips.push("en"),
+    }
+    clips
+}
+
+/// Bounds on [`stretch_ratio`]: compressing speech further than 2.5× or slowing it by more
+/// than a quarter makes it hard to follow, so the phrase overruns or ends early instead.
+pub const MIN_STRETCH: f64 = 0.4;
+pub const MAX_STRETCH: f64 = 1.25;
+
+/// Output/input length ratio that fits `natural_ms` of speech into `target_ms`.
+pub fn stretch_ratio(natural_ms: u64, target_ms: u64) -> f64 {
+    if natural_ms == 0 || target_ms == 0 {
+        return 1.0;
+    }
+    (target_ms as f64 / natural_ms as f64).clamp(MIN_STRETCH, MAX_STRETCH)
+}
+
+/// Change the length of interleaved `samples` by `ratio` (output/input) without changing
+/// pitch, by overlap-adding Hann-windowed frames read at a different hop than they are written.
+pub fn time_stretch(samples: &[f32], channels: u16, sample_rate: u32, ratio: f64) -> Vec<f32> {
+    let channels = channels.max(1) as usize;
+    let frames_in = samples.len() / channels;
+    if frames_in == 0 || (ratio - 1.0).abs() < 1e-3 {
+        return samples.to_vec();
+    }
+
+    // ~40 ms windows at 50% overlap on the output side.
+    let window = ((sample_rate as usize) / 25).clamp(16, frames_in.max(16));
+    let synthesis_hop = window / 2;
+    let analysis_hop = synthesis_hop as f64 / ratio;
+    let frames_out = (frames_in as f64 * ratio).round() as usize;
+
+    let hann: Vec<f32> = (0..window)
+        .map(|i| {
+            // Offset by half a sample so no output frame gets zero total weight.
+            let phase = std::f32::consts::TAU * (i as f32 + 0.5) / window as f32;
+            0.5 - 0.5 * phase.cos()
+        })
+        .collect();
+
+    let mut out = vec![0.0f32; frames_out * channels];
+    let mut weight = vec![0.0f32; frames_out];
+    let mut k = 0usize;
+    loop {
+        let out_start = k * synthesis_hop;
+        if out_start >= frames_out {
+            break;
+        }
+        let in_start = ((k as f64 * analysis_hop) as usize).min(frames_in - 1);
+        for (i, &w) in hann.iter().enumerate() {
+            let o = out_start + i;
+            if o >= frames_out {
+                break;
+            }
+            let s = (in_start + i).min(frames_in - 1);
+            for c in 0..channels {
+                out[o * channels + c] += samples[s * channels + c] * w;
+            }
+            weight[o] += w;
+        }
+        k += 1;
+    }
+
+    for (frame, &w) in out.chunks_mut(channels).zip(&weight) {
+        if w > 0.0 {
+            frame.iter_mut().for_each(|s| *s /= w);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn en(value: i64, places: u32) -> String {
+        SpokenPhrase::new(DictationLanguage::English, value, places, None, 1000)
+            .clips
+            .join(" ")
+    }
+
+    fn ja(value: i64, places: u32, previous: Option<i64>) -> String {
+        SpokenPhrase::new(DictationLanguage::Japanese, value, places, previous, 1000)
+            .clips
+            .join(" ")
+    }
+
+    #[test]
+    fn english_reading_rules() {
+        assert_eq!(en(0, 0), "zero");
+        assert_eq!(en(15, 0), "fifteen");
+        assert_eq!(en(40, 0), "forty");
+        assert_eq!(en(-1234, 0), "minus one thousand two hundred thirty four");
+        assert_eq!(en(1_000_007, 0), "one million seven");
+        assert_eq!(en(1205, 2), "twelve point zero five");
+        assert_eq!(en(-5, 1), "minus zero point five");
+        assert!(en(i64::MIN, 0).starts_with("minus nine quintillion"));
+    }
+
+    #[test]
+    fn japanese_soroban_reading_rules() {
+        assert_eq!(ja(0, 0, None), "rei en");
+        assert_eq!(ja(111, 0, None), "hyaku juu ichi en");
+        assert_eq!(ja(3_865, 0, None), "sanzen happyaku roku juu go en");
+        assert_eq!(ja(610, 0, None), "roppyaku juu en");
+        assert_eq!(ja(12_000, 0, None), "ichi man ni sen en");
+        assert_eq!(ja(-2_004, 0, Some(5)), "hiite ni sen yon en");
+        assert_eq!(ja(70, 0, Some(-3)), "kuwaete nana juu en");
+        assert_eq!(ja(1_0000_0000_0000, 0, None), "itchou en");
+        assert_eq!(ja(10_0000_0000_0000_0000, 0, None), "jukkei en");
+        assert_eq!(ja(1205, 2, None), "juu ni ten rei go");
+    }
+
+    #[test]
+    fn every_reading_uses_known_clips() {
+        for language in [DictationLanguage::English, DictationLanguage::Japanese] {
+            for value in [-987_654_321, 0, 7, 80, 1_018, i64::MAX, i64::MIN + 1] {
+                for phrase in [
+                    SpokenPhrase::new(language, value, 0, Some(-1), 500),
+                    SpokenPhrase::new(language, value, 3, None, 500),
+                ] {
+                    for clip in &phrase.clips {
+                        assert!(language.clip_names().contains(clip), "{}", clip);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn stretch_fits_length_and_keeps_level() {
+        assert_eq!(stretch_ratio(2000, 1000), 0.5);
+        assert_eq!(stretch_ratio(500, 1000), MAX_STRETCH);
+        assert_eq!(stretch_ratio(10_000, 1000), MIN_STRETCH);
+
+        let rate = 8_000;
+        let tone: Vec<f32> = (0..rate * 2)
+            .map(|i| 0.5 * (i as f32 * 0.1).sin())
+            .collect();
+        let stereo: Vec<f32> = tone.iter().flat_map(|&s| [s, s]).collect();
+        for ratio in [0.5, 1.2] {
+            let out = time_stretch(&stereo, 2, rate as u32, ratio);
+            assert_eq!(out.len(), (tone.len() as f64 * ratio).round() as usize * 2);
+            let peak = out.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+            assert!(peak > 0.3 && peak <= 0.51, "peak {}", peak);
+        }
+
+        let silence = vec![0.25f32; 4000];
+        let out = time_stretch(&silence, 1, rate as u32, 0.5);
+        assert!(out.iter().all(|s| (s - 0.25).abs() < 1e-3));
+    }
+}
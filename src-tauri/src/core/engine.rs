@@ -1,26 +1,116 @@
-use crate::core::generate::random_number_with_constraints;
-use crate::core::types::{SessionConfig, SessionConfigEffective, SessionPlan, SessionStep};
-use rand::SeedableRng;
+use crate::core::carry::carry_columns;
+use crate::core::formula::term_formulas;
+use crate::core::generate::{random_operand_pair, random_term};
+use crate::core::types::{
+    CarryPolicy, CheckpointSchedule, FormulaMode, OperandPair, OperationMode, SeedStrategy,
+    SessionConfig, SessionConfigEffective, SessionPlan, SessionStep,
+};
 use rand::rngs::StdRng;
+use rand::{RngExt, SeedableRng};
+
+/// Largest seed handed out by [`random_seed`]: seeds stay exact as JavaScript numbers, so
+/// the UI can show one and send it back to rebuild the session.
+pub const MAX_SEED: u64 = (1 << 53) - 1;
+
+pub fn random_seed() -> u64 {
+    rand::rng().random_range(0..=MAX_SEED)
+}
+
+impl SeedStrategy {
+    /// Seed of the round after one that used `seed`.
+    pub fn next_seed(self, seed: u64) -> u64 {
+        match self {
+            SeedStrategy::Fixed => seed,
+            SeedStrategy::Incrementing => seed.wrapping_add(1) & MAX_SEED,
+            SeedStrategy::Random => random_seed(),
+        }
+    }
+}
+
+/// Time between a checkpoint answer and the next flash.
+pub const CHECKPOINT_RESUME_MS: u64 = 1000;
+
+impl CheckpointSchedule {
+    /// Whether the session stops for the subtotal after `flashed` of `total` numbers.
+    pub fn is_after(&self, flashed: u32, total: u32) -> bool {
+        if flashed == 0 || flashed >= total {
+            return false;
+        }
+        match self {
+            CheckpointSchedule::Off => false,
+            CheckpointSchedule::Every { every } => *every > 0 && flashed.is_multiple_of(*every),
+            CheckpointSchedule::After { positions } => positions.contains(&flashed),
+        }
+    }
+}
+
+/// Whether `term` may follow `running_sum` as number `index` under the config's sign,
+/// formula and carry rules. `InColumn` accepts any term, as its generator settles for one
+/// when the carry is impossible.
+fn term_fits(config: &SessionConfig, index: u32, running_sum: i128, term: i128) -> bool {
+    if term < 0 && !(config.allow_negative_numbers && index > 0 && running_sum + term >= 0) {
+        return false;
+    }
+    let formula_ok = config.formula_mode == FormulaMode::Any
+        || term_formulas(running_sum, term)
+            .is_some_and(|used| used.iter().all(|f| config.formula_mode.allows(*f)));
+    let carry_ok = match config.carry_policy {
+        CarryPolicy::Any | CarryPolicy::InColumn { .. } => true,
+        policy => policy.accepts(&carry_columns(running_sum, term)),
+    };
+    formula_ok && carry_ok
+}
+
+/// The first term after `duplicate` that differs from it in one digit or in sign, keeps its
+/// width and satisfies [`term_fits`]. `None` when every such term breaks a rule.
+fn next_valid_term(
+    config: &SessionConfig,
+    index: u32,
+    running_sum: i128,
+    duplicate: i128,
+) -> Option<i128> {
+    let magnitude = duplicate.unsigned_abs();
+    let width = magnitude.checked_ilog10().unwrap_or(0) + 1;
+    let negative = duplicate < 0;
+
+    for sign in [negative, !negative] {
+        for place in 0..width {
+            let unit = 10u128.pow(place);
+            let digit = magnitude / unit % 10;
+            for step in 0..10 {
+                let replaced = (digit + step) % 10;
+                if replaced == 0 && place + 1 == width {
+                    // No leading zero.
+                    continue;
+                }
+                let candidate = (magnitude - digit * unit + replaced * unit) as i128;
+                let candidate = if sign { -candidate } else { candidate };
+                if candidate != duplicate && term_fits(config, index, running_sum, candidate) {
+                    return Some(candidate);
+                }
+            }
+        }
+    }
+    None
+}
 
 /// Build a deterministic session plan from configuration and an optional seed.
 ///
 /// A session plan is an immutable snapshot of all progression steps: countdown (if enabled),
-/// numbered flashes, clears, and completion. Each step includes the relative delay (ms) before
+/// numbered flashes, clears, checkpoints, and completion. Each step includes the relative delay (ms) before
 /// the next step. The plan contains the full sequence of numbers and their arrangement.
 ///
 /// Given the same config and seed, this function always produces identical results,
 /// enabling replay, serialization, and testing without timers or platform dependencies.
+/// Without a seed one is drawn with [`random_seed`] and recorded in the plan.
 pub fn build_session_plan(
     session_id: u64,
     config: SessionConfig,
     config_effective: SessionConfigEffective,
     seed_opt: Option<u64>,
 ) -> SessionPlan {
-    let mut rng: StdRng = match seed_opt {
-        Some(seed) => StdRng::seed_from_u64(seed),
-        None => StdRng::from_rng(&mut rand::rng()),
-    };
+    let seed = seed_opt.unwrap_or_else(random_seed);
+    let mut rng = StdRng::seed_from_u64(seed);
 
     let mut steps: Vec<SessionStep> = Vec::new();
     let mut accumulated_duration_ms: u64 = 0;
@@ -60,19 +150,64 @@ pub fn build_session_plan(
     let mut running_sum: i128 = 0;
     let mut numbers: Vec<i64> = Vec::with_capacity(config.total_numbers as usize);
     let mut sum_i128: i128 = 0;
+    let mut pairs: Vec<OperandPair> = Vec::new();
+    // The arithmetic duplicate fallback ignores bead and carry rules, so constrained
+    // generation first looks for a nearby term that keeps them and only settles for the
+    // arithmetic one when no such term exists.
+    let unconstrained =
+        config.formula_mode == FormulaMode::Any && config.carry_policy == CarryPolicy::Any;
 
     for i in 0..config.total_numbers {
-        // Generate a number with constraints
-        let (mut payload, mut payload_value) = {
+        let flash_ms =
+            config
+                .timing_schedule
+                .duration_ms(config.number_duration_ms, i, config.total_numbers);
+
+        if config.operation != OperationMode::Addition {
+            // Multiplication/division: every flash is an independent problem.
+            let mut pair = random_operand_pair(
+                &mut rng,
+                config.operation,
+                config.digits_per_number,
+                config.second_operand_digits,
+            );
             let mut attempt = 0u32;
-            loop {
-                let (candidate, candidate_value) = random_number_with_constraints(
+            while pairs.last() == Some(&pair) && attempt < 256 {
+                pair = random_operand_pair(
                     &mut rng,
+                    config.operation,
                     config.digits_per_number,
-                    config.allow_negative_numbers,
-                    i,
-                    running_sum,
+                    config.second_operand_digits,
                 );
+                attempt += 1;
+            }
+            pairs.push(pair);
+
+            steps.push(SessionStep::ShowPair {
+                session_id,
+                index: i + 1,
+                total: config.total_numbers,
+                operation: config.operation,
+                left: pair.left,
+                right: pair.right,
+                delay_ms_before_next: flash_ms,
+            });
+            accumulated_duration_ms += flash_ms;
+
+            steps.push(SessionStep::ClearScreen {
+                session_id,
+                index: Some(i + 1),
+                delay_ms_before_next: config.delay_between_numbers_ms,
+            });
+            accumulated_duration_ms += config.delay_between_numbers_ms;
+            continue;
+        }
+
+        // Generate a number with constraints
+        let (mut payload, mut payload_value) = {
+            let mut attempt = 0u32;
+            loop {
+                let (candidate, candidate_value) = random_term(&mut rng, &config, i, running_sum);
 
                 if last_payload.as_deref() != Some(candidate.as_str()) {
                     break (candidate, candidate_value);
@@ -85,9 +220,16 @@ pub fn build_session_plan(
             }
         };
 
-        // Deterministic fallback if consecutive duplicates detected
-        if last_payload.as_deref() == Some(payload.as_str()) {
-            let digits = config.digits_per_number;
+        // Deterministic fallbacks if consecutive duplicates detected
+        let constrained_fallback = (!unconstrained
+            && last_payload.as_deref() == Some(payload.as_str()))
+        .then(|| next_valid_term(&config, i, running_sum, payload_value))
+        .flatten();
+        if let Some(value) = constrained_fallback {
+            payload = value.to_string();
+            payload_value = value;
+        } else if last_payload.as_deref() == Some(payload.as_str()) {
+            let digits = payload.trim_start_matches('-').len() as u32;
             let fallback = if payload.starts_with('-') {
                 payload.trim_start_matches('-').to_string()
             } else {
@@ -117,6 +259,7 @@ pub fn build_session_plan(
         }
 
         last_payload = Some(payload.clone());
+        let carries = carry_columns(running_sum, payload_value).len() as u32;
         running_sum = (running_sum + payload_value).max(0);
         sum_i128 += payload_value;
 
@@ -134,9 +277,11 @@ pub fn build_session_plan(
             running_sum: running_sum
                 .try_into()
                 .expect("running_sum should fit into i64 with current constraints"),
-            delay_ms_before_next: config.number_duration_ms,
+            carries,
+            decimal_places: config.decimal_places,
+            delay_ms_before_next: flash_ms,
         });
-        accumulated_duration_ms += config.number_duration_ms;
+        accumulated_duration_ms += flash_ms;
 
         // Add clear_screen step
         steps.push(SessionStep::ClearScreen {
@@ -145,6 +290,21 @@ pub fn build_session_plan(
             delay_ms_before_next: config.delay_between_numbers_ms,
         });
         accumulated_duration_ms += config.delay_between_numbers_ms;
+
+        // The time spent answering is not planned; only the resume delay is.
+        if config.checkpoints.is_after(i + 1, config.total_numbers) {
+            steps.push(SessionStep::Checkpoint {
+                session_id,
+                index: i + 1,
+                total: config.total_numbers,
+                expected_sum: sum_i128
+                    .try_into()
+                    .expect("sum should fit into i64 with current constraints"),
+                decimal_places: config.decimal_places,
+                delay_ms_before_next: CHECKPOINT_RESUME_MS,
+            });
+            accumulated_duration_ms += CHECKPOINT_RESUME_MS;
+        }
     }
 
     // Phase 4: Global clear before complete
@@ -163,6 +323,8 @@ pub fn build_session_plan(
         session_id,
         numbers: numbers.clone(),
         sum: sum_i64,
+        decimal_places: config.decimal_places,
+        pairs: pairs.clone(),
     });
 
     SessionPlan {
@@ -172,13 +334,17 @@ pub fn build_session_plan(
         total_duration_ms: accumulated_duration_ms,
         numbers_generated: numbers,
         expected_sum: sum_i64,
+        pairs_generated: pairs,
+        seed,
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::core::types::{SessionConfig, SessionConfigEffective, SessionConfigInput};
+    use crate::core::types::{
+        SessionConfig, SessionConfigEffective, SessionConfigInput, TimingSchedule,
+    };
     use crate::core::validate::normalize_session_config;
 
     #[test]
@@ -189,6 +355,7 @@ mod tests {
             delay_between_numbers_s: 0.2,
             total_numbers: 5,
             allow_negative_numbers: false,
+            ..Default::default()
         };
 
         let (config, config_eff) = normalize_session_config(input);
@@ -208,6 +375,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn unseeded_plan_records_a_replayable_seed() {
+        let (config, config_eff) = normalize_session_config(SessionConfigInput {
+            digits_per_number: 3,
+            number_duration_s: 0.5,
+            delay_between_numbers_s: 0.2,
+            total_numbers: 8,
+            ..Default::default()
+        });
+
+        let plan = build_session_plan(1, config.clone(), config_eff.clone(), None);
+        assert!(plan.seed <= MAX_SEED);
+        let replay = build_session_plan(1, config, config_eff, Some(plan.seed));
+        assert_eq!(replay.numbers_generated, plan.numbers_generated);
+        assert_eq!(replay.seed, plan.seed);
+    }
+
+    #[test]
+    fn seed_strategies_pick_the_next_round() {
+        assert_eq!(SeedStrategy::Fixed.next_seed(41), 41);
+        assert_eq!(SeedStrategy::Incrementing.next_seed(41), 42);
+        assert_eq!(SeedStrategy::Incrementing.next_seed(MAX_SEED), 0);
+        assert!(SeedStrategy::Random.next_seed(41) <= MAX_SEED);
+    }
+
     #[test]
     fn session_plan_different_seeds_produce_different_numbers() {
         let input = SessionConfigInput {
@@ -216,6 +408,7 @@ mod tests {
             delay_between_numbers_s: 0.2,
             total_numbers: 10,
             allow_negative_numbers: false,
+            ..Default::default()
         };
 
         let (config, config_eff) = normalize_session_config(input);
@@ -238,6 +431,7 @@ mod tests {
             delay_between_numbers_s: 0.1,
             total_numbers: 20,
             allow_negative_numbers: true,
+            ..Default::default()
         };
 
         let total_numbers = input.total_numbers;
@@ -284,6 +478,7 @@ mod tests {
             delay_between_numbers_s: 0.1,
             total_numbers: 3,
             allow_negative_numbers: false,
+            ..Default::default()
         };
 
         let (config, config_eff) = normalize_session_config(input);
@@ -314,6 +509,7 @@ mod tests {
             delay_between_numbers_s: 0.1,
             total_numbers: 20,
             allow_negative_numbers: true,
+            ..Default::default()
         };
 
         let (config, config_eff) = normalize_session_config(input);
@@ -341,6 +537,7 @@ mod tests {
             delay_between_numbers_ms: 0,
             total_numbers: 0,
             allow_negative_numbers: false,
+            ..Default::default()
         };
         let config_eff = SessionConfigEffective {
             digits_per_number: 1,
@@ -348,6 +545,7 @@ mod tests {
             delay_between_numbers_s: 0.0,
             total_numbers: 0,
             allow_negative_numbers: false,
+            ..Default::default()
         };
         let plan = build_session_plan(1, config, config_eff, Some(123u64));
 
@@ -367,6 +565,7 @@ mod tests {
             delay_between_numbers_s: 0.1,
             total_numbers: 1,
             allow_negative_numbers: false,
+            ..Default::default()
         };
         let (config, config_eff) = normalize_session_config(input);
         let plan = build_session_plan(1, config, config_eff, Some(456u64));
@@ -393,6 +592,7 @@ mod tests {
             delay_between_numbers_s: 0.1,
             total_numbers: 3,
             allow_negative_numbers: false,
+            ..Default::default()
         };
         let (config, config_eff) = normalize_session_config(input);
         // number_duration_ms = 500, delay_between_numbers_ms = 100
@@ -412,6 +612,7 @@ mod tests {
             delay_between_numbers_s: 0.1,
             total_numbers: 2,
             allow_negative_numbers: false,
+            ..Default::default()
         };
         let (config, config_eff) = normalize_session_config(input);
         let plan = build_session_plan(1, config, config_eff, Some(321u64));
@@ -451,6 +652,7 @@ mod tests {
             delay_between_numbers_s: 0.0,
             total_numbers: 100,
             allow_negative_numbers: false,
+            ..Default::default()
         };
         let (config, config_eff) = normalize_session_config(input);
 
@@ -474,6 +676,7 @@ mod tests {
             delay_between_numbers_s: 0.1,
             total_numbers: 50,
             allow_negative_numbers: true,
+            ..Default::default()
         };
         let (config, config_eff) = normalize_session_config(input);
         let plan = build_session_plan(1, config, config_eff, Some(77777u64));
@@ -503,4 +706,269 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn multiplication_plan_shows_pairs_with_products() {
+        let input = SessionConfigInput {
+            digits_per_number: 3,
+            number_duration_s: 0.5,
+            delay_between_numbers_s: 0.1,
+            total_numbers: 20,
+            operation: OperationMode::Multiplication,
+            second_operand_digits: 2,
+            ..Default::default()
+        };
+        let (config, config_eff) = normalize_session_config(input);
+        let plan = build_session_plan(1, config, config_eff, Some(9u64));
+
+        assert!(plan.numbers_generated.is_empty());
+        assert_eq!(plan.pairs_generated.len(), 20);
+        for pair in &plan.pairs_generated {
+            assert!((100..1000).contains(&pair.left), "{:?}", pair);
+            assert!((10..100).contains(&pair.right), "{:?}", pair);
+            assert_eq!(pair.answer, pair.left * pair.right);
+        }
+
+        let shown: Vec<(i64, i64)> = plan
+            .steps
+            .iter()
+            .filter_map(|step| match step {
+                SessionStep::ShowPair { left, right, .. } => Some((*left, *right)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(shown.len(), 20);
+        assert!(shown.windows(2).all(|w| w[0] != w[1]));
+
+        let Some(SessionStep::Complete { pairs, .. }) = plan.steps.last() else {
+            panic!("last step should be Complete");
+        };
+        assert_eq!(pairs, &plan.pairs_generated);
+    }
+
+    #[test]
+    fn division_plan_divides_exactly() {
+        let input = SessionConfigInput {
+            digits_per_number: 4,
+            number_duration_s: 0.5,
+            delay_between_numbers_s: 0.1,
+            total_numbers: 50,
+            operation: OperationMode::Division,
+            second_operand_digits: 2,
+            ..Default::default()
+        };
+        let (config, config_eff) = normalize_session_config(input);
+        let plan = build_session_plan(1, config, config_eff, Some(31u64));
+
+        assert_eq!(plan.pairs_generated.len(), 50);
+        for pair in &plan.pairs_generated {
+            assert!((1000..10000).contains(&pair.left), "{:?}", pair);
+            assert!((10..100).contains(&pair.right), "{:?}", pair);
+            assert_eq!(pair.left % pair.right, 0);
+            assert_eq!(pair.answer, pair.left / pair.right);
+            assert!(pair.answer >= 2, "{:?}", pair);
+        }
+    }
+
+    #[test]
+    fn division_of_equal_widths_never_has_quotient_one() {
+        for digits in 1..=3 {
+            let input = SessionConfigInput {
+                digits_per_number: digits,
+                number_duration_s: 0.5,
+                total_numbers: 100,
+                operation: OperationMode::Division,
+                second_operand_digits: digits,
+                ..Default::default()
+            };
+            let (config, config_eff) = normalize_session_config(input);
+            let plan = build_session_plan(1, config, config_eff, Some(digits as u64));
+            for pair in &plan.pairs_generated {
+                assert!(pair.answer >= 2, "{:?}", pair);
+                assert_eq!(pair.left, pair.answer * pair.right);
+                assert_eq!(pair.right.to_string().len(), digits as usize);
+                assert_eq!(pair.left.to_string().len(), digits as usize);
+            }
+        }
+    }
+
+    #[test]
+    fn digit_pattern_sets_each_term_width_and_repeats() {
+        let input = SessionConfigInput {
+            digits_per_number: 1,
+            number_duration_s: 0.5,
+            delay_between_numbers_s: 0.0,
+            total_numbers: 10,
+            allow_negative_numbers: true,
+            digit_pattern: vec![3, 3, 2, 4],
+            ..Default::default()
+        };
+        let (config, config_eff) = normalize_session_config(input);
+        let plan = build_session_plan(1, config, config_eff, Some(5u64));
+
+        let widths: Vec<usize> = plan
+            .numbers_generated
+            .iter()
+            .map(|n| n.unsigned_abs().to_string().len())
+            .collect();
+        assert_eq!(widths, vec![3, 3, 2, 4, 3, 3, 2, 4, 3, 3]);
+    }
+
+    #[test]
+    fn decimal_plan_uses_fixed_point_terms() {
+        let input = SessionConfigInput {
+            digits_per_number: 2,
+            number_duration_s: 0.5,
+            delay_between_numbers_s: 0.0,
+            total_numbers: 20,
+            allow_negative_numbers: true,
+            decimal_places: 2,
+            ..Default::default()
+        };
+        let (config, config_eff) = normalize_session_config(input);
+        assert_eq!(config_eff.decimal_places, 2);
+        let plan = build_session_plan(1, config, config_eff, Some(17u64));
+
+        // Two integer digits plus two places: 10.00..=99.99 in hundredths.
+        for &n in &plan.numbers_generated {
+            assert!((1000..10000).contains(&n.unsigned_abs()), "{}", n);
+        }
+        assert_eq!(
+            plan.expected_sum,
+            plan.numbers_generated.iter().sum::<i64>()
+        );
+        let Some(SessionStep::Complete { decimal_places, .. }) = plan.steps.last() else {
+            panic!("last step should be Complete");
+        };
+        assert_eq!(*decimal_places, 2);
+    }
+
+    #[test]
+    fn linear_ramp_sets_flash_delays_and_total_duration() {
+        let input = SessionConfigInput {
+            digits_per_number: 1,
+            number_duration_s: 1.0,
+            delay_between_numbers_s: 0.2,
+            total_numbers: 8,
+            timing_schedule: TimingSchedule::LinearRamp {
+                end_duration_s: 0.3,
+            },
+            ..Default::default()
+        };
+        let (config, config_eff) = normalize_session_config(input);
+        let plan = build_session_plan(1, config, config_eff, Some(3u64));
+
+        let flashes: Vec<u64> = plan
+            .steps
+            .iter()
+            .filter_map(|step| match step {
+                SessionStep::ShowNumber {
+                    delay_ms_before_next,
+                    ..
+                } => Some(*delay_ms_before_next),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(flashes.first(), Some(&1000));
+        assert_eq!(flashes.last(), Some(&300));
+        assert!(flashes.windows(2).all(|w| w[0] >= w[1]));
+
+        // 3 countdown seconds + settle + every flash + every gap.
+        let expected = 3000 + 100 + flashes.iter().sum::<u64>() + 8 * 200;
+        assert_eq!(plan.total_duration_ms, expected);
+        let stepped: u64 = plan
+            .steps
+            .iter()
+            .map(|step| match step {
+                SessionStep::CountdownTick {
+                    delay_ms_before_next,
+                    ..
+                }
+                | SessionStep::ShowNumber {
+                    delay_ms_before_next,
+                    ..
+                }
+                | SessionStep::ShowPair {
+                    delay_ms_before_next,
+                    ..
+                }
+                | SessionStep::ClearScreen {
+                    delay_ms_before_next,
+                    ..
+                }
+                | SessionStep::Checkpoint {
+                    delay_ms_before_next,
+                    ..
+                } => *delay_ms_before_next,
+                SessionStep::Complete { .. } => 0,
+            })
+            .sum();
+        assert_eq!(plan.total_duration_ms, stepped);
+    }
+
+    #[test]
+    fn checkpoints_follow_the_schedule_with_subtotals() {
+        let input = SessionConfigInput {
+            digits_per_number: 2,
+            number_duration_s: 0.5,
+            delay_between_numbers_s: 0.0,
+            total_numbers: 9,
+            allow_negative_numbers: true,
+            checkpoints: CheckpointSchedule::Every { every: 3 },
+            ..Default::default()
+        };
+        let (config, config_eff) = normalize_session_config(input);
+        let plan = build_session_plan(1, config, config_eff, Some(5u64));
+
+        let checkpoints: Vec<(u32, i64)> = plan
+            .steps
+            .iter()
+            .filter_map(|step| match step {
+                SessionStep::Checkpoint {
+                    index,
+                    expected_sum,
+                    ..
+                } => Some((*index, *expected_sum)),
+                _ => None,
+            })
+            .collect();
+        let subtotal = |n: usize| plan.numbers_generated[..n].iter().sum::<i64>();
+        // None after the last number: that is the final answer.
+        assert_eq!(checkpoints, [(3, subtotal(3)), (6, subtotal(6))]);
+        assert_eq!(
+            plan.total_duration_ms,
+            3100 + 9 * 500 + 2 * CHECKPOINT_RESUME_MS
+        );
+
+        let after = CheckpointSchedule::After {
+            positions: vec![2, 9],
+        };
+        assert!(after.is_after(2, 9));
+        assert!(!after.is_after(9, 9));
+        assert!(!CheckpointSchedule::Every { every: 0 }.is_after(3, 9));
+    }
+
+    #[test]
+    fn constrained_duplicates_fall_back_to_a_valid_term() {
+        let direct = SessionConfig {
+            formula_mode: FormulaMode::Direct,
+            allow_negative_numbers: true,
+            ..Default::default()
+        };
+        // On a 4, only +5 is a direct addition, so the fallback has to subtract.
+        assert_eq!(next_valid_term(&direct, 1, 4, 5), Some(-1));
+        let additions_only = SessionConfig {
+            allow_negative_numbers: false,
+            ..direct
+        };
+        assert_eq!(next_valid_term(&additions_only, 1, 4, 5), None);
+
+        let no_carries = SessionConfig {
+            carry_policy: CarryPolicy::NoCarries,
+            allow_negative_numbers: true,
+            ..Default::default()
+        };
+        assert_eq!(next_valid_term(&no_carries, 1, 95, 3), Some(4));
+        assert_eq!(next_valid_term(&no_carries, 1, 91, 18), Some(-10));
+    }
 }
@@ -0,0 +1,301 @@
+//! Error type shared by core, the session runtime and the Tauri/wasm bridges.
+//!
+//! Errors serialise as `{ "code": "<Variant>", "message": "<English text>", ...fields }`, so
+//! the UI can branch on `code` and show `message` as-is.
+
+use serde::ser::{Serialize, SerializeMap, Serializer};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FlashspanError {
+    /// `start_session` while another session is still flashing.
+    SessionAlreadyRunning,
+    /// Pause or resume with no session running.
+    NoSessionRunning,
+    /// The session finished too long ago, was stopped, or never existed.
+    ResultNotFound {
+        session_id: u64,
+    },
+    TimingReportNotFound {
+        session_id: u64,
+    },
+    /// A checkpoint answer when the session is not waiting for one.
+    NoCheckpointPending {
+        session_id: u64,
+    },
+    SessionSpawnFailed {
+        detail: String,
+    },
+
+    /// A numeric config value outside its allowed range, after normalisation.
+    ConfigOutOfRange {
+        field: &'static str,
+        min: i64,
+        max: i64,
+    },
+    /// Config values that cannot be used together.
+    ConfigConflict {
+        field: &'static str,
+        reason: &'static str,
+    },
+
+    /// A typed answer that could not be parsed; `hint` says what to enter instead.
+    InvalidAnswer {
+        hint: String,
+    },
+    AnswerCountMismatch {
+        expected: usize,
+        provided: usize,
+    },
+    /// Command arguments that could not be decoded.
+    InvalidArgs {
+        detail: String,
+    },
+    /// A result that could not be encoded for the caller.
+    Encode {
+        detail: String,
+    },
+
+    ProfileNotFound {
+        id: String,
+    },
+    PresetNotFound {
+        name: String,
+    },
+    InvalidName {
+        reason: String,
+    },
+    NameTaken {
+        name: String,
+    },
+    LimitReached {
+        what: &'static str,
+        max: usize,
+    },
+    /// The default profile, or the active one, cannot be deleted.
+    ProfileProtected {
+        reason: &'static str,
+    },
+    /// A file that is not in a format this build can read.
+    InvalidFile {
+        detail: String,
+    },
+    UnsupportedVersion {
+        found: u32,
+        supported: u32,
+    },
+    /// A loaded session plan whose steps, numbers, sums or timings disagree.
+    InvalidPlan {
+        reason: String,
+    },
+    /// Reading or writing app data failed.
+    Storage {
+        detail: String,
+    },
+
+    /// A race action that does not fit the current state, or that the host refused.
+    Race {
+        reason: String,
+    },
+
+    UnknownSound {
+        kind: String,
+    },
+    SoundPackNotFound {
+        id: String,
+    },
+    Audio {
+        detail: String,
+    },
+}
+
+impl FlashspanError {
+    /// Stable machine-readable code, e.g. `"SessionAlreadyRunning"`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::SessionAlreadyRunning => "SessionAlreadyRunning",
+            Self::NoSessionRunning => "NoSessionRunning",
+            Self::ResultNotFound { .. } => "ResultNotFound",
+            Self::TimingReportNotFound { .. } => "TimingReportNotFound",
+            Self::NoCheckpointPending { .. } => "NoCheckpointPending",
+            Self::SessionSpawnFailed { .. } => "SessionSpawnFailed",
+            Self::ConfigOutOfRange { .. } => "ConfigOutOfRange",
+            Self::ConfigConflict { .. } => "ConfigConflict",
+            Self::InvalidAnswer { .. } => "InvalidAnswer",
+            Self::AnswerCountMismatch { .. } => "AnswerCountMismatch",
+            Self::InvalidArgs { .. } => "InvalidArgs",
+            Self::Encode { .. } => "Encode",
+            Self::ProfileNotFound { .. } => "ProfileNotFound",
+            Self::PresetNotFound { .. } => "PresetNotFound",
+            Self::InvalidName { .. } => "InvalidName",
+            Self::NameTaken { .. } => "NameTaken",
+            Self::LimitReached { .. } => "LimitReached",
+            Self::ProfileProtected { .. } => "ProfileProtected",
+            Self::InvalidFile { .. } => "InvalidFile",
+            Self::UnsupportedVersion { .. } => "UnsupportedVersion",
+            Self::InvalidPlan { .. } => "InvalidPlan",
+            Self::Storage { .. } => "Storage",
+            Self::Race { .. } => "Race",
+            Self::UnknownSound { .. } => "UnknownSound",
+            Self::SoundPackNotFound { .. } => "SoundPackNotFound",
+            Self::Audio { .. } => "Audio",
+        }
+    }
+
+    pub fn out_of_range(field: &'static str, min: impl Into<i64>, max: impl Into<i64>) -> Self {
+        Self::ConfigOutOfRange {
+            field,
+            min: min.into(),
+            max: max.into(),
+        }
+    }
+
+    pub fn storage(detail: impl fmt::Display) -> Self {
+        Self::Storage {
+            detail: detail.to_string(),
+        }
+    }
+
+    pub fn race(reason: impl fmt::Display) -> Self {
+        Self::Race {
+            reason: reason.to_string(),
+        }
+    }
+
+    pub fn invalid_args(detail: impl fmt::Display) -> Self {
+        Self::InvalidArgs {
+            detail: detail.to_string(),
+        }
+    }
+
+    pub fn encode(detail: impl fmt::Display) -> Self {
+        Self::Encode {
+            detail: detail.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for FlashspanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SessionAlreadyRunning => write!(f, "session already running"),
+            Self::NoSessionRunning => write!(f, "no session running"),
+            Self::ResultNotFound { .. } => write!(f, "session result not found"),
+            Self::TimingReportNotFound { .. } => write!(f, "timing report not found"),
+            Self::NoCheckpointPending { .. } => {
+                write!(f, "the session is not waiting for a checkpoint answer")
+            }
+            Self::SessionSpawnFailed { detail } => {
+                write!(f, "failed to spawn session worker: {}", detail)
+            }
+            Self::ConfigOutOfRange { field, min, max } => {
+                write!(f, "{} must be between {} and {}", field, min, max)
+            }
+            Self::ConfigConflict { reason, .. } => write!(f, "{}", reason),
+            Self::InvalidAnswer { hint } => write!(f, "{}", hint),
+            Self::AnswerCountMismatch { expected, provided } => {
+                write!(f, "expected {} answers, got {}", expected, provided)
+            }
+            Self::InvalidArgs { detail } => write!(f, "invalid args: {}", detail),
+            Self::Encode { detail } => write!(f, "failed to encode result: {}", detail),
+            Self::ProfileNotFound { id } => write!(f, "no profile with id {}", id),
+            Self::PresetNotFound { name } => write!(f, "no preset named \"{}\"", name),
+            Self::InvalidName { reason } => write!(f, "{}", reason),
+            Self::NameTaken { name } => write!(f, "the name \"{}\" is already in use", name),
+            Self::LimitReached { what, max } => write!(f, "at most {} {} can be saved", max, what),
+            Self::ProfileProtected { reason } => write!(f, "{}", reason),
+            Self::InvalidFile { detail } => write!(f, "invalid file: {}", detail),
+            Self::UnsupportedVersion { found, supported } => write!(
+                f,
+                "file version {} was made by a newer version of the app (this one reads up to {})",
+                found, supported
+            ),
+            Self::InvalidPlan { reason } => write!(f, "invalid plan: {}", reason),
+            Self::Storage { detail } => write!(f, "storage error: {}", detail),
+            Self::Race { reason } => write!(f, "{}", reason),
+            Self::UnknownSound { kind } => write!(f, "unknown sound kind: {}", kind),
+            Self::SoundPackNotFound { id } => write!(f, "no sound pack named \"{}\"", id),
+            Self::Audio { detail } => write!(f, "audio error: {}", detail),
+        }
+    }
+}
+
+impl std::error::Error for FlashspanError {}
+
+impl Serialize for FlashspanError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("code", self.code())?;
+        map.serialize_entry("message", &self.to_string())?;
+        match self {
+            Self::SessionAlreadyRunning | Self::NoSessionRunning => {}
+            Self::ResultNotFound { session_id }
+            | Self::TimingReportNotFound { session_id }
+            | Self::NoCheckpointPending { session_id } => {
+                map.serialize_entry("session_id", session_id)?;
+            }
+            Self::SessionSpawnFailed { detail }
+            | Self::InvalidArgs { detail }
+            | Self::Encode { detail }
+            | Self::InvalidFile { detail }
+            | Self::Storage { detail }
+            | Self::Audio { detail } => map.serialize_entry("detail", detail)?,
+            Self::ConfigOutOfRange { field, min, max } => {
+                map.serialize_entry("field", field)?;
+                map.serialize_entry("min", min)?;
+                map.serialize_entry("max", max)?;
+            }
+            Self::ConfigConflict { field, .. } => map.serialize_entry("field", field)?,
+            Self::InvalidAnswer { hint } => map.serialize_entry("hint", hint)?,
+            Self::AnswerCountMismatch { expected, provided } => {
+                map.serialize_entry("expected", expected)?;
+                map.serialize_entry("provided", provided)?;
+            }
+            Self::ProfileNotFound { id } | Self::SoundPackNotFound { id } => {
+                map.serialize_entry("id", id)?
+            }
+            Self::PresetNotFound { name } | Self::NameTaken { name } => {
+                map.serialize_entry("name", name)?
+            }
+            Self::InvalidName { .. } | Self::ProfileProtected { .. } => {}
+            Self::LimitReached { what, max } => {
+                map.serialize_entry("what", what)?;
+                map.serialize_entry("max", max)?;
+            }
+            Self::UnsupportedVersion { found, supported } => {
+                map.serialize_entry("found", found)?;
+                map.serialize_entry("supported", supported)?;
+            }
+            Self::InvalidPlan { reason } | Self::Race { reason } => {
+                map.serialize_entry("reason", reason)?
+            }
+            Self::UnknownSound { kind } => map.serialize_entry("kind", kind)?,
+        }
+        map.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialises_code_message_and_fields() {
+        let err = FlashspanError::out_of_range("total_numbers", 1, 10_000);
+        let value = serde_json::to_value(&err).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "code": "ConfigOutOfRange",
+                "message": "total_numbers must be between 1 and 10000",
+                "field": "total_numbers",
+                "min": 1,
+                "max": 10000,
+            })
+        );
+
+        let value = serde_json::to_value(FlashspanError::SessionAlreadyRunning).unwrap();
+        assert_eq!(value["code"], "SessionAlreadyRunning");
+        assert_eq!(value["message"], "session already running");
+    }
+}
@@ -0,0 +1,368 @@
+use crate::core::generate::random_number_with_constraints;
+use crate::core::types::FormulaMode;
+use rand::{Rng, RngExt};
+use serde::Serialize;
+
+/// Number of abacus columns simulated; enough to hold any non-negative `i128`.
+pub(crate) const COLUMNS: usize = 40;
+
+/// How many terms to construct while looking for one that exercises the mode's target formula.
+const TARGET_ATTEMPTS: u32 = 32;
+
+/// A single bead movement pattern used on one soroban column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AbacusFormula {
+    /// Beads are moved directly (e.g. 2 + 2).
+    Direct,
+    /// Five-complement, "small friends" (e.g. 3 + 2 = 3 + 5 - 3).
+    FiveComplement,
+    /// Ten-complement, "big friends" (e.g. 7 + 4 = 7 - 6 + 10).
+    TenComplement,
+    /// Ten-complement whose inner step needs a five-complement (e.g. 5 + 6 = 5 + 1 - 5 + 10).
+    Mixed,
+}
+
+impl FormulaMode {
+    /// Whether a term generated under this mode may use `formula` on any column.
+    pub fn allows(self, formula: AbacusFormula) -> bool {
+        match self {
+            FormulaMode::Any | FormulaMode::Mixed => true,
+            FormulaMode::Direct => formula == AbacusFormula::Direct,
+            FormulaMode::FiveComplement => matches!(
+                formula,
+                AbacusFormula::Direct | AbacusFormula::FiveComplement
+            ),
+            FormulaMode::TenComplement => matches!(
+                formula,
+                AbacusFormula::Direct | AbacusFormula::TenComplement
+            ),
+        }
+    }
+
+    /// The formula each term should exercise at least once, when the running sum allows it.
+    pub fn target(self) -> Option<AbacusFormula> {
+        match self {
+            FormulaMode::Any | FormulaMode::Direct => None,
+            FormulaMode::FiveComplement => Some(AbacusFormula::FiveComplement),
+            FormulaMode::TenComplement => Some(AbacusFormula::TenComplement),
+            FormulaMode::Mixed => Some(AbacusFormula::Mixed),
+        }
+    }
+
+    /// Restricted modes can run out of legal additions (e.g. a 9 in the leading column under
+    /// `Direct`), so they rely on subtraction to keep the sequence going.
+    pub fn requires_negative_numbers(self) -> bool {
+        matches!(
+            self,
+            FormulaMode::Direct | FormulaMode::FiveComplement | FormulaMode::TenComplement
+        )
+    }
+}
+
+/// Decimal digits of `value`, units first. Negative values read as zero.
+pub(crate) fn to_columns(value: i128) -> [u8; COLUMNS] {
+    let mut columns = [0u8; COLUMNS];
+    let mut rest = value.max(0);
+    for column in columns.iter_mut() {
+        *column = (rest % 10) as u8;
+        rest /= 10;
+    }
+    columns
+}
+
+fn can_add_direct(bead: u8, digit: u8) -> bool {
+    bead / 5 + digit / 5 <= 1 && bead % 5 + digit % 5 <= 4
+}
+
+fn can_sub_direct(bead: u8, digit: u8) -> bool {
+    bead / 5 >= digit / 5 && bead % 5 >= digit % 5
+}
+
+/// Add `digit` to one column, rippling any carry leftwards.
+/// Returns false if the carry runs off the simulated board.
+fn add_digit(
+    columns: &mut [u8; COLUMNS],
+    column: usize,
+    digit: u8,
+    used: &mut Vec<AbacusFormula>,
+) -> bool {
+    if digit == 0 {
+        return true;
+    }
+
+    let bead = columns[column];
+    if bead + digit <= 9 {
+        used.push(if can_add_direct(bead, digit) {
+            AbacusFormula::Direct
+        } else {
+            AbacusFormula::FiveComplement
+        });
+        columns[column] = bead + digit;
+        return true;
+    }
+
+    // Big friend: take the complement away here and carry one to the left.
+    let complement = 10 - digit;
+    used.push(if can_sub_direct(bead, complement) {
+        AbacusFormula::TenComplement
+    } else {
+        AbacusFormula::Mixed
+    });
+    columns[column] = bead - complement;
+    column + 1 < COLUMNS && add_digit(columns, column + 1, 1, used)
+}
+
+/// Subtract `digit` from one column, rippling any borrow leftwards.
+/// Returns false if the borrow runs off the board, i.e. the result would be negative.
+fn sub_digit(
+    columns: &mut [u8; COLUMNS],
+    column: usize,
+    digit: u8,
+    used: &mut Vec<AbacusFormula>,
+) -> bool {
+    if digit == 0 {
+        return true;
+    }
+
+    let bead = columns[column];
+    if bead >= digit {
+        used.push(if can_sub_direct(bead, digit) {
+            AbacusFormula::Direct
+        } else {
+            AbacusFormula::FiveComplement
+        });
+        columns[column] = bead - digit;
+        return true;
+    }
+
+    // Big friend: add the complement here and borrow one from the left.
+    let complement = 10 - digit;
+    used.push(if can_add_direct(bead, complement) {
+        AbacusFormula::TenComplement
+    } else {
+        AbacusFormula::Mixed
+    });
+    columns[column] = bead + complement;
+    column + 1 < COLUMNS && sub_digit(columns, column + 1, 1, used)
+}
+
+/// Classify the formulas needed to apply `term` to a soroban showing `running_sum`.
+///
+/// Columns are worked from the highest place down, as on a real soroban, and every carry or
+/// borrow is classified as its own column operation. Returns `None` when `running_sum` is
+/// negative or applying `term` would take the board below zero.
+pub fn term_formulas(running_sum: i128, term: i128) -> Option<Vec<AbacusFormula>> {
+    if running_sum < 0 {
+        return None;
+    }
+
+    let mut columns = to_columns(running_sum);
+    let digits = term.unsigned_abs().to_string();
+    let mut used = Vec::new();
+
+    for (offset, ch) in digits.bytes().enumerate() {
+        let column = digits.len() - 1 - offset;
+        if column >= COLUMNS {
+            return None;
+        }
+        let digit = ch - b'0';
+        let ok = if term < 0 {
+            sub_digit(&mut columns, column, digit, &mut used)
+        } else {
+            add_digit(&mut columns, column, digit, &mut used)
+        };
+        if !ok {
+            return None;
+        }
+    }
+
+    Some(used)
+}
+
+/// Build one term column by column, choosing each digit among those whose bead moves are
+/// allowed by `mode`. Returns the magnitude and whether the mode's target formula was used.
+fn build_term(
+    rng: &mut impl Rng,
+    digits: u32,
+    negative: bool,
+    running_sum: i128,
+    mode: FormulaMode,
+) -> Option<(u64, bool)> {
+    let mut columns = to_columns(running_sum);
+    let mut magnitude: u64 = 0;
+    let mut hit_target = false;
+    let target = mode.target();
+
+    for column in (0..digits as usize).rev() {
+        // No leading zero: the highest column always moves.
+        let lowest = if column + 1 == digits as usize { 1 } else { 0 };
+        let candidates: Vec<(u8, [u8; COLUMNS], bool)> = (lowest..=9u8)
+            .filter_map(|digit| {
+                let mut trial = columns;
+                let mut used = Vec::new();
+                let ok = if negative {
+                    sub_digit(&mut trial, column, digit, &mut used)
+                } else {
+                    add_digit(&mut trial, column, digit, &mut used)
+                };
+                if !ok || !used.iter().all(|f| mode.allows(*f)) {
+                    return None;
+                }
+                let hit = target.is_some_and(|t| used.contains(&t));
+                Some((digit, trial, hit))
+            })
+            .collect();
+
+        if candidates.is_empty() {
+            return None;
+        }
+
+        let (digit, next, hit) = candidates[rng.random_range(0..candidates.len())];
+        columns = next;
+        hit_target |= hit;
+        magnitude = magnitude * 10 + digit as u64;
+    }
+
+    Some((magnitude, hit_target))
+}
+
+/// Generate a term whose bead moves stay within `mode`, on top of the running-sum rules of
+/// [`random_number_with_constraints`] (first number non-negative, sum never below zero).
+///
+/// `FormulaMode::Any` defers to the unconstrained generator. Restricted modes keep trying for
+/// a term that uses the mode's target formula and settle for an allowed term otherwise.
+pub fn random_number_with_formula(
+    rng: &mut impl Rng,
+    digits: u32,
+    allow_negative_numbers: bool,
+    index: u32,
+    running_sum: i128,
+    mode: FormulaMode,
+) -> (String, i128) {
+    if mode == FormulaMode::Any {
+        return random_number_with_constraints(
+            rng,
+            digits,
+            allow_negative_numbers,
+            index,
+            running_sum,
+        );
+    }
+
+    let allow_negative_here = allow_negative_numbers && index > 0 && running_sum > 0;
+    let mut fallback: Option<i128> = None;
+
+    for _ in 0..TARGET_ATTEMPTS {
+        let prefer_negative = allow_negative_here && rng.random_bool(0.5);
+        let signs = [prefer_negative, !prefer_negative];
+
+        for negative in signs {
+            if negative && !allow_negative_here {
+                continue;
+            }
+            let Some((magnitude, hit_target)) =
+                build_term(rng, digits, negative, running_sum, mode)
+            else {
+                continue;
+            };
+
+            let value = if negative {
+                -(magnitude as i128)
+            } else {
+                magnitude as i128
+            };
+            if hit_target || mode.target().is_none() {
+                return (value.to_string(), value);
+            }
+            fallback.get_or_insert(value);
+            break;
+        }
+    }
+
+    match fallback {
+        Some(value) => (value.to_string(), value),
+        // Only reachable when a restricted mode runs without negatives, which
+        // `validate_config` rejects.
+        None => {
+            random_number_with_constraints(rng, digits, allow_negative_numbers, index, running_sum)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_single_column_additions() {
+        assert_eq!(term_formulas(2, 2), Some(vec![AbacusFormula::Direct]));
+        assert_eq!(
+            term_formulas(3, 2),
+            Some(vec![AbacusFormula::FiveComplement])
+        );
+        // 7 + 4: take 6 away directly, carry 1 into the empty tens column.
+        assert_eq!(
+            term_formulas(7, 4),
+            Some(vec![AbacusFormula::TenComplement, AbacusFormula::Direct])
+        );
+        // 5 + 6: taking 4 from 5 needs a small friend inside the big friend.
+        assert_eq!(
+            term_formulas(5, 6),
+            Some(vec![AbacusFormula::Mixed, AbacusFormula::Direct])
+        );
+    }
+
+    #[test]
+    fn classifies_single_column_subtractions() {
+        assert_eq!(term_formulas(9, -4), Some(vec![AbacusFormula::Direct]));
+        assert_eq!(
+            term_formulas(5, -2),
+            Some(vec![AbacusFormula::FiveComplement])
+        );
+        // 12 - 3: add 7 to the units directly, borrow 1 from the tens.
+        assert_eq!(
+            term_formulas(12, -3),
+            Some(vec![AbacusFormula::TenComplement, AbacusFormula::Direct])
+        );
+        // 14 - 6: adding 4 to the 4 in the units needs a small friend.
+        assert_eq!(
+            term_formulas(14, -6),
+            Some(vec![AbacusFormula::Mixed, AbacusFormula::Direct])
+        );
+    }
+
+    #[test]
+    fn carry_ripple_is_classified_per_column() {
+        // 49 + 1: units is a big friend, carrying into a 4 needs a small friend.
+        assert_eq!(
+            term_formulas(49, 1),
+            Some(vec![
+                AbacusFormula::TenComplement,
+                AbacusFormula::FiveComplement
+            ])
+        );
+    }
+
+    #[test]
+    fn term_formulas_rejects_negative_results() {
+        assert_eq!(term_formulas(5, -6), None);
+        assert_eq!(term_formulas(-1, 1), None);
+    }
+
+    #[test]
+    fn restricted_mode_falls_back_to_subtraction_when_addition_is_blocked() {
+        use rand::SeedableRng;
+        use rand::rngs::StdRng;
+
+        let mut rng = StdRng::seed_from_u64(7);
+        // A 9 in the only column: no direct addition exists, so every term must subtract.
+        for _ in 0..50 {
+            let (_, value) =
+                random_number_with_formula(&mut rng, 1, true, 1, 9, FormulaMode::Direct);
+            assert!(value < 0, "expected a subtraction from 9, got {value}");
+            let used = term_formulas(9, value).expect("term should apply");
+            assert!(used.iter().all(|f| *f == AbacusFormula::Direct));
+        }
+    }
+}
@@ -1,3 +1,6 @@
+use crate::core::carry::random_number_with_carry_policy;
+use crate::core::formula::random_number_with_formula;
+use crate::core::types::{CarryPolicy, DigitRange, OperandPair, OperationMode, SessionConfig};
 use rand::{Rng, RngExt};
 
 pub(crate) fn random_fixed_digits_no_leading_zero(rng: &mut impl Rng, digits: u32) -> String {
@@ -81,3 +84,126 @@ pub fn random_number_with_constraints(
         .expect("generated magnitude should parse as integer");
     (magnitude, magnitude_value)
 }
+
+impl SessionConfig {
+    /// The widest integer part this config can produce, excluding decimal places.
+    pub fn max_term_digits(&self) -> u32 {
+        match (self.digit_pattern.iter().max(), self.digit_range) {
+            (Some(&widest), _) => widest,
+            (None, Some(DigitRange { max, .. })) => max,
+            (None, None) => self.digits_per_number,
+        }
+    }
+}
+
+/// Width of the term at `index`. An explicit `digit_pattern` (cycled) wins over `digit_range`,
+/// which wins over the fixed `digits_per_number`.
+pub fn term_digits(rng: &mut impl Rng, config: &SessionConfig, index: u32) -> u32 {
+    if !config.digit_pattern.is_empty() {
+        return config.digit_pattern[index as usize % config.digit_pattern.len()];
+    }
+    match config.digit_range {
+        Some(DigitRange { min, max }) => rng.random_range(min..=max),
+        None => config.digits_per_number,
+    }
+}
+
+/// Generate the next term using whichever constraint family `config` enables.
+/// Formula modes and carry policies are mutually exclusive (see `validate_config`).
+///
+/// In decimal sessions the term is a fixed-point value: its width covers the integer digits
+/// plus `decimal_places`, so abacus columns and carries include the fractional columns.
+pub fn random_term(
+    rng: &mut impl Rng,
+    config: &SessionConfig,
+    index: u32,
+    running_sum: i128,
+) -> (String, i128) {
+    let digits = term_digits(rng, config, index) + config.decimal_places;
+
+    if config.carry_policy != CarryPolicy::Any {
+        return random_number_with_carry_policy(
+            rng,
+            digits,
+            config.allow_negative_numbers,
+            index,
+            running_sum,
+            config.carry_policy,
+        );
+    }
+
+    random_number_with_formula(
+        rng,
+        digits,
+        config.allow_negative_numbers,
+        index,
+        running_sum,
+        config.formula_mode,
+    )
+}
+
+/// A multiplication/division operand of exactly `digits` digits. Single-digit operands skip 1,
+/// which would make the problem trivial.
+fn random_operand(rng: &mut impl Rng, digits: u32) -> u64 {
+    if digits <= 1 {
+        return rng.random_range(2u64..=9u64);
+    }
+    rng.random_range(10u64.pow(digits - 1)..10u64.pow(digits))
+}
+
+/// Generate one multiplication or division problem.
+///
+/// Multiplication pairs a `left_digits`-digit multiplicand with a `right_digits`-digit
+/// multiplier. Division picks a `right_digits`-digit divisor and a `left_digits`-digit dividend
+/// that it divides exactly, so the answer is always a whole number. Like the operands, the
+/// quotient is never 1, so the divisor is at most half the largest dividend.
+pub fn random_operand_pair(
+    rng: &mut impl Rng,
+    operation: OperationMode,
+    left_digits: u32,
+    right_digits: u32,
+) -> OperandPair {
+    match operation {
+        OperationMode::Division => {
+            let dividend_min = if left_digits <= 1 {
+                1
+            } else {
+                10u64.pow(left_digits - 1)
+            };
+            let dividend_max = 10u64.pow(left_digits) - 1;
+            let divisor_min = if right_digits <= 1 {
+                2
+            } else {
+                10u64.pow(right_digits - 1)
+            };
+            let divisor_max = (10u64.pow(right_digits) - 1).min(dividend_max / 2);
+            let divisor = rng.random_range(divisor_min..=divisor_max.max(divisor_min));
+            let quotient_min = dividend_min.div_ceil(divisor).max(2);
+            let quotient_max = dividend_max / divisor;
+            debug_assert!(
+                quotient_min <= quotient_max,
+                "divisor {} has no {}-digit multiple",
+                divisor,
+                left_digits
+            );
+            let quotient = rng.random_range(quotient_min..=quotient_max.max(quotient_min));
+            OperandPair {
+                operation,
+                left: (quotient * divisor) as i64,
+                right: divisor as i64,
+                answer: quotient as i64,
+            }
+        }
+        // Addition has no pairs; treat a stray request as multiplication.
+        OperationMode::Multiplication | OperationMode::Addition => {
+            let left = random_operand(rng, left_digits);
+            let right = random_operand(rng, right_digits);
+            OperandPair {
+                operation: OperationMode::Multiplication,
+                left: left as i64,
+                right: right as i64,
+                answer: (left * right) as i64,
+            }
+        }
+    }
+}
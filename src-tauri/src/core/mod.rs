@@ -1,5 +1,18 @@
+pub mod adaptive;
+pub mod analytics;
+pub mod answer;
+pub mod carry;
+pub mod decimal;
+pub mod diagnosis;
+pub mod dictation;
 pub mod engine;
+pub mod error;
+pub mod formula;
 pub mod generate;
+pub mod plan_file;
+pub mod race;
+pub mod scoring;
+pub mod timing;
 pub mod types;
 pub mod validate;
 
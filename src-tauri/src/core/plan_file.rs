@@ -0,0 +1,741 @@
+//! Saved session plans, so a class can replay the exact same sequence.
+//!
+//! A plan file is JSON: `{ "format": "flashspan-plan", "version": 3, "plan": { ... } }`.
+//! The compact binary form starts with [`BINARY_MAGIC`] and a varint version, followed by
+//! the plan in varint/zigzag encoding (see [`encode_binary`]). [`decode`] accepts either and
+//! checks the plan with [`verify`] before it is returned.
+
+use super::error::FlashspanError;
+use super::timing::MAX_FLASH_MS;
+use super::types::{OperandPair, OperationMode, SessionConfigEffective, SessionPlan, SessionStep};
+use serde::{Deserialize, Serialize};
+
+pub const PLAN_FILE_FORMAT: &str = "flashspan-plan";
+/// Current plan file version, shared by the JSON and binary forms. Bump when the meaning of
+/// a field changes, and keep reading older versions in [`decode`].
+///
+/// Version 2 records the generation seed; version 1 plans read it as 0. Version 3 adds
+/// checkpoint steps, which older readers cannot parse.
+pub const PLAN_FILE_VERSION: u32 = 3;
+pub const BINARY_MAGIC: &[u8; 4] = b"FSPL";
+
+/// Countdown, initial and final clears, complete, plus a flash, a clear and at most one
+/// checkpoint per number.
+const MAX_STEPS: usize = 6 + 3 * 10_000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanFile {
+    pub format: String,
+    pub version: u32,
+    pub plan: SessionPlan,
+}
+
+pub fn encode_json(plan: &SessionPlan) -> Result<String, FlashspanError> {
+    let file = PlanFile {
+        format: PLAN_FILE_FORMAT.to_string(),
+        version: PLAN_FILE_VERSION,
+        plan: plan.clone(),
+    };
+    serde_json::to_string_pretty(&file).map_err(FlashspanError::storage)
+}
+
+/// Parse a plan file in either form and check it with [`verify`].
+pub fn decode(data: &[u8]) -> Result<SessionPlan, FlashspanError> {
+    let plan = match data.strip_prefix(BINARY_MAGIC.as_slice()) {
+        Some(body) => decode_binary(body)?,
+        None => decode_json(data)?,
+    };
+    verify(&plan)?;
+    Ok(plan)
+}
+
+fn invalid_file(detail: impl Into<String>) -> FlashspanError {
+    FlashspanError::InvalidFile {
+        detail: detail.into(),
+    }
+}
+
+/// Refuse files newer than `supported`, normally [`PLAN_FILE_VERSION`].
+fn check_version(version: u64, supported: u32) -> Result<(), FlashspanError> {
+    if version > supported as u64 {
+        return Err(FlashspanError::UnsupportedVersion {
+            found: version.min(u32::MAX as u64) as u32,
+            supported,
+        });
+    }
+    Ok(())
+}
+
+fn decode_json(data: &[u8]) -> Result<SessionPlan, FlashspanError> {
+    let value: serde_json::Value = serde_json::from_slice(data)
+        .map_err(|e| invalid_file(format!("not a plan file: {}", e)))?;
+    if value.get("format").and_then(|f| f.as_str()) != Some(PLAN_FILE_FORMAT) {
+        return Err(invalid_file("not a plan file: missing `format`"));
+    }
+    let version = value
+        .get("version")
+        .and_then(|v| v.as_u64())
+        .ok_or_else(|| invalid_file("not a plan file: missing `version`"))?;
+    check_version(version, PLAN_FILE_VERSION)?;
+
+    let plan = value
+        .get("plan")
+        .ok_or_else(|| invalid_file("not a plan file: missing `plan`"))?;
+    serde_json::from_value(plan.clone()).map_err(|e| invalid_file(format!("invalid plan: {}", e)))
+}
+
+/// Check that a plan is internally consistent before it runs: the flashed steps match
+/// `numbers_generated` and `pairs_generated`, the sums match `expected_sum` and each
+/// checkpoint's subtotal, and the step delays add up to `total_duration_ms`.
+pub fn verify(plan: &SessionPlan) -> Result<(), FlashspanError> {
+    let bad = |reason: String| Err(FlashspanError::InvalidPlan { reason });
+
+    if plan.steps.is_empty() || plan.steps.len() > MAX_STEPS {
+        return bad(format!("a plan must have 1..={} steps", MAX_STEPS));
+    }
+    if !plan.numbers_generated.is_empty() && !plan.pairs_generated.is_empty() {
+        return bad("a plan cannot flash both numbers and pairs".to_string());
+    }
+
+    let decimal_places = plan.config_snapshot.decimal_places;
+    let mut numbers: Vec<i64> = Vec::new();
+    let mut pairs: Vec<OperandPair> = Vec::new();
+    let mut running_sum: i128 = 0;
+    let mut subtotal: i128 = 0;
+    let mut duration_ms: u64 = 0;
+    let mut complete = None;
+
+    for (i, step) in plan.steps.iter().enumerate() {
+        if complete.is_some() {
+            return bad(format!("step {} comes after the complete step", i + 1));
+        }
+        let (session_id, delay_ms) = match step {
+            SessionStep::CountdownTick {
+                delay_ms_before_next,
+                ..
+            } => (plan.session_id, *delay_ms_before_next),
+            SessionStep::ShowNumber {
+                session_id,
+                index,
+                total,
+                value,
+                running_sum: shown_sum,
+                decimal_places: places,
+                delay_ms_before_next,
+                ..
+            } => {
+                numbers.push(*value);
+                subtotal += *value as i128;
+                // The engine never lets the running sum drop below zero.
+                running_sum = (running_sum + *value as i128).max(0);
+                if *index as usize != numbers.len()
+                    || *total as usize != plan.numbers_generated.len()
+                {
+                    return bad(format!(
+                        "flash {} is numbered {}/{}",
+                        numbers.len(),
+                        index,
+                        total
+                    ));
+                }
+                if *shown_sum as i128 != running_sum {
+                    return bad(format!("flash {} shows the wrong running sum", index));
+                }
+                if *places != decimal_places {
+                    return bad(format!("flash {} uses different decimal places", index));
+                }
+                (*session_id, *delay_ms_before_next)
+            }
+            SessionStep::ShowPair {
+                session_id,
+                index,
+                total,
+                operation,
+                left,
+                right,
+                delay_ms_before_next,
+            } => {
+                let Some(expected) = plan.pairs_generated.get(pairs.len()) else {
+                    return bad(format!("pair {} is not in pairs_generated", index));
+                };
+                pairs.push(OperandPair {
+                    operation: *operation,
+                    left: *left,
+                    right: *right,
+                    answer: expected.answer,
+                });
+                if *index as usize != pairs.len() || *total as usize != plan.pairs_generated.len() {
+                    return bad(format!(
+                        "pair {} is numbered {}/{}",
+                        pairs.len(),
+                        index,
+                        total
+                    ));
+                }
+                (*session_id, *delay_ms_before_next)
+            }
+            SessionStep::ClearScreen {
+                session_id,
+                delay_ms_before_next,
+                ..
+            } => (*session_id, *delay_ms_before_next),
+            SessionStep::Checkpoint {
+                session_id,
+                index,
+                total,
+                expected_sum,
+                decimal_places: places,
+                delay_ms_before_next,
+            } => {
+                if numbers.is_empty()
+                    || *index as usize != numbers.len()
+                    || *total as usize != plan.numbers_generated.len()
+                {
+                    return bad(format!(
+                        "checkpoint after {} numbers is numbered {}/{}",
+                        numbers.len(),
+                        index,
+                        total
+                    ));
+                }
+                if *expected_sum as i128 != subtotal {
+                    return bad(format!("checkpoint {} expects the wrong subtotal", index));
+                }
+                if *places != decimal_places {
+                    return bad(format!(
+                        "checkpoint {} uses different decimal places",
+                        index
+                    ));
+                }
+                (*session_id, *delay_ms_before_next)
+            }
+            SessionStep::Complete {
+                session_id,
+                numbers,
+                sum,
+                decimal_places: places,
+                pairs,
+            } => {
+                complete = Some((numbers, *sum, *places, pairs));
+                (*session_id, 0)
+            }
+        };
+
+        if session_id != plan.session_id {
+            return bad(format!("step {} belongs to another session", i + 1));
+        }
+        if delay_ms > MAX_FLASH_MS {
+            return bad(format!(
+                "step {} waits longer than {} ms",
+                i + 1,
+                MAX_FLASH_MS
+            ));
+        }
+        duration_ms += delay_ms;
+    }
+
+    let Some((complete_numbers, complete_sum, complete_places, complete_pairs)) = complete else {
+        return bad("the plan has no complete step".to_string());
+    };
+    if numbers != plan.numbers_generated || *complete_numbers != plan.numbers_generated {
+        return bad("flashed numbers do not match numbers_generated".to_string());
+    }
+    if pairs != plan.pairs_generated || *complete_pairs != plan.pairs_generated {
+        return bad("flashed pairs do not match pairs_generated".to_string());
+    }
+    if let Some(pair) = pairs.iter().find(|p| !answer_is_right(p)) {
+        return bad(format!(
+            "pair {} {:?} {} has the wrong answer",
+            pair.left, pair.operation, pair.right
+        ));
+    }
+    let sum: i128 = numbers.iter().map(|&n| n as i128).sum();
+    if sum != plan.expected_sum as i128 || complete_sum != plan.expected_sum {
+        return bad("numbers do not add up to expected_sum".to_string());
+    }
+    if complete_places != decimal_places {
+        return bad("the complete step uses different decimal places".to_string());
+    }
+    if duration_ms != plan.total_duration_ms {
+        return bad(format!(
+            "step delays add up to {} ms, not total_duration_ms {}",
+            duration_ms, plan.total_duration_ms
+        ));
+    }
+    Ok(())
+}
+
+fn answer_is_right(pair: &OperandPair) -> bool {
+    match pair.operation {
+        OperationMode::Addition => false,
+        OperationMode::Multiplication => pair.left.checked_mul(pair.right) == Some(pair.answer),
+        OperationMode::Division => {
+            pair.right != 0
+                && pair.left.checked_rem(pair.right) == Some(0)
+                && pair.left.checked_div(pair.right) == Some(pair.answer)
+        }
+    }
+}
+
+impl SessionPlan {
+    /// The same plan under another session id, e.g. when replaying a loaded plan.
+    pub fn with_session_id(mut self, id: u64) -> Self {
+        self.session_id = id;
+        for step in &mut self.steps {
+            match step {
+                SessionStep::CountdownTick { .. } => {}
+                SessionStep::ShowNumber { session_id, .. }
+                | SessionStep::ShowPair { session_id, .. }
+                | SessionStep::ClearScreen { session_id, .. }
+                | SessionStep::Checkpoint { session_id, .. }
+                | SessionStep::Complete { session_id, .. } => *session_id = id,
+            }
+        }
+        self
+    }
+}
+
+// Binary form: after the magic and version, unsigned integers are LEB128 varints and signed
+// ones are zigzag varints. Per-step session ids are implied by the plan's, and the complete
+// step reuses the plan's numbers, sum and pairs, so neither is repeated.
+
+const TAG_COUNTDOWN: u8 = 0;
+const TAG_SHOW_NUMBER: u8 = 1;
+const TAG_CLEAR: u8 = 2;
+const TAG_SHOW_PAIR: u8 = 3;
+const TAG_COMPLETE: u8 = 4;
+const TAG_CHECKPOINT: u8 = 5;
+
+pub fn encode_binary(plan: &SessionPlan) -> Result<Vec<u8>, FlashspanError> {
+    let mut w = Writer(BINARY_MAGIC.to_vec());
+    w.uint(PLAN_FILE_VERSION as u64);
+    w.uint(plan.session_id);
+    w.uint(plan.seed);
+    // The config snapshot is small and changes shape often; keep it as JSON.
+    w.bytes(&serde_json::to_vec(&plan.config_snapshot).map_err(FlashspanError::storage)?);
+    w.uint(plan.total_duration_ms);
+    w.int(plan.expected_sum);
+    w.uint(plan.numbers_generated.len() as u64);
+    for &n in &plan.numbers_generated {
+        w.int(n);
+    }
+    w.uint(plan.pairs_generated.len() as u64);
+    for pair in &plan.pairs_generated {
+        w.operation(pair.operation);
+        w.int(pair.left);
+        w.int(pair.right);
+        w.int(pair.answer);
+    }
+
+    w.uint(plan.steps.len() as u64);
+    for step in &plan.steps {
+        match step {
+            SessionStep::CountdownTick {
+                value,
+                delay_ms_before_next,
+            } => {
+                w.0.push(TAG_COUNTDOWN);
+                w.bytes(value.as_bytes());
+                w.uint(*delay_ms_before_next);
+            }
+            SessionStep::ShowNumber {
+                index,
+                total,
+                value,
+                running_sum,
+                carries,
+                decimal_places,
+                delay_ms_before_next,
+                ..
+            } => {
+                w.0.push(TAG_SHOW_NUMBER);
+                w.uint(*index as u64);
+                w.uint(*total as u64);
+                w.int(*value);
+                w.int(*running_sum);
+                w.uint(*carries as u64);
+                w.uint(*decimal_places as u64);
+                w.uint(*delay_ms_before_next);
+            }
+            SessionStep::ClearScreen {
+                index,
+                delay_ms_before_next,
+                ..
+            } => {
+                w.0.push(TAG_CLEAR);
+                w.uint(index.map_or(0, |i| i as u64 + 1));
+                w.uint(*delay_ms_before_next);
+            }
+            SessionStep::ShowPair {
+                index,
+                total,
+                operation,
+                left,
+                right,
+                delay_ms_before_next,
+                ..
+            } => {
+                w.0.push(TAG_SHOW_PAIR);
+                w.uint(*index as u64);
+                w.uint(*total as u64);
+                w.operation(*operation);
+                w.int(*left);
+                w.int(*right);
+                w.uint(*delay_ms_before_next);
+            }
+            SessionStep::Checkpoint {
+                index,
+                total,
+                expected_sum,
+                decimal_places,
+                delay_ms_before_next,
+                ..
+            } => {
+                w.0.push(TAG_CHECKPOINT);
+                w.uint(*index as u64);
+                w.uint(*total as u64);
+                w.int(*expected_sum);
+                w.uint(*decimal_places as u64);
+                w.uint(*delay_ms_before_next);
+            }
+            SessionStep::Complete {
+                numbers,
+                sum,
+                decimal_places,
+                pairs,
+                ..
+            } => {
+                if *numbers != plan.numbers_generated
+                    || *sum != plan.expected_sum
+                    || *pairs != plan.pairs_generated
+                {
+                    return Err(FlashspanError::InvalidPlan {
+                        reason: "the complete step does not match the plan".to_string(),
+                    });
+                }
+                w.0.push(TAG_COMPLETE);
+                w.uint(*decimal_places as u64);
+            }
+        }
+    }
+    Ok(w.0)
+}
+
+fn decode_binary(body: &[u8]) -> Result<SessionPlan, FlashspanError> {
+    let mut r = Reader(body);
+    let version = r.uint()?;
+    check_version(version, PLAN_FILE_VERSION)?;
+    let session_id = r.uint()?;
+    let seed = if version >= 2 { r.uint()? } else { 0 };
+    let config_snapshot: SessionConfigEffective = serde_json::from_slice(r.bytes()?)
+        .map_err(|e| invalid_file(format!("invalid config snapshot: {}", e)))?;
+    let total_duration_ms = r.uint()?;
+    let expected_sum = r.int()?;
+    let numbers_generated = (0..r.count()?)
+        .map(|_| r.int())
+        .collect::<Result<Vec<_>, _>>()?;
+    let pairs_generated = (0..r.count()?)
+        .map(|_| {
+            Ok(OperandPair {
+                operation: r.operation()?,
+                left: r.int()?,
+                right: r.int()?,
+                answer: r.int()?,
+            })
+        })
+        .collect::<Result<Vec<_>, FlashspanError>>()?;
+
+    let step_count = r.count()?;
+    let mut steps = Vec::with_capacity(step_count);
+    for _ in 0..step_count {
+        let step = match r.byte()? {
+            TAG_COUNTDOWN => SessionStep::CountdownTick {
+                value: String::from_utf8(r.bytes()?.to_vec())
+                    .map_err(|_| invalid_file("countdown text is not UTF-8"))?,
+                delay_ms_before_next: r.uint()?,
+            },
+            TAG_SHOW_NUMBER => SessionStep::ShowNumber {
+                session_id,
+                index: r.u32()?,
+                total: r.u32()?,
+                value: r.int()?,
+                running_sum: r.int()?,
+                carries: r.u32()?,
+                decimal_places: r.u32()?,
+                delay_ms_before_next: r.uint()?,
+            },
+            TAG_CLEAR => SessionStep::ClearScreen {
+                session_id,
+                index: r.u32()?.checked_sub(1),
+                delay_ms_before_next: r.uint()?,
+            },
+            TAG_SHOW_PAIR => SessionStep::ShowPair {
+                session_id,
+                index: r.u32()?,
+                total: r.u32()?,
+                operation: r.operation()?,
+                left: r.int()?,
+                right: r.int()?,
+                delay_ms_before_next: r.uint()?,
+            },
+            TAG_CHECKPOINT => SessionStep::Checkpoint {
+                session_id,
+                index: r.u32()?,
+                total: r.u32()?,
+                expected_sum: r.int()?,
+                decimal_places: r.u32()?,
+                delay_ms_before_next: r.uint()?,
+            },
+            TAG_COMPLETE => SessionStep::Complete {
+                session_id,
+                numbers: numbers_generated.clone(),
+                sum: expected_sum,
+                decimal_places: r.u32()?,
+                pairs: pairs_generated.clone(),
+            },
+            tag => return Err(invalid_file(format!("unknown step tag {}", tag))),
+        };
+        steps.push(step);
+    }
+    if !r.0.is_empty() {
+        return Err(invalid_file("trailing bytes after the plan"));
+    }
+
+    Ok(SessionPlan {
+        session_id,
+        config_snapshot,
+        steps,
+        total_duration_ms,
+        numbers_generated,
+        expected_sum,
+        pairs_generated,
+        seed,
+    })
+}
+
+struct Writer(Vec<u8>);
+
+impl Writer {
+    fn uint(&mut self, mut v: u64) {
+        while v >= 0x80 {
+            self.0.push((v as u8) | 0x80);
+            v >>= 7;
+        }
+        self.0.push(v as u8);
+    }
+
+    fn int(&mut self, v: i64) {
+        self.uint(((v << 1) ^ (v >> 63)) as u64);
+    }
+
+    fn bytes(&mut self, b: &[u8]) {
+        self.uint(b.len() as u64);
+        self.0.extend_from_slice(b);
+    }
+
+    fn operation(&mut self, op: OperationMode) {
+        self.0.push(match op {
+            OperationMode::Addition => 0,
+            OperationMode::Multiplication => 1,
+            OperationMode::Division => 2,
+        });
+    }
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl Reader<'_> {
+    fn byte(&mut self) -> Result<u8, FlashspanError> {
+        let (&b, rest) = self
+            .0
+            .split_first()
+            .ok_or_else(|| invalid_file("plan file is truncated"))?;
+        self.0 = rest;
+        Ok(b)
+    }
+
+    fn uint(&mut self) -> Result<u64, FlashspanError> {
+        let mut v: u64 = 0;
+        for shift in (0..64).step_by(7) {
+            let b = self.byte()?;
+            v |= ((b & 0x7f) as u64) << shift;
+            if b & 0x80 == 0 {
+                return Ok(v);
+            }
+        }
+        Err(invalid_file("varint is too long"))
+    }
+
+    fn u32(&mut self) -> Result<u32, FlashspanError> {
+        u32::try_from(self.uint()?).map_err(|_| invalid_file("value does not fit in 32 bits"))
+    }
+
+    fn int(&mut self) -> Result<i64, FlashspanError> {
+        let v = self.uint()?;
+        Ok(((v >> 1) as i64) ^ -((v & 1) as i64))
+    }
+
+    /// A length that cannot exceed the bytes left, so corrupt input cannot force a huge
+    /// allocation.
+    fn count(&mut self) -> Result<usize, FlashspanError> {
+        let n = self.uint()?;
+        if n > self.0.len() as u64 {
+            return Err(invalid_file("plan file is truncated"));
+        }
+        Ok(n as usize)
+    }
+
+    fn bytes(&mut self) -> Result<&[u8], FlashspanError> {
+        let n = self.count()?;
+        let (b, rest) = self.0.split_at(n);
+        self.0 = rest;
+        Ok(b)
+    }
+
+    fn operation(&mut self) -> Result<OperationMode, FlashspanError> {
+        match self.byte()? {
+            0 => Ok(OperationMode::Addition),
+            1 => Ok(OperationMode::Multiplication),
+            2 => Ok(OperationMode::Division),
+            op => Err(invalid_file(format!("unknown operation {}", op))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::engine::build_session_plan;
+    use crate::core::types::{CheckpointSchedule, SessionConfigInput};
+    use crate::core::validate::normalize_session_config;
+
+    fn plan(input: SessionConfigInput) -> SessionPlan {
+        let (config, effective) = normalize_session_config(input);
+        build_session_plan(7, config, effective, Some(99))
+    }
+
+    fn addition() -> SessionPlan {
+        plan(SessionConfigInput {
+            digits_per_number: 3,
+            number_duration_s: 0.4,
+            delay_between_numbers_s: 0.1,
+            total_numbers: 12,
+            allow_negative_numbers: true,
+            decimal_places: 1,
+            checkpoints: CheckpointSchedule::After {
+                positions: vec![4, 8],
+            },
+            ..Default::default()
+        })
+    }
+
+    fn multiplication() -> SessionPlan {
+        plan(SessionConfigInput {
+            digits_per_number: 2,
+            number_duration_s: 1.0,
+            delay_between_numbers_s: 0.2,
+            total_numbers: 4,
+            operation: OperationMode::Multiplication,
+            second_operand_digits: 1,
+            ..Default::default()
+        })
+    }
+
+    fn same(a: &SessionPlan, b: &SessionPlan) -> bool {
+        serde_json::to_value(a).unwrap() == serde_json::to_value(b).unwrap()
+    }
+
+    #[test]
+    fn both_forms_round_trip() {
+        for original in [addition(), multiplication()] {
+            verify(&original).unwrap();
+
+            let json = encode_json(&original).unwrap();
+            assert!(same(&decode(json.as_bytes()).unwrap(), &original));
+
+            let binary = encode_binary(&original).unwrap();
+            assert!(binary.starts_with(BINARY_MAGIC));
+            assert!(binary.len() < json.len() / 4);
+            assert!(same(&decode(&binary).unwrap(), &original));
+        }
+    }
+
+    #[test]
+    fn tampered_plans_are_rejected() {
+        let original = addition();
+
+        let mut wrong_sum = original.clone();
+        wrong_sum.expected_sum += 1;
+        assert!(matches!(
+            verify(&wrong_sum),
+            Err(FlashspanError::InvalidPlan { .. })
+        ));
+
+        let mut wrong_duration = original.clone();
+        wrong_duration.total_duration_ms += 1;
+        assert!(verify(&wrong_duration).is_err());
+
+        let mut swapped = original.clone();
+        swapped.numbers_generated.swap(0, 1);
+        assert!(verify(&swapped).is_err());
+
+        let mut edited_step = original.clone();
+        if let Some(SessionStep::ShowNumber { value, .. }) = edited_step.steps.get_mut(4) {
+            *value += 1;
+        }
+        assert!(verify(&edited_step).is_err());
+
+        let mut wrong_subtotal = original.clone();
+        for step in &mut wrong_subtotal.steps {
+            if let SessionStep::Checkpoint { expected_sum, .. } = step {
+                *expected_sum += 1;
+            }
+        }
+        assert!(verify(&wrong_subtotal).is_err());
+
+        let mut wrong_answer = multiplication();
+        wrong_answer.pairs_generated[0].answer += 1;
+        if let Some(SessionStep::Complete { pairs, .. }) = wrong_answer.steps.last_mut() {
+            pairs[0].answer += 1;
+        }
+        assert!(verify(&wrong_answer).is_err());
+    }
+
+    #[test]
+    fn unknown_files_and_newer_versions_are_rejected() {
+        let json = encode_json(&addition()).unwrap();
+        let newer = json.replacen("\"version\": 3", "\"version\": 4", 1);
+        assert!(matches!(
+            decode(newer.as_bytes()),
+            Err(FlashspanError::UnsupportedVersion { found: 4, .. })
+        ));
+        assert!(decode(b"{\"presets\": []}").is_err());
+        assert!(decode(b"FSPL").is_err());
+
+        let mut binary = encode_binary(&addition()).unwrap();
+        binary.pop();
+        assert!(decode(&binary).is_err());
+    }
+
+    #[test]
+    fn checkpoint_plans_are_version_3() {
+        let json = encode_json(&addition()).unwrap();
+        assert!(json.contains("\"version\": 3"));
+        // A reader that only knows version 2 refuses them instead of misreading tag 5.
+        assert!(matches!(
+            check_version(3, 2),
+            Err(FlashspanError::UnsupportedVersion {
+                found: 3,
+                supported: 2
+            })
+        ));
+        assert!(check_version(2, PLAN_FILE_VERSION).is_ok());
+    }
+
+    #[test]
+    fn with_session_id_restamps_every_step() {
+        let plan = addition().with_session_id(42);
+        assert_eq!(plan.session_id, 42);
+        verify(&plan).unwrap();
+    }
+}
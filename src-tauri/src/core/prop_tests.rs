@@ -1,5 +1,10 @@
 // Property-based tests using proptest for determinism and bounds checking
-use super::types::{SessionConfig, SessionConfigInput};
+use super::carry::carry_columns;
+use super::engine::build_session_plan;
+use super::formula::term_formulas;
+use super::types::{
+    CarryPolicy, DigitRangeInput, FormulaMode, SessionConfig, SessionConfigInput, SessionStep,
+};
 use super::validate::{normalize_session_config, validate_config};
 use proptest::prelude::*;
 
@@ -12,6 +17,7 @@ fn prop_normalize_digits_in_bounds() {
             delay_between_numbers_s: 0.0,
             total_numbers: 5,
             allow_negative_numbers: false,
+            ..Default::default()
         };
         let (config, _effective) = normalize_session_config(input);
 
@@ -30,6 +36,7 @@ fn prop_normalize_total_numbers_in_bounds() {
             delay_between_numbers_s: 0.0,
             total_numbers: total,
             allow_negative_numbers: false,
+            ..Default::default()
         };
         let (config, _effective) = normalize_session_config(input);
 
@@ -52,6 +59,7 @@ fn prop_normalize_duration_in_bounds() {
             delay_between_numbers_s: 0.0,
             total_numbers: 5,
             allow_negative_numbers: false,
+            ..Default::default()
         };
         let (config, _effective) = normalize_session_config(input);
 
@@ -74,6 +82,7 @@ fn prop_normalize_delay_in_bounds() {
             delay_between_numbers_s: delay_s,
             total_numbers: 5,
             allow_negative_numbers: false,
+            ..Default::default()
         };
         let (config, _effective) = normalize_session_config(input);
 
@@ -96,6 +105,7 @@ fn prop_normalize_idempotent() {
             delay_between_numbers_s: delay_s,
             total_numbers: total,
             allow_negative_numbers: false,
+            ..Default::default()
         };
 
         let (config1, _) = normalize_session_config(input.clone());
@@ -123,6 +133,7 @@ fn prop_validate_accepts_valid_configs() {
             delay_between_numbers_ms: delay_ms,
             total_numbers: total,
             allow_negative_numbers: false,
+            ..Default::default()
         };
 
         // Valid configs within bounds should always validate
@@ -140,6 +151,7 @@ fn prop_effective_duration_round_1_decimal() {
             delay_between_numbers_s: 0.0,
             total_numbers: 5,
             allow_negative_numbers: false,
+            ..Default::default()
         };
 
         let (_config, effective) = normalize_session_config(input);
@@ -159,6 +171,7 @@ fn prop_allow_negative_flag_preserved() {
             delay_between_numbers_s: 0.0,
             total_numbers: 5,
             allow_negative_numbers: allow_neg,
+            ..Default::default()
         };
 
         let (_config, effective) = normalize_session_config(input);
@@ -180,6 +193,7 @@ fn prop_duration_monotonic() {
             delay_between_numbers_s: 0.0,
             total_numbers: 5,
             allow_negative_numbers: false,
+            ..Default::default()
         };
 
         let input2 = SessionConfigInput {
@@ -188,6 +202,7 @@ fn prop_duration_monotonic() {
             delay_between_numbers_s: 0.0,
             total_numbers: 5,
             allow_negative_numbers: false,
+            ..Default::default()
         };
 
         let (config1, _) = normalize_session_config(input1);
@@ -213,6 +228,7 @@ fn prop_nan_duration_clamps_to_min() {
             delay_between_numbers_s: 0.0,
             total_numbers: total as i64,
             allow_negative_numbers: false,
+            ..Default::default()
         };
 
         let (config, _) = normalize_session_config(input);
@@ -231,6 +247,7 @@ fn prop_infinity_duration_clamps_to_max() {
             delay_between_numbers_s: 0.0,
             total_numbers: 5,
             allow_negative_numbers: false,
+            ..Default::default()
         };
 
         let (config, _) = normalize_session_config(input);
@@ -239,3 +256,165 @@ fn prop_infinity_duration_clamps_to_max() {
         prop_assert_eq!(config.number_duration_ms, 60_000);
     });
 }
+
+fn formula_mode_strategy() -> impl Strategy<Value = FormulaMode> {
+    prop_oneof![
+        Just(FormulaMode::Direct),
+        Just(FormulaMode::FiveComplement),
+        Just(FormulaMode::TenComplement),
+        Just(FormulaMode::Mixed),
+    ]
+}
+
+#[test]
+fn prop_formula_mode_terms_use_only_allowed_formulas() {
+    proptest!(|
+        (mode in formula_mode_strategy(),
+         digits in 1i64..7,
+         total in 1i64..41,
+         allow_neg in any::<bool>(),
+         seed in any::<u64>())
+    | {
+        let input = SessionConfigInput {
+            digits_per_number: digits,
+            number_duration_s: 0.5,
+            delay_between_numbers_s: 0.0,
+            total_numbers: total,
+            allow_negative_numbers: allow_neg,
+            formula_mode: mode,
+            ..Default::default()
+        };
+        let (config, effective) = normalize_session_config(input);
+        prop_assert!(validate_config(&config).is_ok());
+
+        let plan = build_session_plan(1, config, effective, Some(seed));
+        prop_assert_eq!(plan.numbers_generated.len(), total as usize);
+        prop_assert!(plan.numbers_generated[0] >= 0, "first number must be non-negative");
+
+        let mut running_sum: i128 = 0;
+        for (idx, &term) in plan.numbers_generated.iter().enumerate() {
+            prop_assert_eq!(
+                term.unsigned_abs().to_string().len(),
+                digits as usize,
+                "term {} has the wrong width",
+                term
+            );
+
+            let used = term_formulas(running_sum, term as i128);
+            prop_assert!(used.is_some(), "term {} at index {} drove the sum below zero", term, idx);
+            for formula in used.unwrap() {
+                prop_assert!(
+                    mode.allows(formula),
+                    "{:?} used {:?} for {} on {} at index {}",
+                    mode, formula, term, running_sum, idx
+                );
+            }
+
+            running_sum += term as i128;
+        }
+    });
+}
+
+#[test]
+fn prop_restricted_formula_modes_enable_negatives() {
+    proptest!(|(mode in formula_mode_strategy())| {
+        let input = SessionConfigInput {
+            digits_per_number: 2,
+            number_duration_s: 1.0,
+            delay_between_numbers_s: 0.0,
+            total_numbers: 5,
+            allow_negative_numbers: false,
+            formula_mode: mode,
+            ..Default::default()
+        };
+
+        let (config, effective) = normalize_session_config(input);
+
+        prop_assert_eq!(effective.formula_mode, mode);
+        prop_assert_eq!(config.allow_negative_numbers, mode.requires_negative_numbers());
+        prop_assert!(validate_config(&config).is_ok());
+    });
+}
+
+fn capping_carry_policy_strategy() -> impl Strategy<Value = CarryPolicy> {
+    prop_oneof![
+        Just(CarryPolicy::NoCarries),
+        (0u32..4).prop_map(|max| CarryPolicy::AtMost { max }),
+    ]
+}
+
+#[test]
+fn prop_carry_policy_caps_hold_and_invariants_are_kept() {
+    proptest!(|
+        (policy in capping_carry_policy_strategy(),
+         digits in 1i64..8,
+         total in 1i64..41,
+         seed in any::<u64>())
+    | {
+        let input = SessionConfigInput {
+            digits_per_number: digits,
+            number_duration_s: 0.5,
+            delay_between_numbers_s: 0.0,
+            total_numbers: total,
+            allow_negative_numbers: true,
+            carry_policy: policy,
+            ..Default::default()
+        };
+        let (config, effective) = normalize_session_config(input);
+        prop_assert!(validate_config(&config).is_ok());
+        let policy = config.carry_policy;
+
+        let plan = build_session_plan(1, config, effective, Some(seed));
+        prop_assert!(plan.numbers_generated[0] >= 0, "first number must be non-negative");
+
+        let mut running_sum: i128 = 0;
+        let mut previous: Option<i64> = None;
+        for step in &plan.steps {
+            let SessionStep::ShowNumber { value, carries, .. } = step else {
+                continue;
+            };
+            prop_assert_ne!(previous, Some(*value), "consecutive duplicate {}", value);
+
+            let columns = carry_columns(running_sum, *value as i128);
+            prop_assert_eq!(*carries, columns.len() as u32);
+            prop_assert!(policy.accepts(&columns), "{:?} rejected {} on {}", policy, value, running_sum);
+
+            running_sum += *value as i128;
+            prop_assert!(running_sum >= 0, "running sum went negative");
+            previous = Some(*value);
+        }
+    });
+}
+
+#[test]
+fn prop_digit_range_terms_stay_within_range() {
+    proptest!(|
+        // 30 terms of up to 16 digits keep the sum within i64.
+        (a in 1i64..17,
+         b in 1i64..17,
+         allow_neg in any::<bool>(),
+         seed in any::<u64>())
+    | {
+        let input = SessionConfigInput {
+            digits_per_number: 1,
+            number_duration_s: 0.5,
+            delay_between_numbers_s: 0.0,
+            total_numbers: 30,
+            allow_negative_numbers: allow_neg,
+            digit_range: Some(DigitRangeInput { min: a, max: b }),
+            ..Default::default()
+        };
+        let (config, effective) = normalize_session_config(input);
+        prop_assert!(validate_config(&config).is_ok());
+
+        let (min, max) = (a.min(b) as usize, a.max(b) as usize);
+        let plan = build_session_plan(1, config, effective, Some(seed));
+        let mut running_sum: i128 = 0;
+        for &term in &plan.numbers_generated {
+            let width = term.unsigned_abs().to_string().len();
+            prop_assert!((min..=max).contains(&width), "{} is not {}-{} digits", term, min, max);
+            running_sum += term as i128;
+            prop_assert!(running_sum >= 0);
+        }
+    });
+}
@@ -0,0 +1,397 @@
+//! LAN race mode: the messages a race host and its players exchange, and the leaderboard the
+//! host keeps. Networking lives in the native `race` module; this part is plain data.
+//!
+//! Every message is one JSON text frame tagged by `type`. A player sends `join` once, then
+//! an `answer` per round, and a `checkpoint` whenever it answers one; the host replies with
+//! `welcome`, sends each round's plan as `round`, answers each answer with a `verdict`, and
+//! broadcasts the `leaderboard` whenever it changes.
+//!
+//! Round plans travel [`without_answers`]; players work the sums out from the numbers with
+//! [`with_answers`], and only the host's copy is used to judge answers.
+
+use super::answer::{Submission, ValidationResult};
+use super::error::FlashspanError;
+use super::types::{OperationMode, SessionPlan, SessionStep};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+
+/// Bump when a message changes shape; hosts refuse players on another version.
+pub const RACE_PROTOCOL_VERSION: u32 = 3;
+pub const MAX_PLAYER_NAME_CHARS: usize = 24;
+pub const MAX_PLAYERS: usize = 64;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    Join {
+        name: String,
+        version: u32,
+    },
+    Answer {
+        round: u64,
+        submission: Submission,
+    },
+    /// Checkpoint `index` of the round was just answered; the host times the wait.
+    Checkpoint {
+        round: u64,
+        index: u32,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum HostMessage {
+    Welcome {
+        player_id: u32,
+    },
+    /// The plan to run now. Its `session_id` is the round number.
+    Round {
+        round: u64,
+        plan: SessionPlan,
+    },
+    Verdict {
+        round: u64,
+        validation: ValidationResult,
+        answer_ms: u64,
+        /// Points under the host's scoring weights.
+        score: u32,
+    },
+    Leaderboard {
+        leaderboard: Leaderboard,
+    },
+    /// A `join` or `answer` the host refused; `reason` is ready to show.
+    Rejected {
+        reason: String,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RaceAnswer {
+    pub correct: bool,
+    /// From the end of the last flash to the answer reaching the host.
+    pub answer_ms: u64,
+    pub score: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RaceStanding {
+    pub player_id: u32,
+    pub name: String,
+    /// Answer to the current round, if given.
+    pub answer: Option<RaceAnswer>,
+    pub rounds_correct: u32,
+    /// Points over every round answered.
+    pub total_score: u64,
+}
+
+/// Players ranked for the current round: correct answers by score, then by answer time, then
+/// wrong answers by answer time, then players still thinking.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Leaderboard {
+    pub round: Option<u64>,
+    pub standings: Vec<RaceStanding>,
+    #[serde(skip)]
+    next_player_id: u32,
+}
+
+impl Leaderboard {
+    /// Add a player. Names are trimmed and must be unique, ignoring case.
+    pub fn join(&mut self, name: &str) -> Result<u32, FlashspanError> {
+        let name = name.trim();
+        if name.is_empty() || name.chars().count() > MAX_PLAYER_NAME_CHARS {
+            return Err(FlashspanError::InvalidName {
+                reason: format!(
+                    "player names must be 1 to {} characters",
+                    MAX_PLAYER_NAME_CHARS
+                ),
+            });
+        }
+        if self
+            .standings
+            .iter()
+            .any(|s| s.name.to_lowercase() == name.to_lowercase())
+        {
+            return Err(FlashspanError::NameTaken {
+                name: name.to_string(),
+            });
+        }
+        if self.standings.len() >= MAX_PLAYERS {
+            return Err(FlashspanError::LimitReached {
+                what: "players",
+                max: MAX_PLAYERS,
+            });
+        }
+
+        self.next_player_id += 1;
+        self.standings.push(RaceStanding {
+            player_id: self.next_player_id,
+            name: name.to_string(),
+            answer: None,
+            rounds_correct: 0,
+            total_score: 0,
+        });
+        self.rank();
+        Ok(self.next_player_id)
+    }
+
+    pub fn leave(&mut self, player_id: u32) {
+        self.standings.retain(|s| s.player_id != player_id);
+    }
+
+    /// Open `round`, clearing everyone's answer to the last one.
+    pub fn start_round(&mut self, round: u64) {
+        self.round = Some(round);
+        for standing in &mut self.standings {
+            standing.answer = None;
+        }
+        self.rank();
+    }
+
+    /// Record a player's answer to the open round. Each player answers a round once.
+    pub fn record(
+        &mut self,
+        player_id: u32,
+        round: u64,
+        answer: RaceAnswer,
+    ) -> Result<(), FlashspanError> {
+        if self.round != Some(round) {
+            return Err(FlashspanError::race(format!("round {} is over", round)));
+        }
+        let standing = self
+            .standings
+            .iter_mut()
+            .find(|s| s.player_id == player_id)
+            .ok_or_else(|| FlashspanError::race("you are not in this race"))?;
+        if standing.answer.is_some() {
+            return Err(FlashspanError::race(format!(
+                "you already answered round {}",
+                round
+            )));
+        }
+
+        standing.answer = Some(answer);
+        standing.total_score += answer.score as u64;
+        if answer.correct {
+            standing.rounds_correct += 1;
+        }
+        self.rank();
+        Ok(())
+    }
+
+    fn rank(&mut self) {
+        self.standings.sort_by_key(|s| match s.answer {
+            Some(a) if a.correct => (0, Reverse(a.score), a.answer_ms, s.player_id),
+            Some(a) => (1, Reverse(0), a.answer_ms, s.player_id),
+            None => (2, Reverse(0), 0, s.player_id),
+        });
+    }
+}
+
+/// `plan` with running sums, checkpoint subtotals, the total and pair answers zeroed, for
+/// sending to players.
+pub fn without_answers(plan: &SessionPlan) -> SessionPlan {
+    let mut plan = plan.clone();
+    plan.expected_sum = 0;
+    for pair in &mut plan.pairs_generated {
+        pair.answer = 0;
+    }
+    for step in &mut plan.steps {
+        match step {
+            SessionStep::ShowNumber { running_sum, .. } => *running_sum = 0,
+            SessionStep::Checkpoint { expected_sum, .. } => *expected_sum = 0,
+            SessionStep::Complete { sum, pairs, .. } => {
+                *sum = 0;
+                for pair in pairs {
+                    pair.answer = 0;
+                }
+            }
+            SessionStep::CountdownTick { .. }
+            | SessionStep::ClearScreen { .. }
+            | SessionStep::ShowPair { .. } => {}
+        }
+    }
+    plan
+}
+
+/// Fill in what [`without_answers`] removed, from the flashed numbers and pairs. Sums that
+/// overflow are left at 0, so the plan fails verification instead of running.
+pub fn with_answers(mut plan: SessionPlan) -> SessionPlan {
+    let sum = plan
+        .numbers_generated
+        .iter()
+        .try_fold(0i64, |acc, &n| acc.checked_add(n))
+        .unwrap_or(0);
+    plan.expected_sum = sum;
+    for pair in &mut plan.pairs_generated {
+        pair.answer = match pair.operation {
+            OperationMode::Multiplication => pair.left.checked_mul(pair.right),
+            OperationMode::Division => pair.left.checked_div(pair.right),
+            OperationMode::Addition => None,
+        }
+        .unwrap_or(0);
+    }
+
+    // Mirrors the engine: the shown running sum never drops below zero.
+    let mut running_sum: i128 = 0;
+    let mut subtotal: i128 = 0;
+    let fit = |value: i128| i64::try_from(value).unwrap_or(0);
+    for step in &mut plan.steps {
+        match step {
+            SessionStep::ShowNumber {
+                value,
+                running_sum: shown,
+                ..
+            } => {
+                subtotal += *value as i128;
+                running_sum = (running_sum + *value as i128).max(0);
+                *shown = fit(running_sum);
+            }
+            SessionStep::Checkpoint { expected_sum, .. } => *expected_sum = fit(subtotal),
+            SessionStep::Complete {
+                sum: complete_sum,
+                pairs,
+                ..
+            } => {
+                *complete_sum = sum;
+                pairs.clone_from(&plan.pairs_generated);
+            }
+            SessionStep::CountdownTick { .. }
+            | SessionStep::ClearScreen { .. }
+            | SessionStep::ShowPair { .. } => {}
+        }
+    }
+    plan
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn answer(correct: bool, answer_ms: u64) -> RaceAnswer {
+        RaceAnswer {
+            correct,
+            answer_ms,
+            score: if correct { 1_000 } else { 0 },
+        }
+    }
+
+    #[test]
+    fn ranks_correct_then_wrong_then_unanswered_by_time() {
+        let mut board = Leaderboard::default();
+        let ada = board.join("Ada").unwrap();
+        let bo = board.join(" Bo ").unwrap();
+        let cy = board.join("Cy").unwrap();
+        let di = board.join("Di").unwrap();
+        assert!(matches!(
+            board.join("ada"),
+            Err(FlashspanError::NameTaken { .. })
+        ));
+        assert!(matches!(
+            board.join("  "),
+            Err(FlashspanError::InvalidName { .. })
+        ));
+
+        board.start_round(1);
+        board.record(cy, 1, answer(false, 900)).unwrap();
+        board.record(bo, 1, answer(true, 2_500)).unwrap();
+        board.record(di, 1, answer(true, 1_200)).unwrap();
+        let order: Vec<&str> = board.standings.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(order, ["Di", "Bo", "Cy", "Ada"]);
+
+        assert!(board.record(bo, 1, answer(true, 3_000)).is_err());
+        assert!(board.record(ada, 2, answer(true, 100)).is_err());
+
+        // A higher score outranks a faster answer.
+        board.start_round(2);
+        let mut slow_but_harder = answer(true, 4_000);
+        slow_but_harder.score = 1_500;
+        board.record(bo, 2, slow_but_harder).unwrap();
+        board.record(di, 2, answer(true, 1_000)).unwrap();
+        let order: Vec<&str> = board.standings.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(order, ["Bo", "Di", "Ada", "Cy"]);
+        assert_eq!(board.standings[0].total_score, 2_500);
+
+        board.start_round(3);
+        assert!(board.standings.iter().all(|s| s.answer.is_none()));
+        board.record(ada, 3, answer(true, 100)).unwrap();
+        board.leave(cy);
+        let rounds: Vec<(&str, u32)> = board
+            .standings
+            .iter()
+            .map(|s| (s.name.as_str(), s.rounds_correct))
+            .collect();
+        assert_eq!(rounds, [("Ada", 1), ("Bo", 2), ("Di", 2)]);
+    }
+
+    #[test]
+    fn messages_are_tagged_json() {
+        let join = ClientMessage::Join {
+            name: "Ada".to_string(),
+            version: RACE_PROTOCOL_VERSION,
+        };
+        let text = serde_json::to_string(&join).unwrap();
+        assert_eq!(text, r#"{"type":"join","name":"Ada","version":3}"#);
+        assert_eq!(serde_json::from_str::<ClientMessage>(&text).unwrap(), join);
+
+        let answer: ClientMessage =
+            serde_json::from_str(r#"{"type":"answer","round":3,"submission":{"provided_sum":42}}"#)
+                .unwrap();
+        assert_eq!(
+            answer,
+            ClientMessage::Answer {
+                round: 3,
+                submission: Submission {
+                    provided_sum: Some(42),
+                    provided_answers: None,
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn round_plans_travel_without_answers() {
+        use crate::core::engine::build_session_plan;
+        use crate::core::plan_file;
+        use crate::core::types::{CheckpointSchedule, SessionConfigInput};
+        use crate::core::validate::normalize_session_config;
+
+        let plan_for = |input: SessionConfigInput| {
+            let (config, effective) = normalize_session_config(input);
+            build_session_plan(1, config, effective, Some(11))
+        };
+        let plans = [
+            plan_for(SessionConfigInput {
+                digits_per_number: 2,
+                total_numbers: 6,
+                allow_negative_numbers: true,
+                checkpoints: CheckpointSchedule::Every { every: 2 },
+                ..Default::default()
+            }),
+            plan_for(SessionConfigInput {
+                digits_per_number: 2,
+                total_numbers: 3,
+                operation: OperationMode::Multiplication,
+                ..Default::default()
+            }),
+        ];
+
+        for plan in plans {
+            let sent = without_answers(&plan);
+            assert_eq!(sent.expected_sum, 0);
+            assert!(sent.pairs_generated.iter().all(|p| p.answer == 0));
+            assert!(sent.steps.iter().all(|step| match step {
+                SessionStep::ShowNumber { running_sum, .. } => *running_sum == 0,
+                SessionStep::Checkpoint { expected_sum, .. } => *expected_sum == 0,
+                SessionStep::Complete { sum, .. } => *sum == 0,
+                _ => true,
+            }));
+
+            let restored = with_answers(sent);
+            plan_file::verify(&restored).unwrap();
+            assert_eq!(
+                serde_json::to_value(&restored).unwrap(),
+                serde_json::to_value(&plan).unwrap()
+            );
+        }
+    }
+}
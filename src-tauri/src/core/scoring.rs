@@ -0,0 +1,226 @@
+//! Points for an answered session.
+//!
+//! A correct answer earns [`ScoringConfig::base_points`], scaled up for wider numbers, more
+//! numbers and a faster pace, and scaled down the longer the answer took. In multiplication
+//! and division sessions each correct pair earns its share. The weights are saved with the
+//! settings, so each profile can tune them.
+
+use super::answer::ValidationResult;
+use super::error::FlashspanError;
+use super::types::SessionConfigEffective;
+use super::validate::normalize_timing_schedule;
+use serde::{Deserialize, Serialize};
+
+pub const MAX_BASE_POINTS: u32 = 10_000;
+pub const MAX_BONUS_PCT: u32 = 1_000;
+pub const MAX_REFERENCE_PACE_MS: u32 = 60_000;
+pub const MAX_LATENCY_MS: u32 = 600_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScoringConfig {
+    /// Points for a correct answer to a single 1-digit number flashed at the reference pace.
+    pub base_points: u32,
+    /// Extra points per digit past the first, in percent of the base.
+    pub digit_bonus_pct: u32,
+    /// Extra points per number past the first, in percent of the base.
+    pub number_bonus_pct: u32,
+    /// Time per number (flash plus gap) that scores at par; twice as fast scores double.
+    pub reference_pace_ms: u32,
+    /// Answers within this long of the last flash lose nothing.
+    pub latency_grace_ms: u32,
+    /// Past the grace period, points halve every this long; 0 ignores answer time.
+    pub latency_half_life_ms: u32,
+}
+
+impl Default for ScoringConfig {
+    fn default() -> Self {
+        Self {
+            base_points: 100,
+            digit_bonus_pct: 50,
+            number_bonus_pct: 10,
+            reference_pace_ms: 1_000,
+            latency_grace_ms: 2_000,
+            latency_half_life_ms: 10_000,
+        }
+    }
+}
+
+impl ScoringConfig {
+    pub fn validate(&self) -> Result<(), FlashspanError> {
+        let limits = [
+            ("base_points", self.base_points, 0, MAX_BASE_POINTS),
+            ("digit_bonus_pct", self.digit_bonus_pct, 0, MAX_BONUS_PCT),
+            ("number_bonus_pct", self.number_bonus_pct, 0, MAX_BONUS_PCT),
+            (
+                "reference_pace_ms",
+                self.reference_pace_ms,
+                1,
+                MAX_REFERENCE_PACE_MS,
+            ),
+            ("latency_grace_ms", self.latency_grace_ms, 0, MAX_LATENCY_MS),
+            (
+                "latency_half_life_ms",
+                self.latency_half_life_ms,
+                0,
+                MAX_LATENCY_MS,
+            ),
+        ];
+        for (field, value, min, max) in limits {
+            if !(min..=max).contains(&value) {
+                return Err(FlashspanError::out_of_range(field, min, max));
+            }
+        }
+        Ok(())
+    }
+
+    /// Bring each weight into range, e.g. after reading a hand-edited settings file.
+    pub fn clamped(self) -> Self {
+        Self {
+            base_points: self.base_points.min(MAX_BASE_POINTS),
+            digit_bonus_pct: self.digit_bonus_pct.min(MAX_BONUS_PCT),
+            number_bonus_pct: self.number_bonus_pct.min(MAX_BONUS_PCT),
+            reference_pace_ms: self.reference_pace_ms.clamp(1, MAX_REFERENCE_PACE_MS),
+            latency_grace_ms: self.latency_grace_ms.min(MAX_LATENCY_MS),
+            latency_half_life_ms: self.latency_half_life_ms.min(MAX_LATENCY_MS),
+        }
+    }
+
+    /// Points for `validation`, the answer to a session that ran `config` and flashed
+    /// `numbers`. Pairs are read from the validation. Without an `answer_ms` the answer
+    /// counts as immediate.
+    pub fn score(
+        &self,
+        config: &SessionConfigEffective,
+        numbers: &[i64],
+        validation: &ValidationResult,
+    ) -> u32 {
+        let pairs = &validation.pairs;
+        let (correctness, count, terms): (f64, usize, Vec<i64>) = if pairs.is_empty() {
+            let correctness = if validation.correct { 1.0 } else { 0.0 };
+            (correctness, numbers.len(), numbers.to_vec())
+        } else {
+            let correct = pairs.iter().filter(|p| p.correct).count();
+            (
+                correct as f64 / pairs.len() as f64,
+                pairs.len(),
+                pairs.iter().flat_map(|p| [p.left, p.right]).collect(),
+            )
+        };
+        if correctness == 0.0 || terms.is_empty() {
+            return 0;
+        }
+
+        let digits = terms.iter().map(|&n| digit_count(n)).sum::<u32>() as f64 / terms.len() as f64;
+        let bonus = |pct: u32, extra: f64| 1.0 + pct as f64 / 100.0 * extra;
+
+        let speed = self.reference_pace_ms as f64 / pace_ms(config);
+        let latency = match (validation.answer_ms, self.latency_half_life_ms) {
+            (Some(answer_ms), half_life) if half_life > 0 => {
+                let late_ms = answer_ms.saturating_sub(self.latency_grace_ms as u64);
+                0.5f64.powf(late_ms as f64 / half_life as f64)
+            }
+            _ => 1.0,
+        };
+
+        let points = self.base_points as f64
+            * correctness
+            * bonus(self.digit_bonus_pct, digits - 1.0)
+            * bonus(self.number_bonus_pct, count as f64 - 1.0)
+            * speed
+            * latency;
+        // `as` saturates, so a huge score stops at u32::MAX.
+        points.round() as u32
+    }
+}
+
+/// Mean time per number, flash plus gap, over the whole session.
+fn pace_ms(config: &SessionConfigEffective) -> f64 {
+    let (schedule, _) = normalize_timing_schedule(&config.timing_schedule);
+    let base_ms = (config.number_duration_s * 1000.0).round().max(1.0) as u64;
+    let delay_ms = (config.delay_between_numbers_s * 1000.0).round().max(0.0);
+    let total = config.total_numbers.max(1);
+    let flashing_ms: u64 = (0..total)
+        .map(|i| schedule.duration_ms(base_ms, i, total))
+        .sum();
+    (flashing_ms as f64 / total as f64 + delay_ms).max(1.0)
+}
+
+/// Digits in `n`, fraction digits included; 0 has one.
+fn digit_count(n: i64) -> u32 {
+    n.unsigned_abs().checked_ilog10().unwrap_or(0) + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::types::{OperandPair, OperationMode};
+
+    fn config(total_numbers: u32, number_duration_s: f64) -> SessionConfigEffective {
+        SessionConfigEffective {
+            digits_per_number: 2,
+            number_duration_s,
+            total_numbers,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn scales_with_digits_count_pace_and_latency() {
+        let scoring = ScoringConfig::default();
+        let numbers = [12, -34, 56];
+        let mut validation = ValidationResult::for_sum(34, 34, 0);
+
+        // 100 × 1.5 (two digits) × 1.2 (three numbers) at the reference pace.
+        assert_eq!(scoring.score(&config(3, 1.0), &numbers, &validation), 180);
+        assert_eq!(scoring.score(&config(3, 0.5), &numbers, &validation), 360);
+
+        validation.answer_ms = Some(2_000);
+        assert_eq!(scoring.score(&config(3, 1.0), &numbers, &validation), 180);
+        validation.answer_ms = Some(12_000);
+        assert_eq!(scoring.score(&config(3, 1.0), &numbers, &validation), 90);
+
+        let untimed = ScoringConfig {
+            latency_half_life_ms: 0,
+            ..scoring
+        };
+        assert_eq!(untimed.score(&config(3, 1.0), &numbers, &validation), 180);
+
+        let wrong = ValidationResult::for_sum(34, 35, 0);
+        assert_eq!(scoring.score(&config(3, 1.0), &numbers, &wrong), 0);
+    }
+
+    #[test]
+    fn pairs_earn_their_share() {
+        let pairs: Vec<OperandPair> = [(3, 4), (5, 6)]
+            .iter()
+            .map(|&(left, right)| OperandPair {
+                operation: OperationMode::Multiplication,
+                left,
+                right,
+                answer: left * right,
+            })
+            .collect();
+        let validation = ValidationResult::for_pairs(&pairs, &[12, 31]).unwrap();
+        // Half right: 100 × 0.5 × 1.1 (two pairs) at one digit.
+        let score = ScoringConfig::default().score(&config(2, 1.0), &[], &validation);
+        assert_eq!(score, 55);
+    }
+
+    #[test]
+    fn validates_and_clamps_weights() {
+        assert!(ScoringConfig::default().validate().is_ok());
+        let zero_pace = ScoringConfig {
+            reference_pace_ms: 0,
+            ..Default::default()
+        };
+        assert!(matches!(
+            zero_pace.validate(),
+            Err(FlashspanError::ConfigOutOfRange {
+                field: "reference_pace_ms",
+                ..
+            })
+        ));
+        assert_eq!(zero_pace.clamped().reference_pace_ms, 1);
+    }
+}
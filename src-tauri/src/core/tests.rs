@@ -2,8 +2,16 @@
 #[cfg(test)]
 #[allow(clippy::module_inception)]
 mod tests {
-    use crate::core::types::{SessionConfig, SessionConfigInput};
-    use crate::core::validate::{normalize_session_config, validate_config};
+    use crate::core::dictation::DictationLanguage;
+    use crate::core::error::FlashspanError;
+    use crate::core::types::{
+        AdaptivePolicyInput, AutoRepeatConfigInput, CarryPolicy, DigitRange, DigitRangeInput,
+        FormulaMode, OperationMode, SeedStrategy, SessionConfig, SessionConfigInput,
+        TimingSchedule, TimingScheduleMs,
+    };
+    use crate::core::validate::{
+        normalize_adaptive_policy, normalize_auto_repeat, normalize_session_config, validate_config,
+    };
 
     // ======================
     // Validation Module Tests
@@ -18,6 +26,7 @@ mod tests {
             delay_between_numbers_s: 0.0,
             total_numbers: 5,
             allow_negative_numbers: false,
+            ..Default::default()
         };
         let (config, _effective) = normalize_session_config(input);
         assert_eq!(
@@ -32,6 +41,7 @@ mod tests {
             delay_between_numbers_s: 0.0,
             total_numbers: 5,
             allow_negative_numbers: false,
+            ..Default::default()
         };
         let (config_high, _) = normalize_session_config(input_high);
         assert_eq!(
@@ -49,6 +59,7 @@ mod tests {
             delay_between_numbers_s: 0.0,
             total_numbers: 0,
             allow_negative_numbers: false,
+            ..Default::default()
         };
         let (config, _) = normalize_session_config(input);
         assert_eq!(
@@ -63,6 +74,7 @@ mod tests {
             delay_between_numbers_s: 0.0,
             total_numbers: 20_000,
             allow_negative_numbers: false,
+            ..Default::default()
         };
         let (config_high, _) = normalize_session_config(input_high);
         assert_eq!(
@@ -80,6 +92,7 @@ mod tests {
             delay_between_numbers_s: 0.0,
             total_numbers: 5,
             allow_negative_numbers: false,
+            ..Default::default()
         };
         let (config, effective) = normalize_session_config(input);
         assert!(
@@ -98,6 +111,7 @@ mod tests {
             delay_between_numbers_s: 0.0,
             total_numbers: 5,
             allow_negative_numbers: false,
+            ..Default::default()
         };
         let (config_large, effective_large) = normalize_session_config(input_large);
         assert_eq!(
@@ -119,6 +133,7 @@ mod tests {
             delay_between_numbers_s: 0.0,
             total_numbers: 5,
             allow_negative_numbers: false,
+            ..Default::default()
         };
         let (config_nan, _) = normalize_session_config(input_nan);
         assert_eq!(
@@ -133,6 +148,7 @@ mod tests {
             delay_between_numbers_s: 0.0,
             total_numbers: 5,
             allow_negative_numbers: false,
+            ..Default::default()
         };
         let (config_inf, _) = normalize_session_config(input_inf);
         assert_eq!(
@@ -147,6 +163,7 @@ mod tests {
             delay_between_numbers_s: 0.0,
             total_numbers: 5,
             allow_negative_numbers: false,
+            ..Default::default()
         };
         let (config_neginf, _) = normalize_session_config(input_neginf);
         assert_eq!(
@@ -164,6 +181,7 @@ mod tests {
             delay_between_numbers_s: 2.567,
             total_numbers: 5,
             allow_negative_numbers: false,
+            ..Default::default()
         };
         let (_config, effective) = normalize_session_config(input);
 
@@ -187,6 +205,7 @@ mod tests {
             delay_between_numbers_ms: 0,
             total_numbers: 5,
             allow_negative_numbers: false,
+            ..Default::default()
         };
         assert!(
             validate_config(&config_bad_digits).is_err(),
@@ -199,6 +218,7 @@ mod tests {
             delay_between_numbers_ms: 0,
             total_numbers: 5,
             allow_negative_numbers: false,
+            ..Default::default()
         };
         assert!(
             validate_config(&config_bad_duration).is_err(),
@@ -211,6 +231,7 @@ mod tests {
             delay_between_numbers_ms: 0,
             total_numbers: 0,
             allow_negative_numbers: false,
+            ..Default::default()
         };
         assert!(
             validate_config(&config_bad_numbers).is_err(),
@@ -227,6 +248,7 @@ mod tests {
             delay_between_numbers_ms: 0,
             total_numbers: 5,
             allow_negative_numbers: false,
+            ..Default::default()
         };
         assert!(
             validate_config(&config_digits_over).is_err(),
@@ -240,6 +262,7 @@ mod tests {
             delay_between_numbers_ms: 0,
             total_numbers: 10_001,
             allow_negative_numbers: false,
+            ..Default::default()
         };
         assert!(
             validate_config(&config_numbers_over).is_err(),
@@ -253,6 +276,7 @@ mod tests {
             delay_between_numbers_ms: 0,
             total_numbers: 5,
             allow_negative_numbers: false,
+            ..Default::default()
         };
         assert!(
             validate_config(&config_duration_over).is_err(),
@@ -266,6 +290,7 @@ mod tests {
             delay_between_numbers_ms: 60_001,
             total_numbers: 5,
             allow_negative_numbers: false,
+            ..Default::default()
         };
         assert!(
             validate_config(&config_delay_over).is_err(),
@@ -273,6 +298,40 @@ mod tests {
         );
     }
 
+    #[test]
+    fn validate_config_reports_field_and_range() {
+        let config = SessionConfig {
+            digits_per_number: 1,
+            number_duration_ms: 100,
+            total_numbers: 10_001,
+            ..Default::default()
+        };
+        assert_eq!(
+            validate_config(&config),
+            Err(FlashspanError::ConfigOutOfRange {
+                field: "total_numbers",
+                min: 1,
+                max: 10_000
+            })
+        );
+
+        let config = SessionConfig {
+            digits_per_number: 2,
+            number_duration_ms: 100,
+            total_numbers: 5,
+            formula_mode: FormulaMode::FiveComplement,
+            allow_negative_numbers: false,
+            ..Default::default()
+        };
+        assert!(matches!(
+            validate_config(&config),
+            Err(FlashspanError::ConfigConflict {
+                field: "formula_mode",
+                ..
+            })
+        ));
+    }
+
     #[test]
     fn validate_config_accepts_boundary_values() {
         // All minimum values (except zero)
@@ -282,6 +341,7 @@ mod tests {
             delay_between_numbers_ms: 0,
             total_numbers: 1,
             allow_negative_numbers: false,
+            ..Default::default()
         };
         assert!(
             validate_config(&config_min).is_ok(),
@@ -295,6 +355,7 @@ mod tests {
             delay_between_numbers_ms: 60_000,
             total_numbers: 10_000,
             allow_negative_numbers: true,
+            ..Default::default()
         };
         assert!(
             validate_config(&config_max).is_ok(),
@@ -310,6 +371,7 @@ mod tests {
             delay_between_numbers_s: 0.0,
             total_numbers: 10,
             allow_negative_numbers: true,
+            ..Default::default()
         };
         let (_config, effective) = normalize_session_config(input_neg);
         assert!(
@@ -323,6 +385,7 @@ mod tests {
             delay_between_numbers_s: 0.0,
             total_numbers: 10,
             allow_negative_numbers: false,
+            ..Default::default()
         };
         let (_config_pos, effective_pos) = normalize_session_config(input_pos);
         assert!(
@@ -340,6 +403,7 @@ mod tests {
             delay_between_numbers_s: -5.0,
             total_numbers: 5,
             allow_negative_numbers: false,
+            ..Default::default()
         };
         let (config, effective) = normalize_session_config(input);
         assert_eq!(
@@ -358,6 +422,7 @@ mod tests {
             delay_between_numbers_s: 100.0,
             total_numbers: 5,
             allow_negative_numbers: false,
+            ..Default::default()
         };
         let (config_high, effective_high) = normalize_session_config(input_high);
         assert_eq!(
@@ -369,4 +434,398 @@ mod tests {
             "effective delay should be 60.0s"
         );
     }
+
+    #[test]
+    fn normalize_session_config_carry_policy() {
+        // A zero cap is the same as forbidding carries, and caps turn negatives on.
+        let input = SessionConfigInput {
+            digits_per_number: 3,
+            number_duration_s: 1.0,
+            total_numbers: 5,
+            carry_policy: CarryPolicy::AtMost { max: 0 },
+            ..Default::default()
+        };
+        let (config, effective) = normalize_session_config(input);
+        assert_eq!(config.carry_policy, CarryPolicy::NoCarries);
+        assert!(config.allow_negative_numbers);
+        assert_eq!(effective.carry_policy, CarryPolicy::NoCarries);
+
+        // Forced columns are clamped to the term width.
+        let input_column = SessionConfigInput {
+            digits_per_number: 2,
+            number_duration_s: 1.0,
+            total_numbers: 5,
+            carry_policy: CarryPolicy::InColumn { column: 7 },
+            ..Default::default()
+        };
+        let (config_column, _) = normalize_session_config(input_column);
+        assert_eq!(
+            config_column.carry_policy,
+            CarryPolicy::InColumn { column: 1 }
+        );
+
+        // Combining a formula mode with a carry policy is left for validation to reject.
+        let input_formula = SessionConfigInput {
+            digits_per_number: 2,
+            number_duration_s: 1.0,
+            total_numbers: 5,
+            formula_mode: FormulaMode::Mixed,
+            carry_policy: CarryPolicy::NoCarries,
+            ..Default::default()
+        };
+        let (config_formula, _) = normalize_session_config(input_formula);
+        assert_eq!(config_formula.carry_policy, CarryPolicy::NoCarries);
+        assert!(matches!(
+            validate_config(&config_formula),
+            Err(FlashspanError::ConfigConflict {
+                field: "carry_policy",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn validate_config_rejects_bad_carry_policies() {
+        let base = SessionConfig {
+            digits_per_number: 2,
+            number_duration_ms: 100,
+            delay_between_numbers_ms: 0,
+            total_numbers: 5,
+            allow_negative_numbers: true,
+            ..Default::default()
+        };
+
+        let column_out_of_range = SessionConfig {
+            carry_policy: CarryPolicy::InColumn { column: 2 },
+            ..base.clone()
+        };
+        assert!(validate_config(&column_out_of_range).is_err());
+
+        let combined_with_formula = SessionConfig {
+            carry_policy: CarryPolicy::NoCarries,
+            formula_mode: FormulaMode::TenComplement,
+            ..base.clone()
+        };
+        assert!(validate_config(&combined_with_formula).is_err());
+
+        let cap_without_negatives = SessionConfig {
+            carry_policy: CarryPolicy::AtMost { max: 1 },
+            allow_negative_numbers: false,
+            ..base.clone()
+        };
+        assert!(validate_config(&cap_without_negatives).is_err());
+
+        let in_column = SessionConfig {
+            carry_policy: CarryPolicy::InColumn { column: 1 },
+            ..base
+        };
+        assert!(validate_config(&in_column).is_ok());
+    }
+
+    #[test]
+    fn normalize_session_config_clamps_second_operand_digits() {
+        let input = SessionConfigInput {
+            digits_per_number: 18,
+            number_duration_s: 1.0,
+            total_numbers: 5,
+            operation: OperationMode::Multiplication,
+            second_operand_digits: 9,
+            formula_mode: FormulaMode::Direct,
+            ..Default::default()
+        };
+        let (config, _) = normalize_session_config(input);
+        assert_eq!(config.digits_per_number, 17);
+        assert_eq!(config.second_operand_digits, 1);
+        assert_eq!(config.formula_mode, FormulaMode::Any);
+        assert!(validate_config(&config).is_ok());
+
+        let input = SessionConfigInput {
+            digits_per_number: 3,
+            number_duration_s: 1.0,
+            total_numbers: 5,
+            operation: OperationMode::Division,
+            second_operand_digits: 5,
+            ..Default::default()
+        };
+        let (config, _) = normalize_session_config(input);
+        assert_eq!(config.second_operand_digits, 3);
+        assert!(validate_config(&config).is_ok());
+    }
+
+    #[test]
+    fn validate_config_rejects_bad_operand_widths() {
+        let base = SessionConfig {
+            digits_per_number: 10,
+            number_duration_ms: 100,
+            delay_between_numbers_ms: 0,
+            total_numbers: 5,
+            ..Default::default()
+        };
+
+        let too_wide_product = SessionConfig {
+            operation: OperationMode::Multiplication,
+            second_operand_digits: 9,
+            ..base.clone()
+        };
+        assert!(validate_config(&too_wide_product).is_err());
+
+        let divisor_wider_than_dividend = SessionConfig {
+            operation: OperationMode::Division,
+            second_operand_digits: 11,
+            ..base.clone()
+        };
+        assert!(validate_config(&divisor_wider_than_dividend).is_err());
+
+        let with_formula = SessionConfig {
+            operation: OperationMode::Multiplication,
+            second_operand_digits: 2,
+            formula_mode: FormulaMode::Mixed,
+            ..base
+        };
+        assert!(validate_config(&with_formula).is_err());
+    }
+
+    #[test]
+    fn dictation_only_applies_to_addition() {
+        let input = SessionConfigInput {
+            digits_per_number: 2,
+            number_duration_s: 1.0,
+            total_numbers: 5,
+            dictation: Some(DictationLanguage::Japanese),
+            ..Default::default()
+        };
+        let (config, effective) = normalize_session_config(input.clone());
+        assert_eq!(config.dictation, Some(DictationLanguage::Japanese));
+        assert_eq!(effective.dictation, Some(DictationLanguage::Japanese));
+        assert!(validate_config(&config).is_ok());
+
+        let (config, _) = normalize_session_config(SessionConfigInput {
+            operation: OperationMode::Multiplication,
+            second_operand_digits: 1,
+            ..input
+        });
+        assert_eq!(config.dictation, None);
+
+        let forced = SessionConfig {
+            operation: OperationMode::Multiplication,
+            second_operand_digits: 1,
+            dictation: Some(DictationLanguage::English),
+            ..config
+        };
+        assert!(matches!(
+            validate_config(&forced),
+            Err(FlashspanError::ConfigConflict {
+                field: "dictation",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn normalize_session_config_clamps_digit_range_and_pattern() {
+        let input = SessionConfigInput {
+            digits_per_number: 2,
+            number_duration_s: 1.0,
+            total_numbers: 5,
+            digit_range: Some(DigitRangeInput { min: 25, max: 0 }),
+            ..Default::default()
+        };
+        let (config, effective) = normalize_session_config(input);
+        assert_eq!(config.digit_range, Some(DigitRange { min: 1, max: 18 }));
+        assert_eq!(effective.digit_range, config.digit_range);
+        assert!(validate_config(&config).is_ok());
+
+        // A pattern wins over a range.
+        let input = SessionConfigInput {
+            digits_per_number: 2,
+            number_duration_s: 1.0,
+            total_numbers: 5,
+            digit_range: Some(DigitRangeInput { min: 2, max: 4 }),
+            digit_pattern: vec![3, 0, 40],
+            ..Default::default()
+        };
+        let (config, _) = normalize_session_config(input);
+        assert_eq!(config.digit_range, None);
+        assert_eq!(config.digit_pattern, vec![3, 1, 18]);
+        assert!(validate_config(&config).is_ok());
+    }
+
+    #[test]
+    fn validate_config_rejects_bad_digit_ranges_and_patterns() {
+        let base = SessionConfig {
+            digits_per_number: 2,
+            number_duration_ms: 100,
+            delay_between_numbers_ms: 0,
+            total_numbers: 5,
+            ..Default::default()
+        };
+
+        let reversed = SessionConfig {
+            digit_range: Some(DigitRange { min: 4, max: 2 }),
+            ..base.clone()
+        };
+        assert!(validate_config(&reversed).is_err());
+
+        let zero_width = SessionConfig {
+            digit_pattern: vec![2, 0],
+            ..base.clone()
+        };
+        assert!(validate_config(&zero_width).is_err());
+
+        let both = SessionConfig {
+            digit_range: Some(DigitRange { min: 2, max: 4 }),
+            digit_pattern: vec![2],
+            ..base.clone()
+        };
+        assert!(validate_config(&both).is_err());
+
+        let with_multiplication = SessionConfig {
+            operation: OperationMode::Multiplication,
+            second_operand_digits: 1,
+            digit_pattern: vec![2, 3],
+            ..base.clone()
+        };
+        assert!(validate_config(&with_multiplication).is_err());
+
+        // The carry column may sit in the widest term even if digits_per_number is narrower.
+        let wide_column = SessionConfig {
+            digit_range: Some(DigitRange { min: 2, max: 4 }),
+            carry_policy: CarryPolicy::InColumn { column: 3 },
+            ..base
+        };
+        assert!(validate_config(&wide_column).is_ok());
+    }
+
+    #[test]
+    fn decimal_places_share_the_digit_budget() {
+        let input = SessionConfigInput {
+            digits_per_number: 15,
+            number_duration_s: 1.0,
+            total_numbers: 5,
+            decimal_places: 6,
+            ..Default::default()
+        };
+        let (config, _) = normalize_session_config(input);
+        assert_eq!(config.decimal_places, 3);
+        assert!(validate_config(&config).is_ok());
+
+        let too_wide = SessionConfig {
+            decimal_places: 4,
+            ..config.clone()
+        };
+        assert!(validate_config(&too_wide).is_err());
+
+        let with_division = SessionConfig {
+            digits_per_number: 4,
+            operation: OperationMode::Division,
+            second_operand_digits: 2,
+            decimal_places: 2,
+            ..config
+        };
+        assert!(validate_config(&with_division).is_err());
+    }
+
+    #[test]
+    fn normalize_session_config_clamps_timing_schedules() {
+        let input = SessionConfigInput {
+            digits_per_number: 1,
+            number_duration_s: 1.0,
+            total_numbers: 5,
+            timing_schedule: TimingSchedule::PerIndex {
+                durations_s: vec![0.0, 0.55, 120.0],
+            },
+            ..Default::default()
+        };
+        let (config, effective) = normalize_session_config(input);
+        assert_eq!(
+            config.timing_schedule,
+            TimingScheduleMs::PerIndex {
+                durations_ms: vec![100, 550, 60_000]
+            }
+        );
+        assert_eq!(
+            effective.timing_schedule,
+            TimingSchedule::PerIndex {
+                durations_s: vec![0.1, 0.6, 60.0]
+            }
+        );
+        assert!(validate_config(&config).is_ok());
+
+        let input = SessionConfigInput {
+            digits_per_number: 1,
+            number_duration_s: 1.0,
+            total_numbers: 5,
+            timing_schedule: TimingSchedule::Steps {
+                every: 0,
+                change_s: f64::NAN,
+            },
+            ..Default::default()
+        };
+        let (config, _) = normalize_session_config(input);
+        assert_eq!(
+            config.timing_schedule,
+            TimingScheduleMs::Steps {
+                every: 1,
+                change_ms: 0
+            }
+        );
+
+        let empty = SessionConfig {
+            timing_schedule: TimingScheduleMs::PerIndex {
+                durations_ms: vec![],
+            },
+            ..config.clone()
+        };
+        assert!(validate_config(&empty).is_err());
+
+        let too_short = SessionConfig {
+            timing_schedule: TimingScheduleMs::PerIndex {
+                durations_ms: vec![500, 1],
+            },
+            ..config
+        };
+        assert!(validate_config(&too_short).is_err());
+    }
+
+    #[test]
+    fn normalize_auto_repeat_clamps_and_skips_disabled() {
+        let input = |enabled, repeats, delay_s| AutoRepeatConfigInput {
+            enabled,
+            repeats,
+            delay_s,
+            adaptive: None,
+            seed_strategy: SeedStrategy::Incrementing,
+        };
+        assert!(normalize_auto_repeat(&input(false, 3, 10.0)).is_none());
+
+        let ar = normalize_auto_repeat(&input(true, 50, 1.0)).unwrap();
+        assert_eq!((ar.repeats, ar.delay_s), (20, 5.0));
+        let ar = normalize_auto_repeat(&input(true, -2, f64::NAN)).unwrap();
+        assert_eq!((ar.repeats, ar.delay_s), (1, 5.0));
+        let ar = normalize_auto_repeat(&input(true, 4, 7.25)).unwrap();
+        assert_eq!((ar.repeats, ar.delay_s), (4, 7.25));
+        assert_eq!(ar.seed_strategy, SeedStrategy::Incrementing);
+    }
+
+    #[test]
+    fn normalize_adaptive_policy_orders_bounds_and_clamps() {
+        let policy = normalize_adaptive_policy(&AdaptivePolicyInput {
+            harden_after_correct: 0,
+            duration_step_s: f64::INFINITY,
+            min_duration_s: 5.0,
+            max_duration_s: 0.01,
+            min_digits: 30,
+            max_digits: 2,
+            total_numbers_step: -4,
+            ..Default::default()
+        });
+        assert_eq!(policy.harden_after_correct, 1);
+        assert_eq!(policy.duration_step_ms, 10_000);
+        assert_eq!(
+            (policy.min_duration_ms, policy.max_duration_ms),
+            (100, 5_000)
+        );
+        assert_eq!((policy.min_digits, policy.max_digits), (2, 18));
+        assert_eq!(policy.total_numbers_step, 0);
+    }
 }
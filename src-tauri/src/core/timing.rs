@@ -0,0 +1,101 @@
+use crate::core::types::{TimingSchedule, TimingScheduleMs};
+
+/// Shortest flash a schedule may produce, matching the 0.1s floor of `number_duration_s`.
+pub const MIN_FLASH_MS: u64 = 100;
+/// Longest flash a schedule may produce.
+pub const MAX_FLASH_MS: u64 = 60_000;
+
+impl TimingScheduleMs {
+    /// Flash duration for the zero-based flash `index` of `total`, starting from `base_ms`.
+    pub fn duration_ms(&self, base_ms: u64, index: u32, total: u32) -> u64 {
+        match self {
+            TimingScheduleMs::Constant => base_ms,
+            TimingScheduleMs::LinearRamp { end_duration_ms } => {
+                if total <= 1 {
+                    return base_ms;
+                }
+                let progress = index as f64 / (total - 1) as f64;
+                let span = *end_duration_ms as f64 - base_ms as f64;
+                (base_ms as f64 + span * progress).round() as u64
+            }
+            TimingScheduleMs::Steps { every, change_ms } => {
+                let steps_taken = (index / (*every).max(1)) as i64;
+                let ms = (base_ms as i64).saturating_add(change_ms.saturating_mul(steps_taken));
+                ms.clamp(MIN_FLASH_MS as i64, MAX_FLASH_MS as i64) as u64
+            }
+            TimingScheduleMs::PerIndex { durations_ms } => {
+                if durations_ms.is_empty() {
+                    return base_ms;
+                }
+                durations_ms[index as usize % durations_ms.len()].clamp(MIN_FLASH_MS, MAX_FLASH_MS)
+            }
+        }
+    }
+
+    /// The same schedule in seconds, for `SessionConfigEffective`.
+    pub fn to_seconds(&self) -> TimingSchedule {
+        let s = |ms: u64| ms as f64 / 1000.0;
+        match self {
+            TimingScheduleMs::Constant => TimingSchedule::Constant,
+            TimingScheduleMs::LinearRamp { end_duration_ms } => TimingSchedule::LinearRamp {
+                end_duration_s: s(*end_duration_ms),
+            },
+            TimingScheduleMs::Steps { every, change_ms } => TimingSchedule::Steps {
+                every: *every as i64,
+                change_s: *change_ms as f64 / 1000.0,
+            },
+            TimingScheduleMs::PerIndex { durations_ms } => TimingSchedule::PerIndex {
+                durations_s: durations_ms.iter().map(|&ms| s(ms)).collect(),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn linear_ramp_hits_both_ends() {
+        let ramp = TimingScheduleMs::LinearRamp {
+            end_duration_ms: 300,
+        };
+        assert_eq!(ramp.duration_ms(1000, 0, 8), 1000);
+        assert_eq!(ramp.duration_ms(1000, 7, 8), 300);
+        assert_eq!(ramp.duration_ms(1000, 0, 1), 1000);
+        let mid = ramp.duration_ms(1000, 3, 8);
+        assert!(mid < 1000 && mid > 300);
+    }
+
+    #[test]
+    fn steps_change_every_n_and_stay_in_bounds() {
+        let steps = TimingScheduleMs::Steps {
+            every: 5,
+            change_ms: -100,
+        };
+        assert_eq!(steps.duration_ms(500, 4, 20), 500);
+        assert_eq!(steps.duration_ms(500, 5, 20), 400);
+        assert_eq!(steps.duration_ms(500, 19, 20), 200);
+        assert_eq!(steps.duration_ms(500, 100, 200), MIN_FLASH_MS);
+    }
+
+    #[test]
+    fn per_index_repeats() {
+        let explicit = TimingScheduleMs::PerIndex {
+            durations_ms: vec![900, 700],
+        };
+        assert_eq!(explicit.duration_ms(500, 0, 5), 900);
+        assert_eq!(explicit.duration_ms(500, 1, 5), 700);
+        assert_eq!(explicit.duration_ms(500, 2, 5), 900);
+    }
+
+    #[test]
+    fn per_index_stays_in_bounds() {
+        let explicit = TimingScheduleMs::PerIndex {
+            durations_ms: vec![1, 0, 120_000],
+        };
+        assert_eq!(explicit.duration_ms(500, 0, 3), MIN_FLASH_MS);
+        assert_eq!(explicit.duration_ms(500, 1, 3), MIN_FLASH_MS);
+        assert_eq!(explicit.duration_ms(500, 2, 3), MAX_FLASH_MS);
+    }
+}
@@ -1,4 +1,7 @@
+use super::answer::ValidationResult;
+use super::dictation::DictationLanguage;
 use serde::{Deserialize, Serialize};
+use std::time::Instant;
 
 #[derive(Debug, Clone, Serialize)]
 pub struct ClearScreen {
@@ -14,6 +17,24 @@ pub struct SessionComplete {
     pub session_id: u64,
     pub numbers: Vec<i64>,
     pub sum: i64,
+    /// Scale of `numbers` and `sum`; see [`ShowNumber::decimal_places`].
+    pub decimal_places: u32,
+    /// Problems flashed in multiplication/division sessions; empty for running-sum sessions.
+    pub pairs: Vec<OperandPair>,
+    pub started_at_ms: u64,
+    pub completed_at_ms: u64,
+    /// When `Complete` was emitted, on the monotonic clock, so answer times survive wall-clock
+    /// changes. Not sent to the UI.
+    #[serde(skip)]
+    pub completed_at: Option<Instant>,
+    /// Settings the session ran with.
+    pub config: SessionConfigEffective,
+    /// User profile that was active when the session started (native app only).
+    pub profile_id: Option<String>,
+    /// Seed the numbers were generated from; with `config` it regenerates this session.
+    pub seed: u64,
+    /// Subtotals asked for during the session, in order.
+    pub checkpoints: Vec<CheckpointResult>,
 }
 
 #[derive(Debug, Clone, serde::Serialize)]
@@ -23,18 +44,349 @@ pub struct ShowNumber {
     pub total: u32,
     pub value: i64,
     pub running_sum: i64,
+    /// Carries (addition) or borrows (subtraction) this value causes against the previous sum.
+    pub carries: u32,
+    /// `value` formatted for display, e.g. "-3.91" in a two-place decimal session.
+    pub display: String,
+    /// In decimal sessions `value` and `running_sum` count units of 10^-decimal_places.
+    pub decimal_places: u32,
     pub emitted_at_ms: u64,
 }
 
+/// Planned vs measured emission time of one plan step, as offsets from the session start
+/// with paused time left out.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct StepTiming {
+    pub step_index: u32,
+    pub planned_offset_us: u64,
+    pub actual_offset_us: u64,
+    /// `actual - planned`; positive when the step went out late.
+    pub jitter_us: i64,
+}
+
+/// Scheduling accuracy of one executed session.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct TimingReport {
+    pub session_id: u64,
+    pub steps: Vec<StepTiming>,
+    pub max_abs_jitter_us: u64,
+    pub mean_abs_jitter_us: u64,
+    pub paused_ms: u64,
+    /// False when the session was stopped before its last step.
+    pub completed: bool,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SessionPaused {
+    pub session_id: u64,
+    /// Time left in the interrupted step; it runs out after `session_resumed`.
+    pub remaining_ms: u64,
+    pub emitted_at_ms: u64,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SessionResumed {
+    pub session_id: u64,
+    pub paused_ms: u64,
+    pub emitted_at_ms: u64,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ShowPair {
+    pub session_id: u64,
+    pub index: u32,
+    pub total: u32,
+    pub operation: OperationMode,
+    pub left: i64,
+    pub right: i64,
+    pub emitted_at_ms: u64,
+}
+
+/// The session stopped after `index` numbers and waits for their subtotal.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct Checkpoint {
+    pub session_id: u64,
+    pub index: u32,
+    pub total: u32,
+    /// Scale the subtotal is answered in; see [`ShowNumber::decimal_places`].
+    pub decimal_places: u32,
+    pub emitted_at_ms: u64,
+}
+
+/// A checkpoint answer, checked against the sum of the first `index` numbers.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckpointResult {
+    pub index: u32,
+    pub validation: ValidationResult,
+    /// From the checkpoint being asked to the answer.
+    pub answer_ms: u64,
+}
+
+/// Auto-repeat settings as sent by the UI.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutoRepeatConfigInput {
+    pub enabled: bool,
+    pub repeats: i64,
+    pub delay_s: f64,
+    /// Tune each round from the previous answer instead of replaying the same config.
+    #[serde(default)]
+    pub adaptive: Option<AdaptivePolicyInput>,
+    #[serde(default)]
+    pub seed_strategy: SeedStrategy,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutoRepeatEffective {
+    pub enabled: bool,
+    pub repeats: u32,
+    pub delay_s: f64,
+    #[serde(default)]
+    pub adaptive: Option<AdaptivePolicy>,
+    #[serde(default)]
+    pub seed_strategy: SeedStrategy,
+}
+
+/// How auto-repeat picks the seed of each round after the first.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SeedStrategy {
+    /// Every round reuses the first seed, so an unchanged config replays the same numbers.
+    Fixed,
+    /// Each round uses the previous seed plus one.
+    Incrementing,
+    /// Each round draws a new seed.
+    #[default]
+    Random,
+}
+
 #[derive(Debug, Clone)]
 pub struct AutoRepeatPlan {
     pub remaining: u32,
     pub delay_ms: u64,
     pub config: SessionConfig,
     pub awaiting_validation_session_id: Option<u64>,
+    pub adaptive: Option<AdaptiveRun>,
+    pub seed_strategy: SeedStrategy,
+    /// Seed of the latest round; the next one is derived from it by `seed_strategy`.
+    pub seed: u64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Staircase rules for adaptive auto-repeat, as sent by the UI. Missing fields take the
+/// defaults: speed up by 0.1s after 2 correct answers, slow down after 1 wrong answer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AdaptivePolicyInput {
+    pub harden_after_correct: i64,
+    pub ease_after_wrong: i64,
+    pub duration_step_s: f64,
+    pub min_duration_s: f64,
+    pub max_duration_s: f64,
+    /// Digits added per harder round; 0 keeps `digits_per_number` fixed.
+    pub digits_step: i64,
+    pub min_digits: i64,
+    pub max_digits: i64,
+    /// Numbers added per harder round; 0 keeps `total_numbers` fixed.
+    pub total_numbers_step: i64,
+    pub min_total_numbers: i64,
+    pub max_total_numbers: i64,
+}
+
+impl Default for AdaptivePolicyInput {
+    fn default() -> Self {
+        Self {
+            harden_after_correct: 2,
+            ease_after_wrong: 1,
+            duration_step_s: 0.1,
+            min_duration_s: 0.2,
+            max_duration_s: 3.0,
+            digits_step: 0,
+            min_digits: 1,
+            max_digits: 6,
+            total_numbers_step: 0,
+            min_total_numbers: 3,
+            max_total_numbers: 30,
+        }
+    }
+}
+
+/// Normalised [`AdaptivePolicyInput`], in milliseconds.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AdaptivePolicy {
+    pub harden_after_correct: u32,
+    pub ease_after_wrong: u32,
+    pub duration_step_ms: u64,
+    pub min_duration_ms: u64,
+    pub max_duration_ms: u64,
+    pub digits_step: u32,
+    pub min_digits: u32,
+    pub max_digits: u32,
+    pub total_numbers_step: u32,
+    pub min_total_numbers: u32,
+    pub max_total_numbers: u32,
+}
+
+/// Answer streaks counted towards the next adaptive step.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct AdaptiveState {
+    pub correct_streak: u32,
+    pub wrong_streak: u32,
+}
+
+/// Adaptive policy and progress of one auto-repeat run.
+#[derive(Debug, Clone)]
+pub struct AdaptiveRun {
+    pub policy: AdaptivePolicy,
+    pub state: AdaptiveState,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AdaptiveDirection {
+    Harder,
+    Easier,
+    /// The streak has not reached a threshold yet.
+    Hold,
+}
+
+/// The settings an adaptive step moves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct AdaptiveLevel {
+    pub number_duration_ms: u64,
+    pub digits_per_number: u32,
+    pub total_numbers: u32,
+}
+
+/// What one validated answer changed for the next round.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct AdaptiveAdjustment {
+    pub correct: bool,
+    pub direction: AdaptiveDirection,
+    /// Streaks after this answer; reset to 0 once they trigger a step.
+    pub state: AdaptiveState,
+    pub previous: AdaptiveLevel,
+    pub next: AdaptiveLevel,
+}
+
+/// What each flash asks the student to compute.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OperationMode {
+    /// A running sum over every flashed number.
+    #[default]
+    Addition,
+    /// Independent `left × right` problems, one answer each.
+    Multiplication,
+    /// Independent `left ÷ right` problems with no remainder, one answer each.
+    Division,
+}
+
+/// One multiplication or division problem and its expected answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OperandPair {
+    pub operation: OperationMode,
+    pub left: i64,
+    pub right: i64,
+    pub answer: i64,
+}
+
+/// Inclusive range of term widths in digits, as sent by the UI.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DigitRangeInput {
+    pub min: i64,
+    pub max: i64,
+}
+
+/// Inclusive range of term widths in digits; each term picks its width at random.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct DigitRange {
+    pub min: u32,
+    pub max: u32,
+}
+
+/// Soroban formula family a generated term is allowed to exercise.
+///
+/// Each restricted mode permits the formulas up to and including its namesake and
+/// prefers terms that use that formula at least once ("small friends" = five-complement,
+/// "big friends" = ten-complement, mixed = combined five-and-ten complement).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FormulaMode {
+    /// No abacus constraint: only the running-sum rules apply.
+    #[default]
+    Any,
+    /// Direct bead moves only.
+    Direct,
+    /// Direct moves plus five-complement.
+    FiveComplement,
+    /// Direct moves plus ten-complement.
+    TenComplement,
+    /// All formulas, including combined five-and-ten complement.
+    Mixed,
+}
+
+/// Limits on the carries (addition) or borrows (subtraction) each term causes, counted per
+/// written-arithmetic column against the running sum. Columns are numbered from 0 (units).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CarryPolicy {
+    /// No limit.
+    #[default]
+    Any,
+    /// Every term is carry- and borrow-free.
+    NoCarries,
+    /// Each term causes at most `max` carries or borrows.
+    AtMost { max: u32 },
+    /// Each term carries or borrows in `column` whenever the running sum allows it.
+    InColumn { column: u32 },
+}
+
+/// How long each flash stays on screen across a session, in seconds (input/effective form).
+/// Only flash durations vary; `delay_between_numbers_s` stays fixed.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TimingSchedule {
+    /// Every flash lasts `number_duration_s`.
+    #[default]
+    Constant,
+    /// Ramp linearly from `number_duration_s` on the first flash to `end_duration_s` on the last.
+    LinearRamp { end_duration_s: f64 },
+    /// Add `change_s` (negative to speed up) after every `every` flashes.
+    Steps { every: i64, change_s: f64 },
+    /// Explicit duration per flash, repeated if shorter than the session.
+    PerIndex { durations_s: Vec<f64> },
+}
+
+/// Where a running-sum session stops to ask for the subtotal so far.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CheckpointSchedule {
+    #[default]
+    Off,
+    /// After every `every` numbers.
+    Every { every: u32 },
+    /// After each listed number, counted from 1.
+    After { positions: Vec<u32> },
+}
+
+/// [`TimingSchedule`] in milliseconds, as used for plan generation.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TimingScheduleMs {
+    #[default]
+    Constant,
+    LinearRamp {
+        end_duration_ms: u64,
+    },
+    Steps {
+        every: u32,
+        change_ms: i64,
+    },
+    PerIndex {
+        durations_ms: Vec<u64>,
+    },
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SessionConfigInput {
     pub digits_per_number: i64,
     pub number_duration_s: f64,
@@ -43,24 +395,102 @@ pub struct SessionConfigInput {
 
     #[serde(default)]
     pub allow_negative_numbers: bool,
+
+    #[serde(default)]
+    pub formula_mode: FormulaMode,
+
+    #[serde(default)]
+    pub carry_policy: CarryPolicy,
+
+    #[serde(default)]
+    pub operation: OperationMode,
+
+    /// Width of the multiplier or divisor; `digits_per_number` sizes the other operand.
+    #[serde(default)]
+    pub second_operand_digits: i64,
+
+    /// Fixed decimal places for decimal anzan (e.g. 2 → 12.37); 0 flashes integers.
+    /// Digit widths count the integer part only.
+    #[serde(default)]
+    pub decimal_places: i64,
+
+    #[serde(default)]
+    pub timing_schedule: TimingSchedule,
+
+    /// Mixed-width terms, e.g. 2–4 digits. Overrides `digits_per_number`.
+    #[serde(default)]
+    pub digit_range: Option<DigitRangeInput>,
+
+    /// Explicit per-term widths (e.g. 3,3,2,4), repeated if shorter than the session.
+    /// Overrides both `digits_per_number` and `digit_range`.
+    #[serde(default)]
+    pub digit_pattern: Vec<i64>,
+
+    /// Read each number aloud in this language instead of beeping (addition only).
+    #[serde(default)]
+    pub dictation: Option<DictationLanguage>,
+
+    /// Stop for the running sum at these points (addition only). Never after the last number.
+    #[serde(default)]
+    pub checkpoints: CheckpointSchedule,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SessionConfigEffective {
     pub digits_per_number: u32,
     pub number_duration_s: f64,
     pub delay_between_numbers_s: f64,
     pub total_numbers: u32,
     pub allow_negative_numbers: bool,
+    #[serde(default)]
+    pub formula_mode: FormulaMode,
+    #[serde(default)]
+    pub carry_policy: CarryPolicy,
+    #[serde(default)]
+    pub operation: OperationMode,
+    #[serde(default)]
+    pub second_operand_digits: u32,
+    #[serde(default)]
+    pub decimal_places: u32,
+    #[serde(default)]
+    pub timing_schedule: TimingSchedule,
+    #[serde(default)]
+    pub digit_range: Option<DigitRange>,
+    #[serde(default)]
+    pub digit_pattern: Vec<u32>,
+    #[serde(default)]
+    pub dictation: Option<DictationLanguage>,
+    #[serde(default)]
+    pub checkpoints: CheckpointSchedule,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SessionConfig {
     pub digits_per_number: u32,
     pub number_duration_ms: u64,
     pub delay_between_numbers_ms: u64,
     pub total_numbers: u32,
     pub allow_negative_numbers: bool,
+    #[serde(default)]
+    pub formula_mode: FormulaMode,
+    #[serde(default)]
+    pub carry_policy: CarryPolicy,
+    #[serde(default)]
+    pub operation: OperationMode,
+    #[serde(default)]
+    pub second_operand_digits: u32,
+    #[serde(default)]
+    pub decimal_places: u32,
+    #[serde(default)]
+    pub timing_schedule: TimingScheduleMs,
+    #[serde(default)]
+    pub digit_range: Option<DigitRange>,
+    #[serde(default)]
+    pub digit_pattern: Vec<u32>,
+    #[serde(default)]
+    pub dictation: Option<DictationLanguage>,
+    #[serde(default)]
+    pub checkpoints: CheckpointSchedule,
 }
 
 /// A single step in a deterministic session plan.
@@ -80,6 +510,10 @@ pub enum SessionStep {
         total: u32,
         value: i64,
         running_sum: i64,
+        #[serde(default)]
+        carries: u32,
+        #[serde(default)]
+        decimal_places: u32,
         delay_ms_before_next: u64,
     },
 
@@ -90,11 +524,38 @@ pub enum SessionStep {
         delay_ms_before_next: u64,
     },
 
+    /// Show a multiplication or division problem
+    ShowPair {
+        session_id: u64,
+        index: u32,
+        total: u32,
+        operation: OperationMode,
+        left: i64,
+        right: i64,
+        delay_ms_before_next: u64,
+    },
+
+    /// Checkpoint: wait for the sum of the first `index` numbers, then continue
+    Checkpoint {
+        session_id: u64,
+        index: u32,
+        total: u32,
+        expected_sum: i64,
+        #[serde(default)]
+        decimal_places: u32,
+        /// Counted from the answer.
+        delay_ms_before_next: u64,
+    },
+
     /// Session complete: emit final results and numbers
     Complete {
         session_id: u64,
         numbers: Vec<i64>,
         sum: i64,
+        #[serde(default)]
+        decimal_places: u32,
+        #[serde(default)]
+        pairs: Vec<OperandPair>,
     },
 }
 
@@ -108,4 +569,9 @@ pub struct SessionPlan {
     pub total_duration_ms: u64,
     pub numbers_generated: Vec<i64>,
     pub expected_sum: i64,
+    #[serde(default)]
+    pub pairs_generated: Vec<OperandPair>,
+    /// Seed the plan was built from; the same config and seed rebuild it exactly.
+    #[serde(default)]
+    pub seed: u64,
 }
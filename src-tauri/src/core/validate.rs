@@ -1,4 +1,11 @@
-use super::types::{SessionConfig, SessionConfigEffective, SessionConfigInput};
+use super::decimal::MAX_DECIMAL_PLACES;
+use super::error::FlashspanError;
+use super::timing::{MAX_FLASH_MS, MIN_FLASH_MS};
+use super::types::{
+    AdaptivePolicy, AdaptivePolicyInput, AutoRepeatConfigInput, AutoRepeatEffective, CarryPolicy,
+    CheckpointSchedule, DigitRange, FormulaMode, OperationMode, SessionConfig,
+    SessionConfigEffective, SessionConfigInput, TimingSchedule, TimingScheduleMs,
+};
 
 fn round_1_decimal(v: f64) -> f64 {
     (v * 10.0).round() / 10.0
@@ -27,25 +34,183 @@ fn seconds_to_ms_clamped(seconds: f64, min_ms: u64, max_ms: u64) -> u64 {
     ms_u64.max(min_ms).min(max_ms)
 }
 
+/// Clamp a schedule the same way as `number_duration_s`, returning the millisecond form used
+/// by the engine and the rounded seconds form reported back to the UI.
+pub(crate) fn normalize_timing_schedule(
+    schedule: &TimingSchedule,
+) -> (TimingScheduleMs, TimingSchedule) {
+    let flash_ms =
+        |s: f64| seconds_to_ms_clamped(clamp_f64(s, 0.1, 60.0), MIN_FLASH_MS, MAX_FLASH_MS);
+    let ms_to_s = |ms: u64| round_1_decimal(ms as f64 / 1000.0);
+
+    match schedule {
+        TimingSchedule::Constant => (TimingScheduleMs::Constant, TimingSchedule::Constant),
+        TimingSchedule::LinearRamp { end_duration_s } => {
+            let end_duration_ms = flash_ms(*end_duration_s);
+            (
+                TimingScheduleMs::LinearRamp { end_duration_ms },
+                TimingSchedule::LinearRamp {
+                    end_duration_s: ms_to_s(end_duration_ms),
+                },
+            )
+        }
+        TimingSchedule::Steps { every, change_s } => {
+            let every = clamp_i64(*every, 1, 10_000);
+            let change_s = if change_s.is_nan() {
+                0.0
+            } else {
+                clamp_f64(*change_s, -60.0, 60.0)
+            };
+            let change_ms = (change_s * 1000.0).round() as i64;
+            (
+                TimingScheduleMs::Steps {
+                    every: every as u32,
+                    change_ms,
+                },
+                TimingSchedule::Steps {
+                    every,
+                    change_s: round_1_decimal(change_ms as f64 / 1000.0),
+                },
+            )
+        }
+        TimingSchedule::PerIndex { durations_s } if !durations_s.is_empty() => {
+            let durations_ms: Vec<u64> = durations_s
+                .iter()
+                .take(10_000)
+                .map(|&s| flash_ms(s))
+                .collect();
+            let durations_s = durations_ms.iter().map(|&ms| ms_to_s(ms)).collect();
+            (
+                TimingScheduleMs::PerIndex { durations_ms },
+                TimingSchedule::PerIndex { durations_s },
+            )
+        }
+        TimingSchedule::PerIndex { .. } => (TimingScheduleMs::Constant, TimingSchedule::Constant),
+    }
+}
+
 pub fn normalize_session_config(
     input: SessionConfigInput,
 ) -> (SessionConfig, SessionConfigEffective) {
-    let digits = clamp_i64(input.digits_per_number, 1, 18) as u32;
+    let operation = input.operation;
+    let digits = match operation {
+        // Leave room for at least a one-digit multiplier within i64.
+        OperationMode::Multiplication => clamp_i64(input.digits_per_number, 1, 17) as u32,
+        _ => clamp_i64(input.digits_per_number, 1, 18) as u32,
+    };
+    let second_operand_digits = match operation {
+        OperationMode::Multiplication => {
+            clamp_i64(input.second_operand_digits, 1, 18 - digits as i64) as u32
+        }
+        OperationMode::Division => clamp_i64(input.second_operand_digits, 1, digits as i64) as u32,
+        OperationMode::Addition => clamp_i64(input.second_operand_digits, 1, 18) as u32,
+    };
+    // Formula modes and carry policies only shape running-sum sequences.
+    let (formula_mode, carry_policy_input) = if operation == OperationMode::Addition {
+        (input.formula_mode, input.carry_policy)
+    } else {
+        (FormulaMode::Any, CarryPolicy::Any)
+    };
     let total_numbers = clamp_i64(input.total_numbers, 1, 10_000) as u32;
 
+    // Mixed widths only apply to running sums; a pattern makes any range redundant.
+    let digit_pattern: Vec<u32> = if operation == OperationMode::Addition {
+        input
+            .digit_pattern
+            .iter()
+            .take(10_000)
+            .map(|&d| clamp_i64(d, 1, 18) as u32)
+            .collect()
+    } else {
+        Vec::new()
+    };
+    let digit_range = match input.digit_range {
+        Some(range) if operation == OperationMode::Addition && digit_pattern.is_empty() => {
+            let a = clamp_i64(range.min, 1, 18) as u32;
+            let b = clamp_i64(range.max, 1, 18) as u32;
+            Some(DigitRange {
+                min: a.min(b),
+                max: a.max(b),
+            })
+        }
+        _ => None,
+    };
+    // Decimal places share the 18-digit budget with the widest integer part.
+    let decimal_places = if operation == OperationMode::Addition {
+        clamp_i64(input.decimal_places, 0, MAX_DECIMAL_PLACES as i64) as u32
+    } else {
+        0
+    };
+    let widest_term = digit_pattern
+        .iter()
+        .max()
+        .copied()
+        .or(digit_range.map(|r| r.max))
+        .unwrap_or(digits);
+    let decimal_places = decimal_places.min(18 - widest_term);
+
     // UI typically uses 0.1–5s, but we allow up to 60s defensively.
     let duration_s = clamp_f64(input.number_duration_s, 0.1, 60.0);
     let delay_s = clamp_f64(input.delay_between_numbers_s, 0.0, 60.0);
 
     let number_duration_ms = seconds_to_ms_clamped(duration_s, 1, 60_000);
     let delay_between_numbers_ms = seconds_to_ms_clamped(delay_s, 0, 60_000);
+    let (timing_schedule, timing_schedule_effective) =
+        normalize_timing_schedule(&input.timing_schedule);
+
+    // Pairs have no running sum to ask about. Positions past the last number are dropped.
+    let checkpoints = match input.checkpoints {
+        _ if operation != OperationMode::Addition => CheckpointSchedule::Off,
+        CheckpointSchedule::Every { every } => CheckpointSchedule::Every {
+            every: every.clamp(1, 10_000),
+        },
+        CheckpointSchedule::After { mut positions } => {
+            positions.retain(|&p| (1..total_numbers).contains(&p));
+            positions.sort_unstable();
+            positions.dedup();
+            if positions.is_empty() {
+                CheckpointSchedule::Off
+            } else {
+                CheckpointSchedule::After { positions }
+            }
+        }
+        CheckpointSchedule::Off => CheckpointSchedule::Off,
+    };
+
+    // A carry policy next to a formula mode is kept as given, for `validate_config` to reject.
+    let carry_policy = match carry_policy_input {
+        CarryPolicy::AtMost { max: 0 } => CarryPolicy::NoCarries,
+        CarryPolicy::InColumn { column } => CarryPolicy::InColumn {
+            column: column.min(widest_term + decimal_places - 1),
+        },
+        policy => policy,
+    };
+
+    // Restricted soroban modes and carry caps need subtraction to avoid dead ends,
+    // so they turn negatives on.
+    let allow_negative_numbers = input.allow_negative_numbers
+        || formula_mode.requires_negative_numbers()
+        || carry_policy.requires_negative_numbers();
 
     let config = SessionConfig {
         digits_per_number: digits,
         number_duration_ms,
         delay_between_numbers_ms,
         total_numbers,
-        allow_negative_numbers: input.allow_negative_numbers,
+        allow_negative_numbers,
+        formula_mode,
+        carry_policy,
+        operation,
+        second_operand_digits,
+        decimal_places,
+        timing_schedule,
+        digit_range,
+        digit_pattern,
+        // Pairs are shown, not read.
+        dictation: input
+            .dictation
+            .filter(|_| operation == OperationMode::Addition),
+        checkpoints,
     };
 
     let effective = SessionConfigEffective {
@@ -54,36 +219,306 @@ pub fn normalize_session_config(
         delay_between_numbers_s: round_1_decimal(config.delay_between_numbers_ms as f64 / 1000.0),
         total_numbers: config.total_numbers,
         allow_negative_numbers: config.allow_negative_numbers,
+        formula_mode: config.formula_mode,
+        carry_policy: config.carry_policy,
+        operation: config.operation,
+        second_operand_digits: config.second_operand_digits,
+        decimal_places: config.decimal_places,
+        timing_schedule: timing_schedule_effective,
+        digit_range: config.digit_range,
+        digit_pattern: config.digit_pattern.clone(),
+        dictation: config.dictation,
+        checkpoints: config.checkpoints.clone(),
     };
 
     (config, effective)
 }
 
-pub fn validate_config(config: &SessionConfig) -> Result<(), String> {
-    if config.digits_per_number == 0 || config.number_duration_ms == 0 || config.total_numbers == 0
-    {
-        return Err(
-            "digits_per_number, number_duration_ms, and total_numbers must be > 0".to_string(),
-        );
+/// Clamp auto-repeat settings to 1–20 repeats and a 5–120s delay; `None` when disabled.
+pub fn normalize_auto_repeat(input: &AutoRepeatConfigInput) -> Option<AutoRepeatEffective> {
+    if !input.enabled {
+        return None;
+    }
+    let delay_s = if input.delay_s.is_finite() {
+        input.delay_s.clamp(5.0, 120.0)
+    } else {
+        5.0
+    };
+    Some(AutoRepeatEffective {
+        enabled: true,
+        repeats: input.repeats.clamp(1, 20) as u32,
+        delay_s: (delay_s * 1000.0).round() / 1000.0,
+        adaptive: input.adaptive.as_ref().map(normalize_adaptive_policy),
+        seed_strategy: input.seed_strategy,
+    })
+}
+
+/// Clamp an adaptive policy to the ranges `normalize_session_config` allows, ordering each
+/// min/max pair.
+pub fn normalize_adaptive_policy(input: &AdaptivePolicyInput) -> AdaptivePolicy {
+    let ordered = |a: u64, b: u64| (a.min(b), a.max(b));
+    let ms = |s: f64| seconds_to_ms_clamped(clamp_f64(s, 0.1, 60.0), 100, 60_000);
+    let (min_duration_ms, max_duration_ms) =
+        ordered(ms(input.min_duration_s), ms(input.max_duration_s));
+    let (min_digits, max_digits) = ordered(
+        clamp_i64(input.min_digits, 1, 18) as u64,
+        clamp_i64(input.max_digits, 1, 18) as u64,
+    );
+    let (min_total_numbers, max_total_numbers) = ordered(
+        clamp_i64(input.min_total_numbers, 1, 10_000) as u64,
+        clamp_i64(input.max_total_numbers, 1, 10_000) as u64,
+    );
+
+    AdaptivePolicy {
+        harden_after_correct: clamp_i64(input.harden_after_correct, 1, 20) as u32,
+        ease_after_wrong: clamp_i64(input.ease_after_wrong, 1, 20) as u32,
+        duration_step_ms: seconds_to_ms_clamped(
+            clamp_f64(input.duration_step_s, 0.0, 10.0),
+            0,
+            10_000,
+        ),
+        min_duration_ms,
+        max_duration_ms,
+        digits_step: clamp_i64(input.digits_step, 0, 17) as u32,
+        min_digits: min_digits as u32,
+        max_digits: max_digits as u32,
+        total_numbers_step: clamp_i64(input.total_numbers_step, 0, 10_000) as u32,
+        min_total_numbers: min_total_numbers as u32,
+        max_total_numbers: max_total_numbers as u32,
     }
+}
 
+pub fn validate_config(config: &SessionConfig) -> Result<(), FlashspanError> {
     // Keep generation simple and safe: 10^digits must fit in u64.
-    if config.digits_per_number > 18 {
-        return Err("digits_per_number must be <= 18".to_string());
+    if !(1..=18).contains(&config.digits_per_number) {
+        return Err(FlashspanError::out_of_range("digits_per_number", 1, 18));
     }
 
     // Defensive caps: UI enforces ranges, but IPC inputs must be treated as untrusted.
     // These limits are generous enough for real use while preventing accidental runaway sessions.
-    if config.total_numbers > 10_000 {
-        return Err("total_numbers must be <= 10000".to_string());
+    if !(1..=10_000).contains(&config.total_numbers) {
+        return Err(FlashspanError::out_of_range("total_numbers", 1, 10_000));
     }
 
-    if config.number_duration_ms > 60_000 {
-        return Err("number_duration_ms must be <= 60000".to_string());
+    if !(1..=60_000).contains(&config.number_duration_ms) {
+        return Err(FlashspanError::out_of_range(
+            "number_duration_ms",
+            1,
+            60_000,
+        ));
     }
 
     if config.delay_between_numbers_ms > 60_000 {
-        return Err("delay_between_numbers_ms must be <= 60000".to_string());
+        return Err(FlashspanError::out_of_range(
+            "delay_between_numbers_ms",
+            0,
+            60_000,
+        ));
+    }
+
+    match &config.timing_schedule {
+        TimingScheduleMs::Constant => {}
+        TimingScheduleMs::LinearRamp { end_duration_ms } => {
+            if !(MIN_FLASH_MS..=MAX_FLASH_MS).contains(end_duration_ms) {
+                return Err(FlashspanError::out_of_range(
+                    "timing_schedule.end_duration_ms",
+                    MIN_FLASH_MS as i64,
+                    MAX_FLASH_MS as i64,
+                ));
+            }
+        }
+        TimingScheduleMs::Steps { every, change_ms } => {
+            if *every == 0 {
+                return Err(FlashspanError::out_of_range(
+                    "timing_schedule.every",
+                    1,
+                    u32::MAX,
+                ));
+            }
+            if change_ms.unsigned_abs() > MAX_FLASH_MS {
+                return Err(FlashspanError::out_of_range(
+                    "timing_schedule.change_ms",
+                    -(MAX_FLASH_MS as i64),
+                    MAX_FLASH_MS as i64,
+                ));
+            }
+        }
+        TimingScheduleMs::PerIndex { durations_ms } => {
+            if durations_ms.is_empty() || durations_ms.len() > 10_000 {
+                return Err(FlashspanError::out_of_range(
+                    "timing_schedule.durations_ms.len",
+                    1,
+                    10_000,
+                ));
+            }
+            if durations_ms
+                .iter()
+                .any(|ms| !(MIN_FLASH_MS..=MAX_FLASH_MS).contains(ms))
+            {
+                return Err(FlashspanError::out_of_range(
+                    "timing_schedule.durations_ms",
+                    MIN_FLASH_MS as i64,
+                    MAX_FLASH_MS as i64,
+                ));
+            }
+        }
+    }
+
+    match config.operation {
+        OperationMode::Addition => {}
+        OperationMode::Multiplication => {
+            // The product of both operands must still fit in 18 digits.
+            let max = 18u32.saturating_sub(config.digits_per_number);
+            if config.second_operand_digits == 0 || config.second_operand_digits > max {
+                return Err(FlashspanError::out_of_range(
+                    "second_operand_digits",
+                    1,
+                    max,
+                ));
+            }
+        }
+        OperationMode::Division => {
+            if config.second_operand_digits == 0
+                || config.second_operand_digits > config.digits_per_number
+            {
+                return Err(FlashspanError::out_of_range(
+                    "second_operand_digits",
+                    1,
+                    config.digits_per_number,
+                ));
+            }
+        }
+    }
+
+    if let Some(DigitRange { min, max }) = config.digit_range
+        && (min == 0 || max > 18 || min > max)
+    {
+        return Err(FlashspanError::ConfigConflict {
+            field: "digit_range",
+            reason: "digit_range must satisfy 1 <= min <= max <= 18",
+        });
+    }
+
+    if config.digit_pattern.len() > 10_000 {
+        return Err(FlashspanError::out_of_range("digit_pattern.len", 0, 10_000));
+    }
+
+    if config.digit_pattern.iter().any(|&d| d == 0 || d > 18) {
+        return Err(FlashspanError::out_of_range("digit_pattern", 1, 18));
+    }
+
+    if config.digit_range.is_some() && !config.digit_pattern.is_empty() {
+        return Err(FlashspanError::ConfigConflict {
+            field: "digit_pattern",
+            reason: "digit_range cannot be combined with digit_pattern",
+        });
+    }
+
+    if config.decimal_places > MAX_DECIMAL_PLACES {
+        return Err(FlashspanError::out_of_range(
+            "decimal_places",
+            0,
+            MAX_DECIMAL_PLACES,
+        ));
+    }
+
+    if config.max_term_digits() + config.decimal_places > 18 {
+        return Err(FlashspanError::out_of_range(
+            "decimal_places",
+            0,
+            18u32.saturating_sub(config.max_term_digits()),
+        ));
+    }
+
+    if config.operation != OperationMode::Addition && config.decimal_places > 0 {
+        return Err(FlashspanError::ConfigConflict {
+            field: "decimal_places",
+            reason: "decimal_places only applies to addition",
+        });
+    }
+
+    if config.operation != OperationMode::Addition
+        && (config.digit_range.is_some() || !config.digit_pattern.is_empty())
+    {
+        return Err(FlashspanError::ConfigConflict {
+            field: "operation",
+            reason: "digit_range and digit_pattern only apply to addition",
+        });
+    }
+
+    if config.operation != OperationMode::Addition
+        && (config.formula_mode != FormulaMode::Any || config.carry_policy != CarryPolicy::Any)
+    {
+        return Err(FlashspanError::ConfigConflict {
+            field: "operation",
+            reason: "formula_mode and carry_policy only apply to addition",
+        });
+    }
+
+    if config.operation != OperationMode::Addition && config.dictation.is_some() {
+        return Err(FlashspanError::ConfigConflict {
+            field: "dictation",
+            reason: "dictation only applies to addition",
+        });
+    }
+
+    if config.formula_mode.requires_negative_numbers() && !config.allow_negative_numbers {
+        return Err(FlashspanError::ConfigConflict {
+            field: "formula_mode",
+            reason: "formula_mode requires allow_negative_numbers",
+        });
+    }
+
+    if config.carry_policy != CarryPolicy::Any && config.formula_mode != FormulaMode::Any {
+        return Err(FlashspanError::ConfigConflict {
+            field: "carry_policy",
+            reason: "carry_policy cannot be combined with formula_mode",
+        });
+    }
+
+    if let CarryPolicy::InColumn { column } = config.carry_policy {
+        let width = config.max_term_digits() + config.decimal_places;
+        if column >= width {
+            return Err(FlashspanError::out_of_range(
+                "carry_policy.column",
+                0,
+                i64::from(width) - 1,
+            ));
+        }
+    }
+
+    if config.carry_policy.requires_negative_numbers() && !config.allow_negative_numbers {
+        return Err(FlashspanError::ConfigConflict {
+            field: "carry_policy",
+            reason: "carry_policy requires allow_negative_numbers",
+        });
+    }
+
+    match &config.checkpoints {
+        CheckpointSchedule::Off => {}
+        _ if config.operation != OperationMode::Addition => {
+            return Err(FlashspanError::ConfigConflict {
+                field: "checkpoints",
+                reason: "checkpoints only apply to addition",
+            });
+        }
+        CheckpointSchedule::Every { every } => {
+            if !(1..=10_000).contains(every) {
+                return Err(FlashspanError::out_of_range("checkpoints.every", 1, 10_000));
+            }
+        }
+        CheckpointSchedule::After { positions } => {
+            if positions
+                .iter()
+                .any(|&p| p == 0 || p >= config.total_numbers)
+            {
+                return Err(FlashspanError::out_of_range(
+                    "checkpoints.positions",
+                    1,
+                    i64::from(config.total_numbers) - 1,
+                ));
+            }
+        }
     }
 
     Ok(())
@@ -0,0 +1,197 @@
+//! Clip voices for dictation sessions.
+//!
+//! A voice is a directory per language (`<voices>/en/`, `<voices>/ja/`) holding one clip per
+//! name in [`DictationLanguage::clip_names`], e.g. `en/seven.wav` or `ja/sanbyaku.ogg`. The
+//! bundled resources are searched first, then `voices/` in the app data directory, so a
+//! voice can be added or replaced without rebuilding. Clips are decoded once and cached.
+
+use crate::core::dictation::{DictationLanguage, SpokenPhrase, stretch_ratio, time_stretch};
+use crate::core::error::FlashspanError;
+use crate::session::recover_lock;
+use rodio::buffer::SamplesBuffer;
+use rodio::{ChannelCount, Decoder, SampleRate, Source};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+
+const CLIP_EXTENSIONS: [&str; 3] = ["wav", "ogg", "flac"];
+
+type ClipCache = Mutex<HashMap<(DictationLanguage, &'static str), Arc<Clip>>>;
+
+static VOICE_DIRS: OnceLock<Vec<PathBuf>> = OnceLock::new();
+static CLIPS: OnceLock<ClipCache> = OnceLock::new();
+
+/// Where voices are looked up, in order. Set once at startup.
+pub fn set_voice_dirs(dirs: Vec<PathBuf>) {
+    let _ = VOICE_DIRS.set(dirs);
+}
+
+fn voice_dirs() -> &'static [PathBuf] {
+    VOICE_DIRS.get().map(Vec::as_slice).unwrap_or_default()
+}
+
+struct Clip {
+    samples: Vec<f32>,
+    channels: ChannelCount,
+    sample_rate: SampleRate,
+}
+
+fn find_clip(dirs: &[PathBuf], language: DictationLanguage, name: &str) -> Option<PathBuf> {
+    dirs.iter().find_map(|dir| {
+        CLIP_EXTENSIONS
+            .iter()
+            .map(|ext| dir.join(language.code()).join(format!("{}.{}", name, ext)))
+            .find(|path| path.is_file())
+    })
+}
+
+fn decode_clip(path: &Path) -> Result<Clip, FlashspanError> {
+    let audio_error = |e: &dyn std::fmt::Display| FlashspanError::Audio {
+        detail: format!("{}: {}", path.display(), e),
+    };
+    let file = File::open(path).map_err(|e| audio_error(&e))?;
+    let decoder = Decoder::try_from(file).map_err(|e| audio_error(&e))?;
+    let (channels, sample_rate) = (decoder.channels(), decoder.sample_rate());
+    Ok(Clip {
+        samples: decoder.collect(),
+        channels,
+        sample_rate,
+    })
+}
+
+fn clip(language: DictationLanguage, name: &'static str) -> Result<Arc<Clip>, FlashspanError> {
+    let cache = CLIPS.get_or_init(Default::default);
+    if let Some(clip) = recover_lock(cache, "dictation clips").get(&(language, name)) {
+        return Ok(Arc::clone(clip));
+    }
+
+    let path = find_clip(voice_dirs(), language, name).ok_or_else(|| FlashspanError::Audio {
+        detail: format!("missing {} dictation clip \"{}\"", language.code(), name),
+    })?;
+    let clip = Arc::new(decode_clip(&path)?);
+    recover_lock(cache, "dictation clips").insert((language, name), Arc::clone(&clip));
+    Ok(clip)
+}
+
+/// Concatenate the phrase's clips and stretch them to fit its flash. Runs on the audio
+/// worker, never the session thread.
+pub fn render(phrase: &SpokenPhrase) -> Result<SamplesBuffer, FlashspanError> {
+    let clips = phrase
+        .clips
+        .iter()
+        .map(|&name| clip(phrase.language, name))
+        .collect::<Result<Vec<_>, _>>()?;
+    let Some(first) = clips.first() else {
+        return Err(FlashspanError::Audio {
+            detail: "nothing to say".to_string(),
+        });
+    };
+    let (channels, sample_rate) = (first.channels, first.sample_rate);
+    if clips
+        .iter()
+        .any(|c| c.channels != channels || c.sample_rate != sample_rate)
+    {
+        return Err(FlashspanError::Audio {
+            detail: format!(
+                "{} dictation clips must share one sample rate and channel count",
+                phrase.language.code()
+            ),
+        });
+    }
+
+    let samples: Vec<f32> = clips
+        .iter()
+        .flat_map(|c| c.samples.iter().copied())
+        .collect();
+    let (channel_count, rate) = (u16::from(channels), u32::from(sample_rate));
+    let frames = samples.len() as u64 / u64::from(channel_count);
+    let natural_ms = frames * 1000 / u64::from(rate);
+    let ratio = stretch_ratio(natural_ms, phrase.target_ms());
+    let stretched = time_stretch(&samples, channel_count, rate, ratio);
+    Ok(SamplesBuffer::new(channels, sample_rate, stretched))
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VoiceStatus {
+    pub language: DictationLanguage,
+    /// Clips with no file in any voice directory; dictation in this language needs all.
+    pub missing: Vec<&'static str>,
+}
+
+fn voice_status(dirs: &[PathBuf], language: DictationLanguage) -> VoiceStatus {
+    VoiceStatus {
+        language,
+        missing: language
+            .clip_names()
+            .iter()
+            .copied()
+            .filter(|name| find_clip(dirs, language, name).is_none())
+            .collect(),
+    }
+}
+
+/// Which dictation languages have a complete voice installed.
+#[tauri::command]
+pub fn list_voices() -> Vec<VoiceStatus> {
+    [DictationLanguage::English, DictationLanguage::Japanese]
+        .into_iter()
+        .map(|language| voice_status(voice_dirs(), language))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::scratch_dir;
+    use std::fs;
+
+    #[test]
+    fn clips_are_found_by_language_and_extension_in_dir_order() {
+        let root = scratch_dir("voices");
+        let (bundled, user) = (root.join("bundled"), root.join("user"));
+        fs::create_dir_all(bundled.join("en")).unwrap();
+        fs::create_dir_all(user.join("en")).unwrap();
+        fs::write(bundled.join("en").join("seven.wav"), b"").unwrap();
+        fs::write(user.join("en").join("seven.ogg"), b"").unwrap();
+        fs::write(user.join("en").join("minus.flac"), b"").unwrap();
+        let dirs = vec![bundled.clone(), user.clone()];
+
+        let en = DictationLanguage::English;
+        assert_eq!(
+            find_clip(&dirs, en, "seven"),
+            Some(bundled.join("en").join("seven.wav"))
+        );
+        assert_eq!(
+            find_clip(&dirs, en, "minus"),
+            Some(user.join("en").join("minus.flac"))
+        );
+        assert_eq!(find_clip(&dirs, DictationLanguage::Japanese, "seven"), None);
+
+        let status = voice_status(&dirs, en);
+        assert_eq!(status.missing.len(), en.clip_names().len() - 2);
+        assert!(!status.missing.contains(&"seven"));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn bundled_voices_are_complete_and_decode() {
+        let dirs = vec![Path::new(env!("CARGO_MANIFEST_DIR")).join("voices")];
+        for language in [DictationLanguage::English, DictationLanguage::Japanese] {
+            assert!(voice_status(&dirs, language).missing.is_empty());
+            let clips: Vec<Clip> = language
+                .clip_names()
+                .iter()
+                .map(|name| decode_clip(&find_clip(&dirs, language, name).unwrap()).unwrap())
+                .collect();
+            assert!(
+                clips
+                    .iter()
+                    .all(|c| c.channels == clips[0].channels
+                        && c.sample_rate == clips[0].sample_rate)
+            );
+        }
+    }
+}
@@ -0,0 +1,464 @@
+//! On-disk session history.
+//!
+//! Every completed session is appended to a JSON Lines log in the app data dir. Submitting an
+//! answer appends the session again with its answer, so each write is a single append. Loading
+//! keeps the latest line for each session and, when the log holds superseded lines, rewrites it
+//! with one line per session.
+
+use crate::core::analytics::{SampleAnswer, SessionSample};
+use crate::core::error::FlashspanError;
+use crate::core::types::{
+    CheckpointResult, OperandPair, OperationMode, SessionComplete, SessionConfigEffective,
+};
+use crate::session::recover_lock;
+use crate::settings::write_atomic;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+pub const HISTORY_FILE_NAME: &str = "history.jsonl";
+
+/// The submitted answer for a history record.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HistoryAnswer {
+    /// In decimal sessions, counted in units of 10^-decimal_places.
+    pub provided_sum: i64,
+    /// One answer per pair, for multiplication and division sessions.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub provided_answers: Vec<i64>,
+    pub correct: bool,
+    pub delta: i64,
+    pub submitted_at_ms: u64,
+    /// From the end of the session to the answer; absent in records saved before it was kept.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub answer_ms: Option<u64>,
+    /// Points under the scoring weights in effect when the answer was given.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub score: Option<u32>,
+}
+
+/// One completed session, with its answer once submitted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryRecord {
+    pub session_id: u64,
+    pub started_at_ms: u64,
+    pub completed_at_ms: u64,
+    pub config: SessionConfigEffective,
+    pub numbers: Vec<i64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pairs: Vec<OperandPair>,
+    #[serde(default)]
+    pub decimal_places: u32,
+    pub expected_sum: i64,
+    #[serde(default)]
+    pub profile_id: Option<String>,
+    /// Generation seed; records saved before seeds were kept read as 0.
+    #[serde(default)]
+    pub seed: u64,
+    /// `None` until an answer is submitted.
+    #[serde(default)]
+    pub answer: Option<HistoryAnswer>,
+    /// Subtotals asked for during the session.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub checkpoints: Vec<CheckpointResult>,
+}
+
+impl HistoryRecord {
+    pub fn from_complete(result: &SessionComplete) -> Self {
+        Self {
+            session_id: result.session_id,
+            started_at_ms: result.started_at_ms,
+            completed_at_ms: result.completed_at_ms,
+            config: result.config.clone(),
+            numbers: result.numbers.clone(),
+            pairs: result.pairs.clone(),
+            decimal_places: result.decimal_places,
+            expected_sum: result.sum,
+            profile_id: result.profile_id.clone(),
+            seed: result.seed,
+            answer: None,
+            checkpoints: result.checkpoints.clone(),
+        }
+    }
+
+    /// What [`crate::core::analytics::analyze`] needs from this record.
+    pub fn sample(&self) -> SessionSample {
+        SessionSample {
+            completed_at_ms: self.completed_at_ms,
+            config: self.config.clone(),
+            answer: self.answer.as_ref().map(|a| SampleAnswer {
+                correct: a.correct,
+                delta: a.delta,
+                submitted_at_ms: a.submitted_at_ms,
+            }),
+        }
+    }
+
+    /// Session ids restart with the app, so the start time is part of the key.
+    fn key(&self) -> (u64, u64) {
+        (self.session_id, self.started_at_ms)
+    }
+}
+
+/// Order of [`HistoryStore::query`] results.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryOrder {
+    #[default]
+    Newest,
+    /// Highest score first, newest first among equals; unscored records last.
+    Score,
+}
+
+/// Query for [`HistoryStore::query`]. Every field is optional; unset fields match anything.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct HistoryFilter {
+    /// Inclusive lower bound on `completed_at_ms`.
+    #[serde(alias = "fromMs")]
+    pub from_ms: Option<u64>,
+    /// Exclusive upper bound on `completed_at_ms`.
+    #[serde(alias = "toMs")]
+    pub to_ms: Option<u64>,
+    /// Only answered sessions with this outcome.
+    pub correct: Option<bool>,
+    #[serde(alias = "digitsPerNumber")]
+    pub digits_per_number: Option<u32>,
+    #[serde(alias = "totalNumbers")]
+    pub total_numbers: Option<u32>,
+    pub operation: Option<OperationMode>,
+    #[serde(alias = "decimalPlaces")]
+    pub decimal_places: Option<u32>,
+    #[serde(alias = "allowNegativeNumbers")]
+    pub allow_negative_numbers: Option<bool>,
+    /// Most records to return, in `order`.
+    pub limit: Option<usize>,
+    pub order: HistoryOrder,
+}
+
+impl HistoryFilter {
+    pub fn matches(&self, record: &HistoryRecord) -> bool {
+        let config = &record.config;
+        self.from_ms
+            .is_none_or(|from| record.completed_at_ms >= from)
+            && self.to_ms.is_none_or(|to| record.completed_at_ms < to)
+            && self
+                .correct
+                .is_none_or(|c| record.answer.as_ref().is_some_and(|a| a.correct == c))
+            && self
+                .digits_per_number
+                .is_none_or(|d| config.digits_per_number == d)
+            && self.total_numbers.is_none_or(|n| config.total_numbers == n)
+            && self.operation.is_none_or(|op| config.operation == op)
+            && self
+                .decimal_places
+                .is_none_or(|p| record.decimal_places == p)
+            && self
+                .allow_negative_numbers
+                .is_none_or(|neg| config.allow_negative_numbers == neg)
+    }
+}
+
+/// Append-only JSON Lines store of [`HistoryRecord`]s; the latest line for a session wins.
+pub struct HistoryStore {
+    /// Log file of the active profile; the lock also serialises writes.
+    path: Mutex<PathBuf>,
+}
+
+impl HistoryStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path: Mutex::new(path),
+        }
+    }
+
+    /// Point the store at another log, e.g. after switching profiles.
+    pub fn set_path(&self, path: PathBuf) {
+        *recover_lock(&self.path, "history path") = path;
+    }
+
+    pub fn append(&self, record: &HistoryRecord) -> Result<(), FlashspanError> {
+        let mut line = serde_json::to_string(record).map_err(FlashspanError::storage)?;
+        line.push('\n');
+
+        let path = recover_lock(&self.path, "history path");
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .map_err(|e| FlashspanError::storage(format!("history dir: {}", e)))?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&*path)
+            .map_err(|e| FlashspanError::storage(format!("history open: {}", e)))?;
+        file.write_all(line.as_bytes())
+            .map_err(|e| FlashspanError::storage(format!("history write: {}", e)))
+    }
+
+    /// All records, oldest first, with each session's latest line winning.
+    /// Unreadable lines (e.g. a write cut short by a crash) are skipped, and a log holding
+    /// any such lines or duplicates is rewritten with one line per session.
+    pub fn load(&self) -> Result<Vec<HistoryRecord>, FlashspanError> {
+        let path = recover_lock(&self.path, "history path");
+        let (records, lines) = read_records(&path)?;
+        if lines > records.len()
+            && let Err(e) = write_records(&path, &records)
+        {
+            warn!("failed to compact {}: {}", path.display(), e);
+        }
+        Ok(records)
+    }
+
+    /// Records matching `filter`, in its order.
+    pub fn query(&self, filter: &HistoryFilter) -> Result<Vec<HistoryRecord>, FlashspanError> {
+        let mut records: Vec<HistoryRecord> = self
+            .load()?
+            .into_iter()
+            .filter(|r| filter.matches(r))
+            .collect();
+        records.sort_by_key(|r| std::cmp::Reverse(r.completed_at_ms));
+        if filter.order == HistoryOrder::Score {
+            // Stable, so equal scores stay newest first.
+            records.sort_by_key(|r| std::cmp::Reverse(r.answer.as_ref().and_then(|a| a.score)));
+        }
+        if let Some(limit) = filter.limit {
+            records.truncate(limit);
+        }
+        Ok(records)
+    }
+}
+
+/// The log's records, latest line per session, and how many non-empty lines it had.
+fn read_records(path: &Path) -> Result<(Vec<HistoryRecord>, usize), FlashspanError> {
+    let file = match fs::File::open(path) {
+        Ok(f) => f,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok((Vec::new(), 0)),
+        Err(e) => return Err(FlashspanError::storage(format!("history open: {}", e))),
+    };
+
+    let mut records: Vec<HistoryRecord> = Vec::new();
+    let mut positions: HashMap<(u64, u64), usize> = HashMap::new();
+    let mut lines = 0;
+    for (line_no, line) in BufReader::new(file).lines().enumerate() {
+        let line = line.map_err(|e| FlashspanError::storage(format!("history read: {}", e)))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        lines += 1;
+        let record: HistoryRecord = match serde_json::from_str(&line) {
+            Ok(r) => r,
+            Err(e) => {
+                warn!("skipping history line {}: {}", line_no + 1, e);
+                continue;
+            }
+        };
+        match positions.get(&record.key()) {
+            Some(&pos) => records[pos] = record,
+            None => {
+                positions.insert(record.key(), records.len());
+                records.push(record);
+            }
+        }
+    }
+    Ok((records, lines))
+}
+
+fn write_records(path: &Path, records: &[HistoryRecord]) -> Result<(), FlashspanError> {
+    let mut text = String::new();
+    for record in records {
+        text.push_str(&serde_json::to_string(record).map_err(FlashspanError::storage)?);
+        text.push('\n');
+    }
+    write_atomic(path, &text).map_err(|e| FlashspanError::storage(format!("history write: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::scratch_dir;
+
+    fn temp_store(name: &str) -> HistoryStore {
+        let dir = scratch_dir(&format!("history-{}", name));
+        HistoryStore::new(dir.join(HISTORY_FILE_NAME))
+    }
+
+    fn record(session_id: u64, completed_at_ms: u64, digits: u32) -> HistoryRecord {
+        HistoryRecord::from_complete(&SessionComplete {
+            session_id,
+            numbers: vec![10, 20],
+            sum: 30,
+            decimal_places: 0,
+            pairs: vec![],
+            started_at_ms: completed_at_ms - 1000,
+            completed_at_ms,
+            completed_at: None,
+            config: SessionConfigEffective {
+                digits_per_number: digits,
+                total_numbers: 2,
+                ..Default::default()
+            },
+            profile_id: None,
+            seed: 0,
+            checkpoints: Vec::new(),
+        })
+    }
+
+    fn answered(mut r: HistoryRecord, provided_sum: i64) -> HistoryRecord {
+        let delta = provided_sum - r.expected_sum;
+        r.answer = Some(HistoryAnswer {
+            provided_sum,
+            provided_answers: vec![],
+            correct: delta == 0,
+            delta,
+            submitted_at_ms: r.completed_at_ms + 500,
+            answer_ms: Some(500),
+            score: Some(if delta == 0 { provided_sum as u32 } else { 0 }),
+        });
+        r
+    }
+
+    #[test]
+    fn missing_file_loads_empty() {
+        let store = temp_store("missing");
+        assert!(store.load().unwrap().is_empty());
+    }
+
+    #[test]
+    fn later_line_replaces_unanswered_record() {
+        let store = temp_store("replace");
+        store.append(&record(1, 5_000, 2)).unwrap();
+        store.append(&record(2, 6_000, 2)).unwrap();
+        store.append(&answered(record(1, 5_000, 2), 30)).unwrap();
+
+        let records = store.load().unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].session_id, 1);
+        assert!(records[0].answer.as_ref().unwrap().correct);
+        assert!(records[1].answer.is_none());
+        // The duplicate line is compacted away.
+        let text = fs::read_to_string(recover_lock(&store.path, "history path").clone()).unwrap();
+        assert_eq!(text.lines().count(), 2);
+        assert_eq!(store.load().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn resubmitted_answer_keeps_one_record_per_session() {
+        let store = temp_store("resubmit");
+        let path = recover_lock(&store.path, "history path").clone();
+        store.append(&record(1, 5_000, 2)).unwrap();
+        store.append(&record(2, 6_000, 2)).unwrap();
+        store.append(&answered(record(1, 5_000, 2), 31)).unwrap();
+        store.append(&answered(record(1, 5_000, 2), 30)).unwrap();
+        store.append(&answered(record(3, 7_000, 2), 30)).unwrap();
+        // Answers are appended; nothing is rewritten until the log is loaded.
+        assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 5);
+
+        let records = store.load().unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 3);
+        let ids: Vec<u64> = records.iter().map(|r| r.session_id).collect();
+        assert_eq!(ids, vec![1, 2, 3]);
+        assert!(records[0].answer.as_ref().unwrap().correct);
+    }
+
+    #[test]
+    fn reused_session_id_after_restart_is_a_new_record() {
+        let store = temp_store("restart");
+        store.append(&record(1, 5_000, 2)).unwrap();
+        store.append(&record(1, 9_000, 2)).unwrap();
+        assert_eq!(store.load().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn corrupt_lines_are_skipped() {
+        let store = temp_store("corrupt");
+        store.append(&record(1, 5_000, 2)).unwrap();
+        {
+            let mut f = OpenOptions::new()
+                .append(true)
+                .open(recover_lock(&store.path, "history path").clone())
+                .unwrap();
+            f.write_all(b"{\"session_id\": 2, \"trunc").unwrap();
+            f.write_all(b"\n").unwrap();
+        }
+        store.append(&record(3, 7_000, 2)).unwrap();
+        let ids: Vec<u64> = store.load().unwrap().iter().map(|r| r.session_id).collect();
+        assert_eq!(ids, vec![1, 3]);
+    }
+
+    #[test]
+    fn query_filters_by_date_config_and_correctness() {
+        let store = temp_store("query");
+        store.append(&answered(record(1, 1_000, 2), 30)).unwrap();
+        store.append(&answered(record(2, 2_000, 3), 31)).unwrap();
+        store.append(&record(3, 3_000, 2)).unwrap();
+        store.append(&answered(record(4, 4_000, 2), 30)).unwrap();
+
+        let ids = |filter: HistoryFilter| -> Vec<u64> {
+            store
+                .query(&filter)
+                .unwrap()
+                .iter()
+                .map(|r| r.session_id)
+                .collect()
+        };
+
+        assert_eq!(ids(HistoryFilter::default()), vec![4, 3, 2, 1]);
+        assert_eq!(
+            ids(HistoryFilter {
+                from_ms: Some(2_000),
+                to_ms: Some(4_000),
+                ..Default::default()
+            }),
+            vec![3, 2]
+        );
+        assert_eq!(
+            ids(HistoryFilter {
+                correct: Some(true),
+                ..Default::default()
+            }),
+            vec![4, 1]
+        );
+        assert_eq!(
+            ids(HistoryFilter {
+                correct: Some(false),
+                ..Default::default()
+            }),
+            vec![2]
+        );
+        assert_eq!(
+            ids(HistoryFilter {
+                digits_per_number: Some(2),
+                limit: Some(2),
+                ..Default::default()
+            }),
+            vec![4, 3]
+        );
+    }
+
+    #[test]
+    fn query_ranks_by_score() {
+        let store = temp_store("score");
+        store.append(&answered(record(1, 1_000, 2), 30)).unwrap();
+        store.append(&answered(record(2, 2_000, 2), 31)).unwrap();
+        store.append(&record(3, 3_000, 2)).unwrap();
+        let mut best = answered(record(4, 4_000, 2), 30);
+        best.answer.as_mut().unwrap().score = Some(120);
+        store.append(&best).unwrap();
+        store.append(&answered(record(5, 5_000, 2), 30)).unwrap();
+
+        let ids: Vec<u64> = store
+            .query(&HistoryFilter {
+                order: HistoryOrder::Score,
+                ..Default::default()
+            })
+            .unwrap()
+            .iter()
+            .map(|r| r.session_id)
+            .collect();
+        assert_eq!(ids, vec![4, 5, 1, 2, 3]);
+    }
+}
@@ -1,4 +1,8 @@
 pub mod core;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod race;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod session;
 
 #[cfg(not(target_arch = "wasm32"))]
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -1,31 +1,72 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+use app_lib::core;
+#[cfg(not(target_arch = "wasm32"))]
+use app_lib::{race, session};
+
 #[cfg(not(target_arch = "wasm32"))]
 mod audio;
-mod core;
 #[cfg(not(target_arch = "wasm32"))]
-mod session;
+mod dictation;
+#[cfg(not(target_arch = "wasm32"))]
+mod history;
+#[cfg(not(target_arch = "wasm32"))]
+mod presets;
+#[cfg(not(target_arch = "wasm32"))]
+mod profiles;
+#[cfg(not(target_arch = "wasm32"))]
+mod settings;
+#[cfg(not(target_arch = "wasm32"))]
+mod sound_packs;
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod test_support;
 
 #[cfg(not(target_arch = "wasm32"))]
 mod native_app {
+    use crate::audio::AudioLevels;
     use crate::core::{
+        analytics::{self, AnalyticsReport, SessionSample},
+        answer::{Submission, ValidationResult, parse_answer_list_text, parse_answer_text},
+        decimal::parse_fixed,
+        diagnosis::{MistakeCandidate, diagnose},
+        dictation::SpokenPhrase,
+        engine::{build_session_plan, random_seed},
+        error::FlashspanError,
+        plan_file,
+        race::Leaderboard,
+        scoring::ScoringConfig,
         types::{
-            AutoRepeatPlan, ClearScreen, SessionComplete, SessionConfigEffective,
-            SessionConfigInput, ShowNumber,
+            AdaptiveAdjustment, AdaptiveRun, AdaptiveState, AutoRepeatConfigInput,
+            AutoRepeatEffective, AutoRepeatPlan, Checkpoint, CheckpointResult, ClearScreen,
+            SessionComplete, SessionConfigEffective, SessionConfigInput, SessionPaused,
+            SessionResumed, ShowNumber, ShowPair, TimingReport,
         },
-        validate::normalize_session_config,
+        validate::{normalize_auto_repeat, normalize_session_config, validate_config},
     };
-    use crate::session::{SessionEmitter, SessionManager, recover_lock};
+    use crate::history::{HistoryAnswer, HistoryFilter, HistoryRecord, HistoryStore};
+    use crate::presets::{ImportSummary, Preset, PresetStore, PresetView};
+    use crate::profiles::{Profile, ProfileList, ProfileStore};
+    use crate::race::{DEFAULT_RACE_PORT, RaceClient, RaceHost};
+    use crate::session::{SessionEmitter, SessionManager, now_epoch_ms, recover_lock};
+    use crate::settings::{AppSettings, ColorScheme, SettingsState, ThemeMode};
+    use crate::sound_packs::SoundPackInfo;
     use log::warn;
-    use serde::Deserialize;
-    use std::sync::{Arc, Mutex};
+    use std::sync::Arc;
     use std::thread;
     use std::time::Instant;
-    use std::time::{SystemTime, UNIX_EPOCH};
-    use tauri::Emitter;
+    use tauri::{Emitter, Manager};
 
     struct TauriEmitter {
         app: tauri::AppHandle,
+        profile_id: String,
+    }
+
+    impl TauriEmitter {
+        /// Emitter for a session run under the currently active profile.
+        fn new(app: tauri::AppHandle) -> Self {
+            let profile_id = app.state::<ProfileStore>().active_id();
+            Self { app, profile_id }
+        }
     }
 
     impl SessionEmitter for TauriEmitter {
@@ -41,9 +82,51 @@ mod native_app {
             let _ = self.app.emit("show_number", payload);
         }
 
+        fn show_pair(&self, payload: ShowPair) {
+            let _ = self.app.emit("show_pair", payload);
+        }
+
         fn session_complete(&self, payload: SessionComplete) {
+            let history = self.app.state::<Arc<HistoryStore>>();
+            if let Err(e) = history.append(&HistoryRecord::from_complete(&payload)) {
+                warn!(
+                    "failed to record session {} in history: {}",
+                    payload.session_id, e
+                );
+            }
             let _ = self.app.emit("session_complete", payload);
         }
+
+        fn session_paused(&self, payload: SessionPaused) {
+            let _ = self.app.emit("session_paused", payload);
+        }
+
+        fn session_resumed(&self, payload: SessionResumed) {
+            let _ = self.app.emit("session_resumed", payload);
+        }
+
+        fn checkpoint(&self, payload: Checkpoint) {
+            let _ = self.app.emit("checkpoint", payload);
+        }
+
+        fn profile_id(&self) -> Option<String> {
+            Some(self.profile_id.clone())
+        }
+
+        fn session_starting(&self) {
+            crate::audio::cut();
+        }
+
+        fn beep(&self) {
+            // Queued on the audio worker, so this returns immediately.
+            let _ = crate::audio::play_kind("beep");
+        }
+
+        fn speak(&self, phrase: SpokenPhrase) {
+            if let Err(e) = crate::audio::speak(phrase) {
+                warn!("dictation: {}", e);
+            }
+        }
     }
 
     #[tauri::command]
@@ -57,22 +140,45 @@ mod native_app {
     }
 
     #[tauri::command]
-    fn cancel_auto_repeat(manager: tauri::State<'_, Arc<SessionManager>>) {
-        manager.configure_auto_repeat(None);
+    fn pause_session(manager: tauri::State<'_, Arc<SessionManager>>) -> Result<(), FlashspanError> {
+        manager.pause()
     }
 
-    #[derive(Debug, Clone, Deserialize)]
-    struct AutoRepeatConfigInput {
-        enabled: bool,
-        repeats: i64,
-        delay_s: f64,
+    #[tauri::command]
+    fn resume_session(
+        manager: tauri::State<'_, Arc<SessionManager>>,
+    ) -> Result<(), FlashspanError> {
+        manager.resume()
     }
 
-    #[derive(Debug, Clone, serde::Serialize)]
-    struct AutoRepeatEffective {
-        enabled: bool,
-        repeats: u32,
-        delay_s: f64,
+    /// Answer the subtotal a `checkpoint` event asked for; the session continues after it.
+    /// In decimal sessions `provided_sum` counts units of 10^-decimal_places.
+    #[tauri::command]
+    fn answer_checkpoint(
+        manager: tauri::State<'_, Arc<SessionManager>>,
+        race: tauri::State<'_, RaceState>,
+        session_id: u64,
+        provided_sum: i64,
+    ) -> Result<CheckpointResult, FlashspanError> {
+        let result = manager.answer_checkpoint(session_id, provided_sum)?;
+        // Race hosts leave the time spent here out of the answer time.
+        if let Some(client) = recover_lock(&race.client, "race client").as_ref() {
+            client.checkpoint(session_id, result.index);
+        }
+        Ok(result)
+    }
+
+    #[tauri::command]
+    fn get_timing_report(
+        manager: tauri::State<'_, Arc<SessionManager>>,
+        session_id: u64,
+    ) -> Result<TimingReport, FlashspanError> {
+        manager.timing_report_for(session_id)
+    }
+
+    #[tauri::command]
+    fn cancel_auto_repeat(manager: tauri::State<'_, Arc<SessionManager>>) {
+        manager.configure_auto_repeat(None);
     }
 
     #[derive(Debug, Clone, serde::Serialize)]
@@ -80,6 +186,8 @@ mod native_app {
         session_id: u64,
         effective_config: SessionConfigEffective,
         effective_auto_repeat: Option<AutoRepeatEffective>,
+        /// Seed of this session; pass it back to `start_session` to get the same numbers.
+        seed: u64,
     }
 
     #[derive(Debug, Clone, serde::Serialize)]
@@ -87,6 +195,9 @@ mod native_app {
         session_id: u64,
         next_start_at_ms: u64,
         remaining: u32,
+        /// How the answer moved the next round, for adaptive runs.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        adjustment: Option<AdaptiveAdjustment>,
     }
 
     #[derive(Debug, Clone, serde::Serialize)]
@@ -96,55 +207,70 @@ mod native_app {
         remaining: u32,
     }
 
-    #[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
-    #[serde(rename_all = "snake_case")]
-    enum ColorScheme {
-        Midnight,
-        Ivory,
-        Crimson,
-        Aqua,
-        Violet,
-        Amber,
+    #[tauri::command]
+    fn get_app_settings(settings: tauri::State<'_, SettingsState>) -> AppSettings {
+        settings.get()
     }
 
-    #[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
-    #[serde(rename_all = "snake_case")]
-    enum ThemeMode {
-        Dark,
-        Light,
+    #[tauri::command]
+    fn get_sound_enabled() -> bool {
+        crate::audio::is_enabled()
     }
 
-    #[derive(Debug, Clone, serde::Serialize)]
-    struct AppSettings {
-        color_scheme: ColorScheme,
-        theme_mode: ThemeMode,
+    #[tauri::command]
+    fn set_sound_enabled(settings: tauri::State<'_, SettingsState>, enabled: bool) {
+        crate::audio::set_enabled(enabled);
+        settings.persist();
     }
 
-    #[derive(Default)]
-    struct SettingsState(Mutex<AppSettings>);
+    #[tauri::command]
+    fn get_audio_levels() -> AudioLevels {
+        crate::audio::levels()
+    }
 
-    impl Default for AppSettings {
-        fn default() -> Self {
-            Self {
-                color_scheme: ColorScheme::Midnight,
-                theme_mode: ThemeMode::Dark,
-            }
-        }
+    /// Set master volume and per-event gains (percent); sounds already playing follow the
+    /// master volume at once.
+    #[tauri::command]
+    fn set_audio_levels(
+        settings: tauri::State<'_, SettingsState>,
+        levels: AudioLevels,
+    ) -> Result<AudioLevels, FlashspanError> {
+        levels.validate()?;
+        crate::audio::set_levels(levels);
+        settings.persist();
+        Ok(levels)
     }
 
+    /// Play sounds from an installed pack, or the built-in sounds for `None`; saved per profile.
     #[tauri::command]
-    fn get_app_settings(settings: tauri::State<'_, SettingsState>) -> AppSettings {
-        recover_lock(&settings.0, "settings").clone()
+    fn select_sound_pack(
+        settings: tauri::State<'_, SettingsState>,
+        id: Option<String>,
+    ) -> Result<Option<SoundPackInfo>, FlashspanError> {
+        let info = crate::sound_packs::select(id.as_deref())?;
+        settings.persist();
+        Ok(info)
     }
 
     #[tauri::command]
-    fn get_sound_enabled() -> bool {
-        crate::audio::is_enabled()
+    fn get_scoring(settings: tauri::State<'_, SettingsState>) -> ScoringConfig {
+        settings.scoring()
     }
 
+    /// Set the weights answers are scored with from now on; saved per profile. Scores
+    /// already in the history keep the weights they were given under.
     #[tauri::command]
-    fn set_sound_enabled(enabled: bool) {
-        crate::audio::set_enabled(enabled);
+    fn set_scoring(
+        settings: tauri::State<'_, SettingsState>,
+        race: tauri::State<'_, RaceState>,
+        scoring: ScoringConfig,
+    ) -> Result<ScoringConfig, FlashspanError> {
+        scoring.validate()?;
+        settings.set_scoring(scoring);
+        if let Some(host) = recover_lock(&race.host, "race host").as_ref() {
+            host.set_scoring(scoring);
+        }
+        Ok(scoring)
     }
 
     #[tauri::command]
@@ -152,12 +278,8 @@ mod native_app {
         app: tauri::AppHandle,
         settings: tauri::State<'_, SettingsState>,
         color_scheme: ColorScheme,
-    ) -> Result<AppSettings, String> {
-        let updated = {
-            let mut guard = recover_lock(&settings.0, "settings");
-            guard.color_scheme = color_scheme;
-            guard.clone()
-        };
+    ) -> Result<AppSettings, FlashspanError> {
+        let updated = settings.update(|s| s.color_scheme = color_scheme);
         let _ = app.emit("app_settings_changed", updated.clone());
         Ok(updated)
     }
@@ -167,101 +289,122 @@ mod native_app {
         app: tauri::AppHandle,
         settings: tauri::State<'_, SettingsState>,
         theme_mode: ThemeMode,
-    ) -> Result<AppSettings, String> {
-        let updated = {
-            let mut guard = recover_lock(&settings.0, "settings");
-            guard.theme_mode = theme_mode;
-            guard.clone()
-        };
+    ) -> Result<AppSettings, FlashspanError> {
+        let updated = settings.update(|s| s.theme_mode = theme_mode);
 
         let _ = app.emit("app_settings_changed", updated.clone());
         Ok(updated)
     }
 
-    #[derive(Debug, Clone, serde::Serialize)]
-    struct ValidationResult {
-        expected_sum: i64,
-        provided_sum: i64,
-        correct: bool,
-        delta: i64,
+    #[tauri::command]
+    fn list_profiles(profiles: tauri::State<'_, ProfileStore>) -> ProfileList {
+        profiles.list()
     }
 
-    #[derive(Debug, Clone, serde::Serialize)]
-    struct SubmitAnswerResponse {
-        validation: ValidationResult,
-        auto_repeat_waiting: Option<AutoRepeatWaitingPayload>,
-        message: String,
+    #[tauri::command]
+    fn create_profile(
+        profiles: tauri::State<'_, ProfileStore>,
+        name: String,
+    ) -> Result<Profile, FlashspanError> {
+        profiles.create(&name)
     }
 
-    fn parse_answer_text(input: &str) -> Result<i64, String> {
-        let cleaned = input.trim().replace(',', "");
-        if cleaned.is_empty() {
-            return Err("Enter a single integer answer (e.g. 42 or -17).".to_string());
-        }
+    #[tauri::command]
+    fn rename_profile(
+        profiles: tauri::State<'_, ProfileStore>,
+        id: String,
+        name: String,
+    ) -> Result<Profile, FlashspanError> {
+        profiles.rename(&id, &name)
+    }
 
-        // Defensive bound: avoid absurd payload sizes.
-        if cleaned.len() > 64 {
-            return Err("Enter a single integer answer (e.g. 42 or -17).".to_string());
-        }
+    #[tauri::command]
+    fn delete_profile(
+        profiles: tauri::State<'_, ProfileStore>,
+        id: String,
+    ) -> Result<(), FlashspanError> {
+        profiles.delete(&id)
+    }
 
-        cleaned
-            .parse::<i64>()
-            .map_err(|_| "Enter a single integer answer (e.g. 42 or -17).".to_string())
+    /// Stop any running session, then load the profile's settings and history.
+    #[tauri::command]
+    fn switch_profile(
+        app: tauri::AppHandle,
+        profiles: tauri::State<'_, ProfileStore>,
+        manager: tauri::State<'_, Arc<SessionManager>>,
+        settings: tauri::State<'_, SettingsState>,
+        presets: tauri::State<'_, PresetStore>,
+        history: tauri::State<'_, Arc<HistoryStore>>,
+        id: String,
+    ) -> Result<ProfileList, FlashspanError> {
+        manager.stop();
+        let paths = profiles.switch(&id)?;
+        let updated = settings.switch_to(paths.settings);
+        presets.set_path(paths.presets);
+        history.set_path(paths.history);
+
+        let list = profiles.list();
+        let _ = app.emit("app_settings_changed", updated);
+        let _ = app.emit("profile_changed", list.clone());
+        Ok(list)
     }
 
-    #[cfg(test)]
-    mod main_tests {
-        use super::*;
+    #[tauri::command]
+    fn list_presets(presets: tauri::State<'_, PresetStore>) -> Vec<PresetView> {
+        presets.list()
+    }
 
-        #[test]
-        fn parse_answer_text_accepts_commas_and_spaces() {
-            assert_eq!(parse_answer_text("  42 ").unwrap(), 42);
-            assert_eq!(parse_answer_text("1,234").unwrap(), 1234);
-            assert!(parse_answer_text("").is_err());
-            assert!(parse_answer_text("not a number").is_err());
-        }
+    #[tauri::command]
+    fn save_preset(
+        presets: tauri::State<'_, PresetStore>,
+        preset: Preset,
+    ) -> Result<PresetView, FlashspanError> {
+        presets.save(preset)
+    }
 
-        #[test]
-        fn parse_answer_text_bounds_check() {
-            // Very long numeric string is rejected by our defensive bound
-            let long = "1".repeat(100);
-            assert!(parse_answer_text(&long).is_err());
-        }
+    #[tauri::command]
+    fn delete_preset(
+        presets: tauri::State<'_, PresetStore>,
+        name: String,
+    ) -> Result<(), FlashspanError> {
+        presets.delete(&name)
+    }
 
-        #[test]
-        fn parse_answer_text_negatives_and_extremes() {
-            // Negative numbers
-            assert_eq!(parse_answer_text("-42").unwrap(), -42);
-            assert_eq!(parse_answer_text("  -17  ").unwrap(), -17);
-            assert_eq!(parse_answer_text("-0").unwrap(), 0);
-
-            // Boundary values
-            assert_eq!(parse_answer_text("9223372036854775807").unwrap(), i64::MAX);
-            assert_eq!(parse_answer_text("-9223372036854775808").unwrap(), i64::MIN);
-
-            // Whitespace and commas
-            assert_eq!(parse_answer_text("  1,234,567  ").unwrap(), 1234567);
-            assert_eq!(parse_answer_text("-9,876").unwrap(), -9876);
-
-            // Rejects non-numeric and empty
-            assert!(parse_answer_text("abc").is_err());
-            assert!(parse_answer_text("12abc34").is_err());
-            assert!(parse_answer_text("   ").is_err());
-        }
+    /// Returns the preset file contents; the UI saves them wherever the user picks.
+    #[tauri::command]
+    fn export_presets(
+        presets: tauri::State<'_, PresetStore>,
+        names: Option<Vec<String>>,
+    ) -> Result<String, FlashspanError> {
+        presets.export(names.as_deref())
     }
 
-    fn now_ms() -> u64 {
-        SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_millis() as u64
+    #[tauri::command]
+    fn import_presets(
+        presets: tauri::State<'_, PresetStore>,
+        text: String,
+        overwrite: Option<bool>,
+    ) -> Result<ImportSummary, FlashspanError> {
+        presets.import(&text, overwrite.unwrap_or(false))
+    }
+
+    #[derive(Debug, Clone, serde::Serialize)]
+    struct SubmitAnswerResponse {
+        validation: ValidationResult,
+        auto_repeat_waiting: Option<AutoRepeatWaitingPayload>,
+        message: String,
+        /// Likely causes of a wrong running sum, most likely first.
+        diagnosis: Vec<MistakeCandidate>,
+        /// Points under the profile's scoring weights.
+        score: u32,
     }
 
     fn schedule_auto_repeat_if_needed(
         app: tauri::AppHandle,
         manager: Arc<SessionManager>,
         session_id: u64,
-    ) -> Result<Option<AutoRepeatWaitingPayload>, String> {
+        adjustment: Option<AdaptiveAdjustment>,
+    ) -> Result<Option<AutoRepeatWaitingPayload>, FlashspanError> {
         warn!(
             "[auto-repeat] schedule_auto_repeat_if_needed: session_id={}",
             session_id
@@ -271,15 +414,16 @@ mod native_app {
             "[auto-repeat] mark_validated_and_schedule_info returned: {:?}",
             result
         );
-        let Some((delay_ms, remaining, config, generation)) = result else {
+        let Some((delay_ms, remaining, config, seed, generation)) = result else {
             return Ok(None);
         };
 
-        let next_start_at_ms = now_ms().saturating_add(delay_ms);
+        let next_start_at_ms = now_epoch_ms().saturating_add(delay_ms);
         let payload = AutoRepeatWaitingPayload {
             session_id,
             next_start_at_ms,
             remaining,
+            adjustment,
         };
 
         let _ = app.emit("auto_repeat_waiting", payload.clone());
@@ -340,10 +484,9 @@ mod native_app {
                     return;
                 }
                 if let Err(e) = manager_arc.start_with_emitter(
-                    TauriEmitter {
-                        app: app_for_thread,
-                    },
+                    TauriEmitter::new(app_for_thread),
                     config,
+                    Some(seed),
                 ) {
                     warn!("auto-repeat start failed: {}", e);
                 }
@@ -361,57 +504,204 @@ mod native_app {
         manager: tauri::State<'_, Arc<SessionManager>>,
         config: SessionConfigInput,
         auto_repeat: Option<AutoRepeatConfigInput>,
-    ) -> Result<StartSessionResponse, String> {
+        seed: Option<u64>,
+    ) -> Result<StartSessionResponse, FlashspanError> {
         warn!("[auto-repeat] start_session: auto_repeat={:?}", auto_repeat);
         let (config, effective_config) = normalize_session_config(config);
+        let seed = seed.unwrap_or_else(random_seed);
 
         // Configure auto-repeat plan for this run (or clear it).
-        let effective_auto_repeat = if let Some(ar) = auto_repeat {
-            if ar.enabled {
-                let repeats = ar.repeats.clamp(1, 20) as u32;
-                let delay_s = if ar.delay_s.is_finite() {
-                    ar.delay_s.clamp(5.0, 120.0)
-                } else {
-                    5.0
-                };
-                let delay_ms = ((delay_s * 1000.0).round() as u64).max(5_000);
-
-                manager.configure_auto_repeat(Some(AutoRepeatPlan {
-                    remaining: repeats,
-                    delay_ms,
-                    config: config.clone(),
-                    awaiting_validation_session_id: None,
-                }));
-
-                Some(AutoRepeatEffective {
-                    enabled: true,
-                    repeats,
-                    delay_s: (delay_ms as f64 / 1000.0),
-                })
-            } else {
-                manager.configure_auto_repeat(None);
-                None
-            }
-        } else {
-            manager.configure_auto_repeat(None);
-            None
-        };
-
-        let session_id = manager.start_with_emitter(TauriEmitter { app: app.clone() }, config)?;
+        let effective_auto_repeat = auto_repeat.as_ref().and_then(normalize_auto_repeat);
+        manager.configure_auto_repeat(effective_auto_repeat.as_ref().map(|ar| AutoRepeatPlan {
+            remaining: ar.repeats,
+            delay_ms: (ar.delay_s * 1000.0).round() as u64,
+            config: config.clone(),
+            awaiting_validation_session_id: None,
+            adaptive: ar.adaptive.clone().map(|policy| AdaptiveRun {
+                policy,
+                state: AdaptiveState::default(),
+            }),
+            seed_strategy: ar.seed_strategy,
+            seed,
+        }));
+
+        let session_id =
+            manager.start_with_emitter(TauriEmitter::new(app.clone()), config, Some(seed))?;
         Ok(StartSessionResponse {
             session_id,
             effective_config,
             effective_auto_repeat,
+            seed,
+        })
+    }
+
+    /// Plan file (JSON) of a recent session, so the same sequence can be replayed later.
+    #[tauri::command]
+    fn export_plan(
+        manager: tauri::State<'_, Arc<SessionManager>>,
+        session_id: u64,
+    ) -> Result<String, FlashspanError> {
+        plan_file::encode_json(&manager.plan_for(session_id)?)
+    }
+
+    /// Same as `export_plan`, in the compact binary form.
+    #[tauri::command]
+    fn export_plan_binary(
+        manager: tauri::State<'_, Arc<SessionManager>>,
+        session_id: u64,
+    ) -> Result<Vec<u8>, FlashspanError> {
+        plan_file::encode_binary(&manager.plan_for(session_id)?)
+    }
+
+    /// Run a plan file (JSON or binary bytes) exactly as saved. Auto-repeat does not apply.
+    #[tauri::command]
+    fn start_plan(
+        app: tauri::AppHandle,
+        manager: tauri::State<'_, Arc<SessionManager>>,
+        data: Vec<u8>,
+    ) -> Result<StartSessionResponse, FlashspanError> {
+        let plan = plan_file::decode(&data)?;
+        let effective_config = plan.config_snapshot.clone();
+        let seed = plan.seed;
+
+        manager.configure_auto_repeat(None);
+        let session_id = manager.start_plan_with_emitter(TauriEmitter::new(app), plan)?;
+        Ok(StartSessionResponse {
+            session_id,
+            effective_config,
+            effective_auto_repeat: None,
+            seed,
+        })
+    }
+
+    /// Race mode: at most one hosted race and one joined race per app.
+    #[derive(Default)]
+    struct RaceState {
+        host: std::sync::Mutex<Option<RaceHost>>,
+        client: std::sync::Mutex<Option<RaceClient>>,
+    }
+
+    #[derive(Debug, Clone, serde::Serialize)]
+    struct RaceHostInfo {
+        port: u16,
+        leaderboard: Leaderboard,
+    }
+
+    #[derive(Debug, Clone, serde::Serialize)]
+    struct RaceRoundResponse {
+        round: u64,
+        effective_config: SessionConfigEffective,
+        seed: u64,
+    }
+
+    /// Host a race on the local network; players join with `race_join`.
+    #[tauri::command]
+    fn race_host_start(
+        app: tauri::AppHandle,
+        race: tauri::State<'_, RaceState>,
+        settings: tauri::State<'_, SettingsState>,
+        port: Option<u16>,
+    ) -> Result<RaceHostInfo, FlashspanError> {
+        let mut host = recover_lock(&race.host, "race host");
+        if host.is_some() {
+            return Err(FlashspanError::race("already hosting a race"));
+        }
+        let started = RaceHost::bind(
+            ("0.0.0.0", port.unwrap_or(DEFAULT_RACE_PORT)),
+            move |leaderboard| {
+                let _ = app.emit("race_leaderboard", leaderboard);
+            },
+        )?;
+        started.set_scoring(settings.scoring());
+        let info = RaceHostInfo {
+            port: started.local_addr().port(),
+            leaderboard: started.leaderboard(),
+        };
+        *host = Some(started);
+        Ok(info)
+    }
+
+    #[tauri::command]
+    fn race_host_stop(race: tauri::State<'_, RaceState>) {
+        recover_lock(&race.host, "race host").take();
+    }
+
+    /// Build one plan and send it to every player as the next round.
+    #[tauri::command]
+    fn race_start_round(
+        race: tauri::State<'_, RaceState>,
+        config: SessionConfigInput,
+        seed: Option<u64>,
+    ) -> Result<RaceRoundResponse, FlashspanError> {
+        let host = recover_lock(&race.host, "race host");
+        let host = host
+            .as_ref()
+            .ok_or_else(|| FlashspanError::race("not hosting a race"))?;
+        let (config, effective_config) = normalize_session_config(config);
+        validate_config(&config)?;
+        let seed = seed.unwrap_or_else(random_seed);
+
+        let plan = build_session_plan(0, config, effective_config.clone(), Some(seed));
+        let round = host.start_round(plan)?;
+        Ok(RaceRoundResponse {
+            round,
+            effective_config,
+            seed,
         })
     }
 
+    #[tauri::command]
+    fn race_leaderboard(race: tauri::State<'_, RaceState>) -> Result<Leaderboard, FlashspanError> {
+        recover_lock(&race.host, "race host")
+            .as_ref()
+            .map(RaceHost::leaderboard)
+            .ok_or_else(|| FlashspanError::race("not hosting a race"))
+    }
+
+    /// Join a race as `name`. Rounds run as local sessions; answers given to them through
+    /// `submit_answer` also go to the host. Progress arrives as `race_event`.
+    #[tauri::command]
+    fn race_join(
+        app: tauri::AppHandle,
+        race: tauri::State<'_, RaceState>,
+        manager: tauri::State<'_, Arc<SessionManager>>,
+        address: String,
+        name: String,
+    ) -> Result<u32, FlashspanError> {
+        let mut client = recover_lock(&race.client, "race client");
+        if client.is_some() {
+            return Err(FlashspanError::race("already in a race"));
+        }
+        let manager = Arc::clone(&*manager);
+        let app_for_rounds = app.clone();
+        let joined = RaceClient::connect(
+            &address,
+            &name,
+            move |plan| {
+                manager.configure_auto_repeat(None);
+                manager.start_plan_with_emitter(TauriEmitter::new(app_for_rounds.clone()), plan)
+            },
+            move |event| {
+                let _ = app.emit("race_event", event);
+            },
+        )?;
+        let player_id = joined.player_id();
+        *client = Some(joined);
+        Ok(player_id)
+    }
+
+    #[tauri::command]
+    fn race_leave(race: tauri::State<'_, RaceState>) {
+        recover_lock(&race.client, "race client").take();
+    }
+
     #[tauri::command]
     fn mark_validated(
         app: tauri::AppHandle,
         manager: tauri::State<'_, Arc<SessionManager>>,
         session_id: u64,
-    ) -> Result<Option<AutoRepeatWaitingPayload>, String> {
-        schedule_auto_repeat_if_needed(app, Arc::clone(&*manager), session_id)
+    ) -> Result<Option<AutoRepeatWaitingPayload>, FlashspanError> {
+        schedule_auto_repeat_if_needed(app, Arc::clone(&*manager), session_id, None)
     }
 
     #[tauri::command]
@@ -419,8 +709,8 @@ mod native_app {
         app: tauri::AppHandle,
         manager: tauri::State<'_, Arc<SessionManager>>,
         session_id: u64,
-    ) -> Result<Option<AutoRepeatWaitingPayload>, String> {
-        schedule_auto_repeat_if_needed(app, Arc::clone(&*manager), session_id)
+    ) -> Result<Option<AutoRepeatWaitingPayload>, FlashspanError> {
+        schedule_auto_repeat_if_needed(app, Arc::clone(&*manager), session_id, None)
     }
 
     #[tauri::command]
@@ -428,65 +718,120 @@ mod native_app {
         app: tauri::AppHandle,
         manager: tauri::State<'_, Arc<SessionManager>>,
         args: serde_json::Value,
-    ) -> Result<SubmitAnswerResponse, String> {
+    ) -> Result<SubmitAnswerResponse, FlashspanError> {
         #[derive(serde::Deserialize)]
         struct SubmitAnswerArgs {
             #[serde(alias = "sessionId")]
             session_id: u64,
-            #[serde(alias = "providedSum")]
-            provided_sum: i64,
+            /// In decimal sessions, counted in units of 10^-decimal_places.
+            #[serde(alias = "providedSum", default)]
+            provided_sum: Option<i64>,
+            /// One answer per pair, for multiplication and division sessions.
+            #[serde(alias = "providedAnswers", default)]
+            provided_answers: Option<Vec<i64>>,
         }
 
         let parsed: SubmitAnswerArgs =
-            serde_json::from_value(args).map_err(|e| format!("invalid args: {}", e))?;
+            serde_json::from_value(args).map_err(FlashspanError::invalid_args)?;
         let session_id = parsed.session_id;
-        let provided_sum = parsed.provided_sum;
 
         let result = manager.result_for(session_id)?;
-        let expected_sum = result.sum;
 
-        let delta = provided_sum.saturating_sub(expected_sum);
-        let correct = delta == 0;
+        let submission = Submission {
+            provided_sum: parsed.provided_sum,
+            provided_answers: parsed.provided_answers,
+        };
+        let mut validation =
+            submission.validate(result.sum, result.decimal_places, &result.pairs)?;
+        let submitted_at_ms = now_epoch_ms();
+        validation.answer_ms = result
+            .completed_at
+            .map(|at| at.elapsed().as_millis() as u64);
+        let correct = validation.correct;
+        let score = app.state::<SettingsState>().scoring().score(
+            &result.config,
+            &result.numbers,
+            &validation,
+        );
 
-        let validation = ValidationResult {
-            expected_sum,
-            provided_sum,
+        // Race rounds are also scored by the host.
+        if let Some(client) = recover_lock(&app.state::<RaceState>().client, "race client").as_ref()
+        {
+            client.submit(session_id, &submission);
+        }
+
+        let history = app.state::<Arc<HistoryStore>>();
+        let mut record = HistoryRecord::from_complete(&result);
+        record.answer = Some(HistoryAnswer {
+            provided_sum: validation.provided_sum,
+            provided_answers: validation.pairs.iter().map(|p| p.provided).collect(),
             correct,
-            delta,
-        };
+            delta: validation.delta,
+            submitted_at_ms,
+            answer_ms: validation.answer_ms,
+            score: Some(score),
+        });
+        if let Err(e) = history.append(&record) {
+            warn!("failed to record answer for session {}: {}", session_id, e);
+        }
 
         // Play feedback sound based on validation result (Rust owns playback).
         let _ = crate::audio::play_kind(if correct { "applause" } else { "buzzer" });
 
-        let waiting = schedule_auto_repeat_if_needed(app, Arc::clone(&*manager), session_id)?;
-        let message = {
-            let mut lines: Vec<String> = Vec::new();
-            if correct {
-                lines.push("Correct ✅".to_string());
-            } else {
-                lines.push("Incorrect".to_string());
-            }
-            lines.push(format!("Expected answer: {}", expected_sum));
-            if !correct {
-                let d = validation.delta;
-                lines.push(format!("Difference: {}{}", if d > 0 { "+" } else { "" }, d));
-            }
-            lines.join("\n")
+        let adjustment = manager.record_adaptive_outcome(session_id, correct);
+        let waiting =
+            schedule_auto_repeat_if_needed(app, Arc::clone(&*manager), session_id, adjustment)?;
+        let diagnosis = if correct || !result.pairs.is_empty() {
+            Vec::new()
+        } else {
+            diagnose(&result.numbers, result.sum, validation.provided_sum)
         };
+        let mut message = validation.message();
+        if let Some(top) = diagnosis.first() {
+            message.push('\n');
+            message.push_str(&top.describe(&result.numbers, result.decimal_places));
+        }
+        message.push_str(&format!("\nScore: {} points", score));
 
         Ok(SubmitAnswerResponse {
             validation,
             auto_repeat_waiting: waiting,
             message,
+            diagnosis,
+            score,
         })
     }
 
+    #[tauri::command]
+    fn list_history(
+        history: tauri::State<'_, Arc<HistoryStore>>,
+        filter: Option<HistoryFilter>,
+    ) -> Result<Vec<HistoryRecord>, FlashspanError> {
+        history.query(&filter.unwrap_or_default())
+    }
+
+    /// Statistics over the matching history; a `limit` keeps the latest sessions. Days and
+    /// weeks follow the caller's clock, `utc_offset_minutes` ahead of UTC (UTC when omitted).
+    #[tauri::command]
+    fn get_analytics(
+        history: tauri::State<'_, Arc<HistoryStore>>,
+        filter: Option<HistoryFilter>,
+        utc_offset_minutes: Option<i32>,
+    ) -> Result<AnalyticsReport, FlashspanError> {
+        let samples: Vec<SessionSample> = history
+            .query(&filter.unwrap_or_default())?
+            .iter()
+            .map(HistoryRecord::sample)
+            .collect();
+        analytics::analyze(&samples, utc_offset_minutes.unwrap_or(0))
+    }
+
     #[tauri::command]
     fn submit_answer_text(
         app: tauri::AppHandle,
         manager: tauri::State<'_, Arc<SessionManager>>,
         args: serde_json::Value,
-    ) -> Result<SubmitAnswerResponse, String> {
+    ) -> Result<SubmitAnswerResponse, FlashspanError> {
         #[derive(serde::Deserialize)]
         struct SubmitAnswerTextArgs {
             #[serde(alias = "sessionId")]
@@ -496,12 +841,27 @@ mod native_app {
         }
 
         let parsed: SubmitAnswerTextArgs =
-            serde_json::from_value(args).map_err(|e| format!("invalid args: {}", e))?;
-        let provided_sum = parse_answer_text(&parsed.provided_text)?;
-        let args_for_submit = serde_json::json!({
-            "session_id": parsed.session_id,
-            "provided_sum": provided_sum,
-        });
+            serde_json::from_value(args).map_err(FlashspanError::invalid_args)?;
+        let result = manager.result_for(parsed.session_id)?;
+        let args_for_submit = if !result.pairs.is_empty() {
+            let provided_answers = parse_answer_list_text(&parsed.provided_text)?;
+            serde_json::json!({
+                "session_id": parsed.session_id,
+                "provided_answers": provided_answers,
+            })
+        } else if result.decimal_places > 0 {
+            let provided_sum = parse_fixed(&parsed.provided_text, result.decimal_places)?;
+            serde_json::json!({
+                "session_id": parsed.session_id,
+                "provided_sum": provided_sum,
+            })
+        } else {
+            let provided_sum = parse_answer_text(&parsed.provided_text)?;
+            serde_json::json!({
+                "session_id": parsed.session_id,
+                "provided_sum": provided_sum,
+            })
+        };
         submit_answer(app, manager, args_for_submit)
     }
 
@@ -515,25 +875,70 @@ mod native_app {
                             .build(),
                     )?;
                 }
+                let profiles =
+                    ProfileStore::load(app.path().app_config_dir()?, app.path().app_data_dir()?);
+                crate::dictation::set_voice_dirs(vec![
+                    app.path().resource_dir()?.join("voices"),
+                    app.path().app_data_dir()?.join("voices"),
+                ]);
+                crate::sound_packs::set_packs_dir(app.path().app_data_dir()?.join("sound-packs"));
+                let paths = profiles.active_paths();
+                app.manage(SettingsState::load(paths.settings));
+                app.manage(PresetStore::new(paths.presets));
+                app.manage(Arc::new(HistoryStore::new(paths.history)));
+                app.manage(profiles);
                 Ok(())
             })
             .manage(Arc::new(SessionManager::default()))
-            .manage(SettingsState::default())
+            .manage(RaceState::default())
             .invoke_handler(tauri::generate_handler![
                 ping,
                 get_app_settings,
                 set_color_scheme,
                 set_theme_mode,
                 start_session,
+                start_plan,
+                export_plan,
+                export_plan_binary,
                 stop_session,
+                pause_session,
+                resume_session,
+                answer_checkpoint,
+                get_timing_report,
                 cancel_auto_repeat,
                 mark_validated,
                 acknowledge_complete,
                 submit_answer,
                 submit_answer_text,
+                list_history,
+                get_analytics,
+                list_profiles,
+                create_profile,
+                rename_profile,
+                delete_profile,
+                switch_profile,
+                list_presets,
+                save_preset,
+                delete_preset,
+                export_presets,
+                import_presets,
                 crate::audio::play_sound_kind,
+                crate::dictation::list_voices,
+                crate::sound_packs::list_sound_packs,
+                crate::sound_packs::install_sound_pack,
+                select_sound_pack,
                 get_sound_enabled,
-                set_sound_enabled
+                set_sound_enabled,
+                get_audio_levels,
+                set_audio_levels,
+                get_scoring,
+                set_scoring,
+                race_host_start,
+                race_host_stop,
+                race_start_round,
+                race_leaderboard,
+                race_join,
+                race_leave
             ])
             .run(tauri::generate_context!())
             .expect("error while running tauri application");
@@ -0,0 +1,465 @@
+//! Named session presets, saved per profile in `presets.json`.
+//!
+//! Presets keep the config exactly as entered and are normalised each time they are read, so
+//! they pick up the current clamping rules. The file on disk and exported files share one
+//! versioned format, so a teacher can export presets and a student can import them.
+
+use crate::core::error::FlashspanError;
+use crate::core::types::{
+    AutoRepeatConfigInput, AutoRepeatEffective, SessionConfigEffective, SessionConfigInput,
+};
+use crate::core::validate::{normalize_auto_repeat, normalize_session_config, validate_config};
+use crate::session::{now_epoch_ms, recover_lock};
+use crate::settings::{move_corrupt_aside, write_atomic};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+pub const PRESETS_FILE_NAME: &str = "presets.json";
+pub const PRESETS_SCHEMA_VERSION: u32 = 1;
+const MAX_PRESETS: usize = 200;
+const MAX_PRESET_NAME_CHARS: usize = 60;
+
+/// A preset as saved, with the config exactly as the UI sent it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Preset {
+    pub name: String,
+    pub config: SessionConfigInput,
+    #[serde(default)]
+    pub auto_repeat: Option<AutoRepeatConfigInput>,
+    #[serde(default)]
+    pub saved_at_ms: u64,
+}
+
+/// A preset after normalisation, ready to start a session with.
+#[derive(Debug, Clone, Serialize)]
+pub struct PresetView {
+    pub name: String,
+    pub config: SessionConfigEffective,
+    pub auto_repeat: Option<AutoRepeatEffective>,
+    pub saved_at_ms: u64,
+}
+
+impl From<&Preset> for PresetView {
+    fn from(preset: &Preset) -> Self {
+        let (_, config) = normalize_session_config(preset.config.clone());
+        Self {
+            name: preset.name.clone(),
+            config,
+            auto_repeat: preset.auto_repeat.as_ref().and_then(normalize_auto_repeat),
+            saved_at_ms: preset.saved_at_ms,
+        }
+    }
+}
+
+/// Versioned list of presets; the format of both `presets.json` and exported files.
+#[derive(Debug, Clone, Serialize)]
+pub struct PresetBundle {
+    pub schema_version: u32,
+    pub presets: Vec<Preset>,
+}
+
+/// Outcome of [`PresetStore::import`], by preset name.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ImportSummary {
+    pub imported: Vec<String>,
+    pub replaced: Vec<String>,
+    /// One message per preset that was left out, with the reason.
+    pub skipped: Vec<String>,
+}
+
+impl PresetBundle {
+    /// Parse bundle text. Entries that fail to parse or validate are reported in the second
+    /// value rather than failing the whole bundle.
+    pub fn parse(text: &str) -> Result<(Self, Vec<String>), FlashspanError> {
+        let value: serde_json::Value =
+            serde_json::from_str(text).map_err(|e| FlashspanError::InvalidFile {
+                detail: e.to_string(),
+            })?;
+        let schema_version = value
+            .get("schema_version")
+            .and_then(|v| v.as_u64())
+            .ok_or_else(|| FlashspanError::InvalidFile {
+                detail: "not a preset file: missing `schema_version`".to_string(),
+            })? as u32;
+        let entries = value
+            .get("presets")
+            .and_then(|v| v.as_array())
+            .ok_or_else(|| FlashspanError::InvalidFile {
+                detail: "not a preset file: missing `presets` list".to_string(),
+            })?;
+
+        let mut presets = Vec::new();
+        let mut skipped = Vec::new();
+        for (i, entry) in entries.iter().enumerate() {
+            match serde_json::from_value::<Preset>(entry.clone())
+                .map_err(|e| e.to_string())
+                .and_then(|p| checked(p).map_err(|e| e.to_string()))
+            {
+                Ok(p) => presets.push(p),
+                Err(e) => {
+                    let name = entry.get("name").and_then(|n| n.as_str());
+                    skipped.push(match name {
+                        Some(name) => format!("\"{}\": {}", name, e),
+                        None => format!("entry {}: {}", i + 1, e),
+                    });
+                }
+            }
+        }
+        Ok((
+            Self {
+                schema_version,
+                presets,
+            },
+            skipped,
+        ))
+    }
+}
+
+/// Trim the name and reject presets whose config cannot start a session.
+fn checked(mut preset: Preset) -> Result<Preset, FlashspanError> {
+    let name = preset.name.trim();
+    if name.is_empty() {
+        return Err(FlashspanError::InvalidName {
+            reason: "preset name must not be empty".to_string(),
+        });
+    }
+    if name.chars().count() > MAX_PRESET_NAME_CHARS {
+        return Err(FlashspanError::InvalidName {
+            reason: format!(
+                "preset name must be at most {} characters",
+                MAX_PRESET_NAME_CHARS
+            ),
+        });
+    }
+    preset.name = name.to_string();
+    let (config, _) = normalize_session_config(preset.config.clone());
+    validate_config(&config)?;
+    Ok(preset)
+}
+
+fn same_name(a: &str, b: &str) -> bool {
+    a.to_lowercase() == b.trim().to_lowercase()
+}
+
+pub struct PresetStore {
+    /// Preset file of the active profile; the lock also serialises read-modify-write cycles.
+    path: Mutex<PathBuf>,
+}
+
+impl PresetStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path: Mutex::new(path),
+        }
+    }
+
+    /// Point the store at another file, e.g. after switching profiles.
+    pub fn set_path(&self, path: PathBuf) {
+        *recover_lock(&self.path, "presets path") = path;
+    }
+
+    /// All presets, normalised and sorted by name.
+    pub fn list(&self) -> Vec<PresetView> {
+        let path = recover_lock(&self.path, "presets path");
+        let mut views: Vec<PresetView> = read_presets(&path).iter().map(PresetView::from).collect();
+        views.sort_by_key(|v| v.name.to_lowercase());
+        views
+    }
+
+    /// Save `preset`, replacing any preset with the same name (ignoring case).
+    pub fn save(&self, preset: Preset) -> Result<PresetView, FlashspanError> {
+        let mut preset = checked(preset)?;
+        preset.saved_at_ms = now_epoch_ms();
+
+        let path = recover_lock(&self.path, "presets path");
+        let mut presets = read_presets(&path);
+        match presets
+            .iter()
+            .position(|p| same_name(&p.name, &preset.name))
+        {
+            Some(i) => presets[i] = preset.clone(),
+            None if presets.len() >= MAX_PRESETS => {
+                return Err(FlashspanError::LimitReached {
+                    what: "presets",
+                    max: MAX_PRESETS,
+                });
+            }
+            None => presets.push(preset.clone()),
+        }
+        write_presets(&path, presets)?;
+        Ok(PresetView::from(&preset))
+    }
+
+    pub fn delete(&self, name: &str) -> Result<(), FlashspanError> {
+        let path = recover_lock(&self.path, "presets path");
+        let mut presets = read_presets(&path);
+        let before = presets.len();
+        presets.retain(|p| !same_name(&p.name, name));
+        if presets.len() == before {
+            return Err(FlashspanError::PresetNotFound {
+                name: name.trim().to_string(),
+            });
+        }
+        write_presets(&path, presets)
+    }
+
+    /// Bundle text for the named presets, or for all of them when `names` is `None`.
+    pub fn export(&self, names: Option<&[String]>) -> Result<String, FlashspanError> {
+        let path = recover_lock(&self.path, "presets path");
+        let mut presets = read_presets(&path);
+        if let Some(names) = names {
+            if let Some(missing) = names
+                .iter()
+                .find(|n| !presets.iter().any(|p| same_name(&p.name, n)))
+            {
+                return Err(FlashspanError::PresetNotFound {
+                    name: missing.trim().to_string(),
+                });
+            }
+            presets.retain(|p| names.iter().any(|n| same_name(&p.name, n)));
+        }
+        let bundle = PresetBundle {
+            schema_version: PRESETS_SCHEMA_VERSION,
+            presets,
+        };
+        serde_json::to_string_pretty(&bundle).map_err(FlashspanError::storage)
+    }
+
+    /// Add the presets in bundle `text`. Presets whose name is already taken replace the saved
+    /// one when `overwrite` is set and are skipped otherwise.
+    pub fn import(&self, text: &str, overwrite: bool) -> Result<ImportSummary, FlashspanError> {
+        let (bundle, skipped) = PresetBundle::parse(text)?;
+        if bundle.schema_version > PRESETS_SCHEMA_VERSION {
+            return Err(FlashspanError::UnsupportedVersion {
+                found: bundle.schema_version,
+                supported: PRESETS_SCHEMA_VERSION,
+            });
+        }
+
+        let mut summary = ImportSummary {
+            skipped,
+            ..Default::default()
+        };
+        let path = recover_lock(&self.path, "presets path");
+        let mut presets = read_presets(&path);
+        for mut incoming in bundle.presets {
+            incoming.saved_at_ms = now_epoch_ms();
+            match presets
+                .iter()
+                .position(|p| same_name(&p.name, &incoming.name))
+            {
+                Some(i) if overwrite => {
+                    summary.replaced.push(incoming.name.clone());
+                    presets[i] = incoming;
+                }
+                Some(_) => summary.skipped.push(format!(
+                    "\"{}\": a preset with this name exists",
+                    incoming.name
+                )),
+                None if presets.len() >= MAX_PRESETS => summary.skipped.push(format!(
+                    "\"{}\": at most {} presets can be saved",
+                    incoming.name, MAX_PRESETS
+                )),
+                None => {
+                    summary.imported.push(incoming.name.clone());
+                    presets.push(incoming);
+                }
+            }
+        }
+        if !summary.imported.is_empty() || !summary.replaced.is_empty() {
+            write_presets(&path, presets)?;
+        }
+        Ok(summary)
+    }
+}
+
+/// Saved presets; a missing file is empty, and an unreadable one or one written by a newer
+/// version is moved aside rather than overwritten by the next save.
+fn read_presets(path: &Path) -> Vec<Preset> {
+    let text = match fs::read_to_string(path) {
+        Ok(t) => t,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Vec::new(),
+        Err(e) => {
+            warn!("failed to read {}: {}", path.display(), e);
+            return Vec::new();
+        }
+    };
+    match PresetBundle::parse(&text) {
+        Ok((bundle, _)) if bundle.schema_version > PRESETS_SCHEMA_VERSION => {
+            let e = FlashspanError::UnsupportedVersion {
+                found: bundle.schema_version,
+                supported: PRESETS_SCHEMA_VERSION,
+            };
+            move_corrupt_aside(path, &e.to_string());
+            Vec::new()
+        }
+        Ok((bundle, skipped)) => {
+            for s in skipped {
+                warn!("ignoring saved preset {}", s);
+            }
+            bundle.presets
+        }
+        Err(e) => {
+            move_corrupt_aside(path, &e.to_string());
+            Vec::new()
+        }
+    }
+}
+
+fn write_presets(path: &Path, presets: Vec<Preset>) -> Result<(), FlashspanError> {
+    let bundle = PresetBundle {
+        schema_version: PRESETS_SCHEMA_VERSION,
+        presets,
+    };
+    let text = serde_json::to_string_pretty(&bundle).map_err(FlashspanError::storage)?;
+    write_atomic(path, &text).map_err(|e| FlashspanError::storage(format!("presets write: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::scratch_dir;
+
+    fn temp_store(name: &str) -> PresetStore {
+        let dir = scratch_dir(&format!("presets-{}", name));
+        PresetStore::new(dir.join(PRESETS_FILE_NAME))
+    }
+
+    fn preset(name: &str, digits: i64) -> Preset {
+        Preset {
+            name: name.to_string(),
+            config: SessionConfigInput {
+                digits_per_number: digits,
+                number_duration_s: 0.8,
+                delay_between_numbers_s: 0.2,
+                total_numbers: 10,
+                ..Default::default()
+            },
+            auto_repeat: None,
+            saved_at_ms: 0,
+        }
+    }
+
+    #[test]
+    fn save_replaces_same_name_and_lists_normalised() {
+        let store = temp_store("save");
+        store.save(preset("Sprint", 3)).unwrap();
+        store.save(preset(" sprint ", 99)).unwrap();
+        store
+            .save(Preset {
+                auto_repeat: Some(AutoRepeatConfigInput {
+                    enabled: true,
+                    repeats: 100,
+                    delay_s: 1.0,
+                    adaptive: Some(Default::default()),
+                    seed_strategy: Default::default(),
+                }),
+                ..preset("Exam", 4)
+            })
+            .unwrap();
+
+        let list = store.list();
+        let names: Vec<&str> = list.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["Exam", "sprint"]);
+        // Digits were clamped on load and auto-repeat was normalised.
+        assert_eq!(list[1].config.digits_per_number, 18);
+        let auto_repeat = list[0].auto_repeat.as_ref().unwrap();
+        assert_eq!(auto_repeat.repeats, 20);
+        assert_eq!(
+            auto_repeat.adaptive.as_ref().unwrap().harden_after_correct,
+            2
+        );
+    }
+
+    #[test]
+    fn save_rejects_bad_names() {
+        let store = temp_store("reject");
+        assert!(store.save(preset("  ", 3)).is_err());
+        assert!(store.save(preset(&"x".repeat(61), 3)).is_err());
+        assert!(store.list().is_empty());
+    }
+
+    #[test]
+    fn delete_requires_existing_name() {
+        let store = temp_store("delete");
+        store.save(preset("Sprint", 3)).unwrap();
+        assert!(store.delete("missing").is_err());
+        store.delete("SPRINT").unwrap();
+        assert!(store.list().is_empty());
+    }
+
+    #[test]
+    fn export_then_import_into_another_store() {
+        let teacher = temp_store("export");
+        teacher.save(preset("Grade 5 exam", 3)).unwrap();
+        teacher.save(preset("3-digit sprint", 3)).unwrap();
+        let text = teacher.export(Some(&["grade 5 EXAM".to_string()])).unwrap();
+        assert!(teacher.export(Some(&["nope".to_string()])).is_err());
+
+        let student = temp_store("import");
+        student.save(preset("Grade 5 exam", 2)).unwrap();
+        let summary = student.import(&text, false).unwrap();
+        assert!(summary.imported.is_empty());
+        assert_eq!(summary.skipped.len(), 1);
+
+        let summary = student.import(&text, true).unwrap();
+        assert_eq!(summary.replaced, vec!["Grade 5 exam"]);
+        assert_eq!(student.list()[0].config.digits_per_number, 3);
+
+        let all = teacher.export(None).unwrap();
+        let summary = student.import(&all, false).unwrap();
+        assert_eq!(summary.imported, vec!["3-digit sprint"]);
+    }
+
+    #[test]
+    fn import_skips_bad_entries_and_rejects_newer_versions() {
+        let store = temp_store("bad-import");
+        let text = r#"{"schema_version":1,"presets":[
+            {"name":"Ok","config":{"digits_per_number":2,"number_duration_s":1,"delay_between_numbers_s":0,"total_numbers":5}},
+            {"name":"Broken","config":{"digits_per_number":"two"}},
+            {"config":{}}
+        ]}"#;
+        let summary = store.import(text, false).unwrap();
+        assert_eq!(summary.imported, vec!["Ok"]);
+        assert_eq!(summary.skipped.len(), 2);
+        assert!(summary.skipped[0].starts_with("\"Broken\""));
+
+        assert!(
+            store
+                .import(r#"{"schema_version":2,"presets":[]}"#, false)
+                .is_err()
+        );
+        assert!(store.import(r#"{"presets":[]}"#, false).is_err());
+        assert!(store.import("[]", false).is_err());
+    }
+
+    #[test]
+    fn corrupt_file_is_moved_aside() {
+        let store = temp_store("corrupt");
+        let path = recover_lock(&store.path, "presets path").clone();
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, "{ nope").unwrap();
+        assert!(store.list().is_empty());
+        store.save(preset("Sprint", 3)).unwrap();
+        assert_eq!(store.list().len(), 1);
+    }
+
+    #[test]
+    fn newer_file_is_moved_aside_not_overwritten() {
+        let store = temp_store("newer");
+        let path = recover_lock(&store.path, "presets path").clone();
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let newer = r#"{"schema_version":2,"presets":[]}"#;
+        fs::write(&path, newer).unwrap();
+        assert!(store.list().is_empty());
+        let backup = path.with_extension("json.corrupt");
+        assert_eq!(fs::read_to_string(&backup).unwrap(), newer);
+
+        store.save(preset("Sprint", 3)).unwrap();
+        assert_eq!(fs::read_to_string(&backup).unwrap(), newer);
+    }
+}
@@ -0,0 +1,454 @@
+//! Named user profiles for shared machines.
+//!
+//! The profile list lives in `profiles.json` in the app config dir. Each other profile keeps its
+//! settings, presets and history under `profiles/<id>/` in the config and data dirs. The built-in default
+//! profile keeps the top-level files, so data saved before profiles existed stays with it.
+
+use crate::core::error::FlashspanError;
+use crate::history::HISTORY_FILE_NAME;
+use crate::presets::PRESETS_FILE_NAME;
+use crate::session::{now_epoch_ms, recover_lock};
+use crate::settings::{SETTINGS_FILE_NAME, move_corrupt_aside, write_atomic};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+pub const PROFILES_FILE_NAME: &str = "profiles.json";
+pub const PROFILES_VERSION: u32 = 1;
+pub const DEFAULT_PROFILE_ID: &str = "default";
+const MAX_PROFILE_NAME_CHARS: usize = 40;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Profile {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub created_at_ms: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfileList {
+    pub active_id: String,
+    pub profiles: Vec<Profile>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+struct ProfilesFile {
+    version: u32,
+    active_id: String,
+    /// Next numeric suffix for generated ids; ids are never reused.
+    next_id: u64,
+    profiles: Vec<Profile>,
+}
+
+impl Default for ProfilesFile {
+    fn default() -> Self {
+        Self {
+            version: PROFILES_VERSION,
+            active_id: DEFAULT_PROFILE_ID.to_string(),
+            next_id: 1,
+            profiles: vec![Profile {
+                id: DEFAULT_PROFILE_ID.to_string(),
+                name: "Default".to_string(),
+                created_at_ms: 0,
+            }],
+        }
+    }
+}
+
+impl ProfilesFile {
+    /// Repair hand-edited or partially written files: keep the default profile, drop ids this
+    /// build would not have generated and duplicates, and fall back to the default profile if
+    /// the active one is gone.
+    fn repair(&mut self) {
+        let mut seen = std::collections::HashSet::new();
+        self.profiles.retain(|p| {
+            if !is_profile_id(&p.id) {
+                warn!("dropping profile with invalid id {:?}", p.id);
+                return false;
+            }
+            seen.insert(p.id.clone())
+        });
+        if !self.profiles.iter().any(|p| p.id == DEFAULT_PROFILE_ID) {
+            self.profiles.insert(0, Self::default().profiles.remove(0));
+        }
+        if !self.profiles.iter().any(|p| p.id == self.active_id) {
+            self.active_id = DEFAULT_PROFILE_ID.to_string();
+        }
+        let highest = self
+            .profiles
+            .iter()
+            .filter_map(|p| p.id.strip_prefix('p')?.parse::<u64>().ok())
+            .max()
+            .unwrap_or(0);
+        self.next_id = self.next_id.max(highest + 1);
+        self.version = PROFILES_VERSION;
+    }
+}
+
+/// Where a profile keeps its files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfilePaths {
+    pub settings: PathBuf,
+    pub presets: PathBuf,
+    pub history: PathBuf,
+}
+
+pub struct ProfileStore {
+    config_dir: PathBuf,
+    data_dir: PathBuf,
+    file: Mutex<ProfilesFile>,
+}
+
+impl ProfileStore {
+    /// Load the profile list, creating the default profile on first run.
+    pub fn load(config_dir: PathBuf, data_dir: PathBuf) -> Self {
+        let path = config_dir.join(PROFILES_FILE_NAME);
+        let mut file = match fs::read_to_string(&path) {
+            Ok(text) => match serde_json::from_str::<ProfilesFile>(&text) {
+                Ok(f) => f,
+                Err(e) => {
+                    move_corrupt_aside(&path, &e.to_string());
+                    ProfilesFile::default()
+                }
+            },
+            Err(e) if e.kind() == ErrorKind::NotFound => ProfilesFile::default(),
+            Err(e) => {
+                warn!("failed to read {}: {}", path.display(), e);
+                ProfilesFile::default()
+            }
+        };
+        file.repair();
+
+        Self {
+            config_dir,
+            data_dir,
+            file: Mutex::new(file),
+        }
+    }
+
+    pub fn list(&self) -> ProfileList {
+        let file = recover_lock(&self.file, "profiles");
+        ProfileList {
+            active_id: file.active_id.clone(),
+            profiles: file.profiles.clone(),
+        }
+    }
+
+    pub fn active_id(&self) -> String {
+        recover_lock(&self.file, "profiles").active_id.clone()
+    }
+
+    /// Where profile `id` keeps its files. Ids are joined into paths, so only ids this build
+    /// generates are accepted.
+    pub fn paths(&self, id: &str) -> Result<ProfilePaths, FlashspanError> {
+        if !is_profile_id(id) {
+            return Err(FlashspanError::ProfileNotFound { id: id.to_string() });
+        }
+        Ok(self.dirs(id))
+    }
+
+    /// The active id is always valid: `repair` and `switch` only ever make a listed id active.
+    pub fn active_paths(&self) -> ProfilePaths {
+        self.dirs(&self.active_id())
+    }
+
+    fn dirs(&self, id: &str) -> ProfilePaths {
+        if id == DEFAULT_PROFILE_ID {
+            return ProfilePaths {
+                settings: self.config_dir.join(SETTINGS_FILE_NAME),
+                presets: self.config_dir.join(PRESETS_FILE_NAME),
+                history: self.data_dir.join(HISTORY_FILE_NAME),
+            };
+        }
+        let config_dir = self.config_dir.join("profiles").join(id);
+        ProfilePaths {
+            settings: config_dir.join(SETTINGS_FILE_NAME),
+            presets: config_dir.join(PRESETS_FILE_NAME),
+            history: self
+                .data_dir
+                .join("profiles")
+                .join(id)
+                .join(HISTORY_FILE_NAME),
+        }
+    }
+
+    pub fn create(&self, name: &str) -> Result<Profile, FlashspanError> {
+        let mut file = recover_lock(&self.file, "profiles");
+        let name = checked_name(&file, name, None)?;
+        let profile = Profile {
+            id: format!("p{}", file.next_id),
+            name,
+            created_at_ms: now_epoch_ms(),
+        };
+        file.next_id += 1;
+        file.profiles.push(profile.clone());
+        self.save(&file)?;
+        Ok(profile)
+    }
+
+    pub fn rename(&self, id: &str, name: &str) -> Result<Profile, FlashspanError> {
+        let mut file = recover_lock(&self.file, "profiles");
+        let name = checked_name(&file, name, Some(id))?;
+        let profile = file
+            .profiles
+            .iter_mut()
+            .find(|p| p.id == id)
+            .ok_or_else(|| FlashspanError::ProfileNotFound { id: id.to_string() })?;
+        profile.name = name;
+        let profile = profile.clone();
+        self.save(&file)?;
+        Ok(profile)
+    }
+
+    /// Delete a profile and its files. The default and the active profile cannot be deleted.
+    pub fn delete(&self, id: &str) -> Result<(), FlashspanError> {
+        if !is_profile_id(id) {
+            return Err(FlashspanError::ProfileNotFound { id: id.to_string() });
+        }
+        if id == DEFAULT_PROFILE_ID {
+            return Err(FlashspanError::ProfileProtected {
+                reason: "the default profile cannot be deleted",
+            });
+        }
+        let mut file = recover_lock(&self.file, "profiles");
+        if file.active_id == id {
+            return Err(FlashspanError::ProfileProtected {
+                reason: "switch to another profile before deleting this one",
+            });
+        }
+        let before = file.profiles.len();
+        file.profiles.retain(|p| p.id != id);
+        if file.profiles.len() == before {
+            return Err(FlashspanError::ProfileNotFound { id: id.to_string() });
+        }
+        self.save(&file)?;
+
+        for dir in [
+            self.config_dir.join("profiles").join(id),
+            self.data_dir.join("profiles").join(id),
+        ] {
+            remove_dir_if_present(&dir)?;
+        }
+        Ok(())
+    }
+
+    /// Make `id` the active profile and return its paths.
+    pub fn switch(&self, id: &str) -> Result<ProfilePaths, FlashspanError> {
+        let paths = self.paths(id)?;
+        let mut file = recover_lock(&self.file, "profiles");
+        if !file.profiles.iter().any(|p| p.id == id) {
+            return Err(FlashspanError::ProfileNotFound { id: id.to_string() });
+        }
+        file.active_id = id.to_string();
+        self.save(&file)?;
+        Ok(paths)
+    }
+
+    fn save(&self, file: &ProfilesFile) -> Result<(), FlashspanError> {
+        let text = serde_json::to_string_pretty(file).map_err(FlashspanError::storage)?;
+        write_atomic(&self.config_dir.join(PROFILES_FILE_NAME), &text)
+            .map_err(|e| FlashspanError::storage(format!("profiles write: {}", e)))
+    }
+}
+
+/// `default` or a generated `p<digits>` id, so it is safe to join into a path.
+fn is_profile_id(id: &str) -> bool {
+    id == DEFAULT_PROFILE_ID
+        || id
+            .strip_prefix('p')
+            .is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// Trimmed `name`, rejected when empty, too long, or already used by another profile.
+fn checked_name(
+    file: &ProfilesFile,
+    name: &str,
+    renaming: Option<&str>,
+) -> Result<String, FlashspanError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(FlashspanError::InvalidName {
+            reason: "profile name must not be empty".to_string(),
+        });
+    }
+    if name.chars().count() > MAX_PROFILE_NAME_CHARS {
+        return Err(FlashspanError::InvalidName {
+            reason: format!(
+                "profile name must be at most {} characters",
+                MAX_PROFILE_NAME_CHARS
+            ),
+        });
+    }
+    let taken = file
+        .profiles
+        .iter()
+        .any(|p| Some(p.id.as_str()) != renaming && p.name.to_lowercase() == name.to_lowercase());
+    if taken {
+        return Err(FlashspanError::NameTaken {
+            name: name.to_string(),
+        });
+    }
+    Ok(name.to_string())
+}
+
+fn remove_dir_if_present(dir: &Path) -> Result<(), FlashspanError> {
+    match fs::remove_dir_all(dir) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(FlashspanError::storage(format!(
+            "failed to remove {}: {}",
+            dir.display(),
+            e
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::scratch_dir;
+
+    fn temp_store(name: &str) -> (PathBuf, ProfileStore) {
+        let root = scratch_dir(&format!("profiles-{}", name));
+        let store = ProfileStore::load(root.join("config"), root.join("data"));
+        (root, store)
+    }
+
+    #[test]
+    fn first_run_has_only_the_default_profile() {
+        let (root, store) = temp_store("first-run");
+        let list = store.list();
+        assert_eq!(list.active_id, DEFAULT_PROFILE_ID);
+        assert_eq!(list.profiles.len(), 1);
+        // Pre-profile files stay with the default profile.
+        assert_eq!(
+            store.active_paths().settings,
+            root.join("config").join(SETTINGS_FILE_NAME)
+        );
+    }
+
+    #[test]
+    fn create_rename_switch_and_reload() {
+        let (root, store) = temp_store("lifecycle");
+        let ana = store.create("  Ana ").unwrap();
+        assert_eq!(ana.name, "Ana");
+        let ben = store.create("Ben").unwrap();
+        assert_ne!(ana.id, ben.id);
+
+        store.rename(&ben.id, "Benjamin").unwrap();
+        let paths = store.switch(&ben.id).unwrap();
+        assert_eq!(
+            paths.history,
+            root.join("data/profiles")
+                .join(&ben.id)
+                .join(HISTORY_FILE_NAME)
+        );
+
+        let reloaded = ProfileStore::load(root.join("config"), root.join("data"));
+        assert_eq!(reloaded.active_id(), ben.id);
+        let names: Vec<String> = reloaded
+            .list()
+            .profiles
+            .into_iter()
+            .map(|p| p.name)
+            .collect();
+        assert_eq!(names, vec!["Default", "Ana", "Benjamin"]);
+    }
+
+    #[test]
+    fn names_must_be_unique_and_non_empty() {
+        let (_root, store) = temp_store("names");
+        let ana = store.create("Ana").unwrap();
+        assert!(store.create("ana").is_err());
+        assert!(store.create("   ").is_err());
+        assert!(store.create(&"x".repeat(41)).is_err());
+        // Renaming to its own name (different case) is fine.
+        assert!(store.rename(&ana.id, "ANA").is_ok());
+        assert!(store.rename(&ana.id, "default").is_err());
+    }
+
+    #[test]
+    fn delete_removes_files_but_not_default_or_active() {
+        let (_root, store) = temp_store("delete");
+        let ana = store.create("Ana").unwrap();
+        let paths = store.switch(&ana.id).unwrap();
+        let data_dir = paths.history.parent().unwrap();
+        fs::create_dir_all(data_dir).unwrap();
+        fs::write(&paths.history, "").unwrap();
+
+        assert!(store.delete(&ana.id).is_err());
+        assert!(store.delete(DEFAULT_PROFILE_ID).is_err());
+
+        store.switch(DEFAULT_PROFILE_ID).unwrap();
+        store.delete(&ana.id).unwrap();
+        assert!(!data_dir.exists());
+        assert_eq!(store.list().profiles.len(), 1);
+        assert!(store.switch(&ana.id).is_err());
+    }
+
+    #[test]
+    fn ids_are_not_reused_after_delete() {
+        let (_root, store) = temp_store("ids");
+        let first = store.create("Ana").unwrap();
+        store.delete(&first.id).unwrap();
+        let second = store.create("Ana").unwrap();
+        assert_ne!(first.id, second.id);
+    }
+
+    #[test]
+    fn corrupt_or_inconsistent_file_is_repaired() {
+        let (root, _store) = temp_store("repair");
+        let config = root.join("config");
+        fs::create_dir_all(&config).unwrap();
+
+        fs::write(
+            config.join(PROFILES_FILE_NAME),
+            r#"{"active_id":"p9","profiles":[{"id":"p4","name":"Ana"},{"id":"p4","name":"Dup"}]}"#,
+        )
+        .unwrap();
+        let store = ProfileStore::load(config.clone(), root.join("data"));
+        let list = store.list();
+        assert_eq!(list.active_id, DEFAULT_PROFILE_ID);
+        assert_eq!(list.profiles.len(), 2);
+        assert_eq!(store.create("Ben").unwrap().id, "p5");
+
+        fs::write(config.join(PROFILES_FILE_NAME), "not json").unwrap();
+        let store = ProfileStore::load(config.clone(), root.join("data"));
+        assert_eq!(store.list().profiles.len(), 1);
+        assert!(config.join("profiles.json.corrupt").exists());
+    }
+
+    #[test]
+    fn ids_that_could_escape_the_profiles_dir_are_dropped_and_rejected() {
+        let (root, _store) = temp_store("bad-ids");
+        let config = root.join("config");
+        fs::create_dir_all(&config).unwrap();
+        fs::write(
+            config.join(PROFILES_FILE_NAME),
+            r#"{"active_id":"..","profiles":[{"id":"..","name":"Up"},{"id":"p1/../..","name":"Deep"},
+                {"id":"","name":"Empty"},{"id":"p2","name":"Ana"}]}"#,
+        )
+        .unwrap();
+        let store = ProfileStore::load(config.clone(), root.join("data"));
+        let list = store.list();
+        assert_eq!(list.active_id, DEFAULT_PROFILE_ID);
+        let ids: Vec<String> = list.profiles.into_iter().map(|p| p.id).collect();
+        assert_eq!(ids, vec!["default", "p2"]);
+
+        for id in ["..", "p1/../..", "", "p", "P2"] {
+            assert!(matches!(
+                store.delete(id),
+                Err(FlashspanError::ProfileNotFound { .. })
+            ));
+            assert!(store.switch(id).is_err());
+            assert!(store.paths(id).is_err());
+        }
+        assert!(config.join(PROFILES_FILE_NAME).exists());
+        assert!(store.paths("p2").is_ok());
+    }
+}
@@ -0,0 +1,633 @@
+//! LAN race mode over WebSocket.
+//!
+//! [`RaceHost`] accepts players, sends each round's plan to all of them and checks their
+//! answers with the same [`Submission::validate`] as `submit_answer`. [`RaceClient`] joins a
+//! host, runs each plan through the caller's session runner and forwards answers.
+//!
+//! Each connection has one thread that alternates between sending queued messages and
+//! reading with a short timeout: a blocking WebSocket cannot be read and written from two
+//! threads at once.
+
+use crate::core::answer::{Submission, ValidationResult};
+use crate::core::error::FlashspanError;
+use crate::core::race::{
+    ClientMessage, HostMessage, Leaderboard, RACE_PROTOCOL_VERSION, RaceAnswer, with_answers,
+    without_answers,
+};
+use crate::core::scoring::ScoringConfig;
+use crate::core::types::{SessionPlan, SessionStep};
+use crate::session::recover_lock;
+use log::{info, warn};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::io::{ErrorKind, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{Receiver, Sender, TryRecvError, channel};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use tungstenite::{Message, WebSocket};
+
+pub const DEFAULT_RACE_PORT: u16 = 47_615;
+
+/// How long a connection thread waits for a message before checking its queue again.
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+/// Limit on connecting and on each step of the WebSocket handshake.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+/// A player's session resumes as soon as a checkpoint is answered, but the host only hears
+/// of it a network hop later, so answers this close to the last flash are still accepted.
+const EARLY_ANSWER_SLACK_MS: u64 = 250;
+
+/// Run one connection until `stop` is set, `outgoing` is dropped, or the peer goes away:
+/// send everything queued on `outgoing`, and pass each message read to `on_message`.
+fn pump<S, In, Out>(
+    ws: &mut WebSocket<S>,
+    outgoing: &Receiver<Out>,
+    stop: &AtomicBool,
+    mut on_message: impl FnMut(In),
+) -> Result<(), tungstenite::Error>
+where
+    S: Read + Write,
+    In: DeserializeOwned,
+    Out: Serialize,
+{
+    loop {
+        if stop.load(Ordering::SeqCst) {
+            return close(ws);
+        }
+        match outgoing.try_recv() {
+            Ok(message) => {
+                let text = serde_json::to_string(&message)
+                    .map_err(|e| std::io::Error::new(ErrorKind::InvalidData, e))?;
+                ws.send(Message::text(text))?;
+                continue;
+            }
+            Err(TryRecvError::Disconnected) => return close(ws),
+            Err(TryRecvError::Empty) => {}
+        }
+
+        match ws.read() {
+            Ok(Message::Text(text)) => match serde_json::from_str(text.as_str()) {
+                Ok(message) => on_message(message),
+                Err(e) => warn!("ignoring malformed race message: {}", e),
+            },
+            Ok(_) => {}
+            Err(tungstenite::Error::Io(e))
+                if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {}
+            Err(tungstenite::Error::ConnectionClosed | tungstenite::Error::AlreadyClosed) => {
+                return Ok(());
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+fn close<S: Read + Write>(ws: &mut WebSocket<S>) -> Result<(), tungstenite::Error> {
+    match ws.close(None).and_then(|_| ws.flush()) {
+        Ok(()) | Err(tungstenite::Error::ConnectionClosed | tungstenite::Error::AlreadyClosed) => {
+            Ok(())
+        }
+        Err(e) => Err(e),
+    }
+}
+
+fn network_error(what: &str, e: impl std::fmt::Display) -> FlashspanError {
+    FlashspanError::race(format!("{}: {}", what, e))
+}
+
+struct OpenRound {
+    plan: SessionPlan,
+    sent_at: Instant,
+    /// Index of each checkpoint and when it is shown, counted from the start of the plan
+    /// without any time spent answering.
+    checkpoints: Vec<(u32, u64)>,
+    /// Checkpoints each player has answered so far, and the time they spent on them.
+    waits: HashMap<u32, (usize, u64)>,
+}
+
+impl OpenRound {
+    fn new(plan: SessionPlan) -> Self {
+        let mut checkpoints = Vec::new();
+        let mut offset_ms = 0;
+        for step in &plan.steps {
+            let delay_ms = match step {
+                SessionStep::Checkpoint {
+                    index,
+                    delay_ms_before_next,
+                    ..
+                } => {
+                    checkpoints.push((*index, offset_ms));
+                    *delay_ms_before_next
+                }
+                SessionStep::CountdownTick {
+                    delay_ms_before_next,
+                    ..
+                }
+                | SessionStep::ShowNumber {
+                    delay_ms_before_next,
+                    ..
+                }
+                | SessionStep::ClearScreen {
+                    delay_ms_before_next,
+                    ..
+                }
+                | SessionStep::ShowPair {
+                    delay_ms_before_next,
+                    ..
+                } => *delay_ms_before_next,
+                SessionStep::Complete { .. } => 0,
+            };
+            offset_ms += delay_ms;
+        }
+        Self {
+            plan,
+            sent_at: Instant::now(),
+            checkpoints,
+            waits: HashMap::new(),
+        }
+    }
+
+    fn elapsed_ms(&self) -> u64 {
+        self.sent_at.elapsed().as_millis() as u64
+    }
+
+    /// Time spent answering checkpoints so far.
+    fn waited_ms(&self, player_id: u32) -> u64 {
+        self.waits
+            .get(&player_id)
+            .map_or(0, |&(_, waited_ms)| waited_ms)
+    }
+}
+
+type LeaderboardListener = Box<dyn Fn(&Leaderboard) + Send + Sync>;
+
+struct HostShared {
+    leaderboard: Mutex<Leaderboard>,
+    players: Mutex<HashMap<u32, Sender<HostMessage>>>,
+    round: Mutex<Option<OpenRound>>,
+    scoring: Mutex<ScoringConfig>,
+    stop: AtomicBool,
+    on_change: LeaderboardListener,
+}
+
+impl HostShared {
+    fn send_to(&self, player_id: u32, message: HostMessage) {
+        if let Some(tx) = recover_lock(&self.players, "race players").get(&player_id) {
+            let _ = tx.send(message);
+        }
+    }
+
+    fn broadcast(&self, message: impl Fn() -> HostMessage) {
+        for tx in recover_lock(&self.players, "race players").values() {
+            let _ = tx.send(message());
+        }
+    }
+
+    fn leaderboard_changed(&self) {
+        let leaderboard = recover_lock(&self.leaderboard, "race leaderboard").clone();
+        self.broadcast(|| HostMessage::Leaderboard {
+            leaderboard: leaderboard.clone(),
+        });
+        (self.on_change)(&leaderboard);
+    }
+
+    fn join(
+        &self,
+        name: &str,
+        version: u32,
+        tx: &Sender<HostMessage>,
+    ) -> Result<u32, FlashspanError> {
+        if version != RACE_PROTOCOL_VERSION {
+            return Err(FlashspanError::race(format!(
+                "this host speaks race protocol {}, not {}; update Flashspan",
+                RACE_PROTOCOL_VERSION, version
+            )));
+        }
+        let player_id = recover_lock(&self.leaderboard, "race leaderboard").join(name)?;
+        recover_lock(&self.players, "race players").insert(player_id, tx.clone());
+        self.send_to(player_id, HostMessage::Welcome { player_id });
+        self.leaderboard_changed();
+        Ok(player_id)
+    }
+
+    fn leave(&self, player_id: u32) {
+        recover_lock(&self.players, "race players").remove(&player_id);
+        recover_lock(&self.leaderboard, "race leaderboard").leave(player_id);
+        self.leaderboard_changed();
+    }
+
+    /// Time a player's checkpoint answer: from when the checkpoint was shown, given the
+    /// player's earlier waits, to now. Checkpoints must be answered in order.
+    fn checkpoint(&self, player_id: u32, round: u64, index: u32) -> Result<(), FlashspanError> {
+        let mut open = recover_lock(&self.round, "race round");
+        let Some(open) = open.as_mut().filter(|o| o.plan.session_id == round) else {
+            return Err(FlashspanError::race(format!("round {} is over", round)));
+        };
+        let elapsed_ms = open.elapsed_ms();
+        let (answered, waited_ms) = open.waits.get(&player_id).copied().unwrap_or((0, 0));
+        match open.checkpoints.get(answered) {
+            Some(&(expected, shown_at_ms)) if expected == index => {
+                let wait_ms = elapsed_ms.saturating_sub(shown_at_ms + waited_ms);
+                open.waits
+                    .insert(player_id, (answered + 1, waited_ms + wait_ms));
+                Ok(())
+            }
+            _ => Err(FlashspanError::race(format!(
+                "checkpoint {} of round {} is out of order",
+                index, round
+            ))),
+        }
+    }
+
+    /// Check an answer against the open round, as `submit_answer` would. Answers that arrive
+    /// before the player could have seen the last number are refused.
+    fn answer(
+        &self,
+        player_id: u32,
+        round: u64,
+        submission: &Submission,
+    ) -> Result<(), FlashspanError> {
+        let (validation, answer_ms, score) = {
+            let open = recover_lock(&self.round, "race round");
+            let Some(open) = open.as_ref().filter(|o| o.plan.session_id == round) else {
+                return Err(FlashspanError::race(format!("round {} is over", round)));
+            };
+            let plan = &open.plan;
+            // Checkpoints hold the flashes for as long as the player takes to answer them.
+            let last_flash_ms = plan.total_duration_ms + open.waited_ms(player_id);
+            let elapsed_ms = open.elapsed_ms();
+            if elapsed_ms + EARLY_ANSWER_SLACK_MS < last_flash_ms {
+                return Err(FlashspanError::race(format!(
+                    "answer to round {} arrived before its last number was shown",
+                    round
+                )));
+            }
+            let answer_ms = elapsed_ms.saturating_sub(last_flash_ms);
+            let mut validation = submission.validate(
+                plan.expected_sum,
+                plan.config_snapshot.decimal_places,
+                &plan.pairs_generated,
+            )?;
+            validation.answer_ms = Some(answer_ms);
+            let score = recover_lock(&self.scoring, "race scoring").score(
+                &plan.config_snapshot,
+                &plan.numbers_generated,
+                &validation,
+            );
+            (validation, answer_ms, score)
+        };
+
+        recover_lock(&self.leaderboard, "race leaderboard").record(
+            player_id,
+            round,
+            RaceAnswer {
+                correct: validation.correct,
+                answer_ms,
+                score,
+            },
+        )?;
+        self.send_to(
+            player_id,
+            HostMessage::Verdict {
+                round,
+                validation,
+                answer_ms,
+                score,
+            },
+        );
+        self.leaderboard_changed();
+        Ok(())
+    }
+}
+
+fn serve_player(shared: Arc<HostShared>, stream: TcpStream) {
+    let _ = stream.set_read_timeout(Some(CONNECT_TIMEOUT));
+    let mut ws = match tungstenite::accept(stream) {
+        Ok(ws) => ws,
+        Err(e) => {
+            warn!("race handshake failed: {}", e);
+            return;
+        }
+    };
+    let _ = ws.get_ref().set_read_timeout(Some(POLL_INTERVAL));
+
+    let (tx, rx) = channel();
+    let mut player_id = None;
+    let result = pump(&mut ws, &rx, &shared.stop, |message: ClientMessage| {
+        let outcome = match (message, player_id) {
+            (ClientMessage::Join { name, version }, None) => shared
+                .join(&name, version, &tx)
+                .map(|id| player_id = Some(id)),
+            (ClientMessage::Join { .. }, Some(_)) => {
+                Err(FlashspanError::race("you already joined this race"))
+            }
+            (ClientMessage::Answer { round, submission }, Some(id)) => {
+                shared.answer(id, round, &submission)
+            }
+            (ClientMessage::Checkpoint { round, index }, Some(id)) => {
+                shared.checkpoint(id, round, index)
+            }
+            (ClientMessage::Answer { .. } | ClientMessage::Checkpoint { .. }, None) => {
+                Err(FlashspanError::race("join the race before answering"))
+            }
+        };
+        if let Err(e) = outcome {
+            let _ = tx.send(HostMessage::Rejected {
+                reason: e.to_string(),
+            });
+        }
+    });
+    if let Err(e) = result {
+        warn!("race player connection ended: {}", e);
+    }
+    if let Some(id) = player_id {
+        shared.leave(id);
+    }
+}
+
+/// A race host: listens for players until dropped.
+pub struct RaceHost {
+    shared: Arc<HostShared>,
+    addr: SocketAddr,
+    accept_thread: Option<JoinHandle<()>>,
+}
+
+impl RaceHost {
+    /// Listen on `addr`; `on_change` gets the leaderboard after every join, leave and answer.
+    pub fn bind(
+        addr: impl ToSocketAddrs,
+        on_change: impl Fn(&Leaderboard) + Send + Sync + 'static,
+    ) -> Result<Self, FlashspanError> {
+        let listener = TcpListener::bind(addr).map_err(|e| network_error("cannot host", e))?;
+        let addr = listener
+            .local_addr()
+            .map_err(|e| network_error("cannot host", e))?;
+        listener
+            .set_nonblocking(true)
+            .map_err(|e| network_error("cannot host", e))?;
+
+        let shared = Arc::new(HostShared {
+            leaderboard: Mutex::new(Leaderboard::default()),
+            players: Mutex::new(HashMap::new()),
+            round: Mutex::new(None),
+            scoring: Mutex::new(ScoringConfig::default()),
+            stop: AtomicBool::new(false),
+            on_change: Box::new(on_change),
+        });
+        let accept_shared = Arc::clone(&shared);
+        let accept_thread = std::thread::Builder::new()
+            .name("race-host".into())
+            .spawn(move || {
+                while !accept_shared.stop.load(Ordering::SeqCst) {
+                    match listener.accept() {
+                        Ok((stream, peer)) => {
+                            info!("race player connecting from {}", peer);
+                            let _ = stream.set_nonblocking(false);
+                            let shared = Arc::clone(&accept_shared);
+                            if let Err(e) = std::thread::Builder::new()
+                                .name("race-player".into())
+                                .spawn(move || serve_player(shared, stream))
+                            {
+                                warn!("race player thread spawn failed: {}", e);
+                            }
+                        }
+                        Err(e) if e.kind() == ErrorKind::WouldBlock => {
+                            std::thread::sleep(POLL_INTERVAL)
+                        }
+                        Err(e) => warn!("race accept failed: {}", e),
+                    }
+                }
+            })
+            .map_err(|e| network_error("cannot host", e))?;
+
+        info!("hosting race on {}", addr);
+        Ok(Self {
+            shared,
+            addr,
+            accept_thread: Some(accept_thread),
+        })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    pub fn leaderboard(&self) -> Leaderboard {
+        recover_lock(&self.shared.leaderboard, "race leaderboard").clone()
+    }
+
+    /// Score answers with `scoring` from now on.
+    pub fn set_scoring(&self, scoring: ScoringConfig) {
+        *recover_lock(&self.shared.scoring, "race scoring") = scoring;
+    }
+
+    /// Send `plan` to every player as the next round and return its number. Players get it
+    /// without the answers. Answers to the previous round are refused from now on.
+    pub fn start_round(&self, plan: SessionPlan) -> Result<u64, FlashspanError> {
+        let round = {
+            let mut open = recover_lock(&self.shared.round, "race round");
+            let round = open.as_ref().map_or(1, |o| o.plan.session_id + 1);
+            let plan = plan.with_session_id(round);
+            let sent = without_answers(&plan);
+            recover_lock(&self.shared.leaderboard, "race leaderboard").start_round(round);
+            *open = Some(OpenRound::new(plan));
+            self.shared.broadcast(|| HostMessage::Round {
+                round,
+                plan: sent.clone(),
+            });
+            round
+        };
+        self.shared.leaderboard_changed();
+        Ok(round)
+    }
+}
+
+impl Drop for RaceHost {
+    fn drop(&mut self) {
+        self.shared.stop.store(true, Ordering::SeqCst);
+        if let Some(handle) = self.accept_thread.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// What a race client reports back to its caller.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RaceEvent {
+    /// A round started locally as `session_id`.
+    Round {
+        round: u64,
+        session_id: u64,
+    },
+    Verdict {
+        round: u64,
+        validation: ValidationResult,
+        answer_ms: u64,
+        score: u32,
+    },
+    Leaderboard {
+        leaderboard: Leaderboard,
+    },
+    /// The host refused an answer, or a round could not be started here.
+    Error {
+        reason: String,
+    },
+    Disconnected,
+}
+
+/// A player connected to a race host, until dropped.
+pub struct RaceClient {
+    player_id: u32,
+    outgoing: Sender<ClientMessage>,
+    /// Local session id of each round started here.
+    rounds: Arc<Mutex<HashMap<u64, u64>>>,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl RaceClient {
+    /// Join the host at `address` (`host:port`) as `name`. Each round's plan is passed to
+    /// `run_round`, which starts it locally and returns the session id; everything else
+    /// arrives through `on_event`.
+    pub fn connect(
+        address: &str,
+        name: &str,
+        mut run_round: impl FnMut(SessionPlan) -> Result<u64, FlashspanError> + Send + 'static,
+        mut on_event: impl FnMut(RaceEvent) + Send + 'static,
+    ) -> Result<Self, FlashspanError> {
+        let (mut ws, player_id) = handshake(address, name)?;
+        let _ = ws.get_ref().set_read_timeout(Some(POLL_INTERVAL));
+
+        let (outgoing, rx) = channel();
+        let rounds = Arc::new(Mutex::new(HashMap::new()));
+        let stop = Arc::new(AtomicBool::new(false));
+        let (thread_rounds, thread_stop) = (Arc::clone(&rounds), Arc::clone(&stop));
+        let thread = std::thread::Builder::new()
+            .name("race-client".into())
+            .spawn(move || {
+                let result = pump(&mut ws, &rx, &thread_stop, |message: HostMessage| {
+                    let event = match message {
+                        HostMessage::Round { round, plan } => match run_round(with_answers(plan)) {
+                            Ok(session_id) => {
+                                recover_lock(&thread_rounds, "race rounds")
+                                    .insert(session_id, round);
+                                RaceEvent::Round { round, session_id }
+                            }
+                            Err(e) => RaceEvent::Error {
+                                reason: format!("could not start round {}: {}", round, e),
+                            },
+                        },
+                        HostMessage::Verdict {
+                            round,
+                            validation,
+                            answer_ms,
+                            score,
+                        } => RaceEvent::Verdict {
+                            round,
+                            validation,
+                            answer_ms,
+                            score,
+                        },
+                        HostMessage::Leaderboard { leaderboard } => {
+                            RaceEvent::Leaderboard { leaderboard }
+                        }
+                        HostMessage::Rejected { reason } => RaceEvent::Error { reason },
+                        HostMessage::Welcome { .. } => return,
+                    };
+                    on_event(event);
+                });
+                if let Err(e) = result {
+                    warn!("race connection ended: {}", e);
+                }
+                if !thread_stop.load(Ordering::SeqCst) {
+                    on_event(RaceEvent::Disconnected);
+                }
+            })
+            .map_err(|e| network_error("cannot join", e))?;
+
+        Ok(Self {
+            player_id,
+            outgoing,
+            rounds,
+            stop,
+            thread: Some(thread),
+        })
+    }
+
+    pub fn player_id(&self) -> u32 {
+        self.player_id
+    }
+
+    /// Send the answer to a local session that was started from a race round. Returns false
+    /// for any other session.
+    pub fn submit(&self, session_id: u64, submission: &Submission) -> bool {
+        let Some(&round) = recover_lock(&self.rounds, "race rounds").get(&session_id) else {
+            return false;
+        };
+        self.outgoing
+            .send(ClientMessage::Answer {
+                round,
+                submission: submission.clone(),
+            })
+            .is_ok()
+    }
+
+    /// Tell the host a checkpoint of a race round was answered, so the time spent on it does
+    /// not count against the final answer. Returns false for any other session.
+    pub fn checkpoint(&self, session_id: u64, index: u32) -> bool {
+        let Some(&round) = recover_lock(&self.rounds, "race rounds").get(&session_id) else {
+            return false;
+        };
+        self.outgoing
+            .send(ClientMessage::Checkpoint { round, index })
+            .is_ok()
+    }
+}
+
+impl Drop for RaceClient {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(handle) = self.thread.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Connect, upgrade to WebSocket and join, waiting for the host to accept or refuse.
+fn handshake(address: &str, name: &str) -> Result<(WebSocket<TcpStream>, u32), FlashspanError> {
+    let cannot_join = |e: &dyn std::fmt::Display| network_error("cannot join", e);
+    let addr = address
+        .to_socket_addrs()
+        .map_err(|e| cannot_join(&e))?
+        .next()
+        .ok_or_else(|| cannot_join(&format!("no address for {}", address)))?;
+    let stream = TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT).map_err(|e| cannot_join(&e))?;
+    stream
+        .set_read_timeout(Some(CONNECT_TIMEOUT))
+        .map_err(|e| cannot_join(&e))?;
+    let (mut ws, _) =
+        tungstenite::client(format!("ws://{}/", addr), stream).map_err(|e| cannot_join(&e))?;
+
+    let join = ClientMessage::Join {
+        name: name.to_string(),
+        version: RACE_PROTOCOL_VERSION,
+    };
+    let text = serde_json::to_string(&join).map_err(|e| cannot_join(&e))?;
+    ws.send(Message::text(text)).map_err(|e| cannot_join(&e))?;
+    loop {
+        let message = ws.read().map_err(|e| cannot_join(&e))?;
+        let Message::Text(text) = message else {
+            continue;
+        };
+        match serde_json::from_str(text.as_str()) {
+            Ok(HostMessage::Welcome { player_id }) => return Ok((ws, player_id)),
+            Ok(HostMessage::Rejected { reason }) => return Err(FlashspanError::race(reason)),
+            // Broadcasts sent before the welcome are not meant for this player yet.
+            Ok(_) => {}
+            Err(e) => return Err(cannot_join(&e)),
+        }
+    }
+}
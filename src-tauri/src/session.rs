@@ -1,14 +1,18 @@
 use crate::core::types::{
-    AutoRepeatPlan, ClearScreen, SessionComplete, SessionConfig, SessionConfigEffective,
-    SessionPlan, SessionStep, ShowNumber,
+    AdaptiveAdjustment, AutoRepeatPlan, Checkpoint, CheckpointResult, ClearScreen, SessionComplete,
+    SessionConfig, SessionConfigEffective, SessionPaused, SessionPlan, SessionResumed, SessionStep,
+    ShowNumber, ShowPair, StepTiming, TimingReport,
+};
+use crate::core::{
+    answer::ValidationResult, decimal::format_fixed, dictation::SpokenPhrase,
+    engine::build_session_plan, error::FlashspanError, plan_file, validate::validate_config,
 };
-use crate::core::{engine::build_session_plan, validate::validate_config};
 use log::warn;
 use std::time::{SystemTime, UNIX_EPOCH};
 use std::{
     collections::VecDeque,
     sync::{
-        Arc, Mutex,
+        Arc, Condvar, Mutex,
         atomic::{AtomicBool, AtomicU64, Ordering},
     },
     thread,
@@ -16,7 +20,8 @@ use std::{
     time::{Duration, Instant},
 };
 
-fn now_epoch_ms() -> u64 {
+/// Wall-clock milliseconds since the Unix epoch, as stamped on events and stored records.
+pub fn now_epoch_ms() -> u64 {
     SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .unwrap_or_default()
@@ -27,7 +32,29 @@ pub trait SessionEmitter {
     fn clear_screen(&self, payload: ClearScreen);
     fn countdown_tick(&self, value: String);
     fn show_number(&self, payload: ShowNumber);
+    fn show_pair(&self, payload: ShowPair);
     fn session_complete(&self, payload: SessionComplete);
+    fn session_paused(&self, payload: SessionPaused);
+    fn session_resumed(&self, payload: SessionResumed);
+    /// The session waits after this until the checkpoint is answered through its
+    /// [`CheckpointGate`], or until it is stopped. A pause during the wait is emitted at once.
+    fn checkpoint(&self, payload: Checkpoint);
+
+    /// Profile the session runs under, stamped on its `SessionComplete`.
+    fn profile_id(&self) -> Option<String> {
+        None
+    }
+
+    /// Called on the starting thread once a new session is accepted, before its first step,
+    /// e.g. to cut sounds still queued from the last one. Must not block.
+    fn session_starting(&self) {}
+
+    /// Cue played with each flash. Must not block the session thread.
+    fn beep(&self) {}
+
+    /// Read a flashed number aloud; replaces the beep in dictation sessions. Must not block
+    /// the session thread.
+    fn speak(&self, _phrase: SpokenPhrase) {}
 }
 
 #[derive(Debug, Clone)]
@@ -39,17 +66,134 @@ pub enum SessionState {
         #[allow(dead_code)]
         total: u32,
     },
+    Paused {
+        #[allow(dead_code)]
+        current: u32,
+        #[allow(dead_code)]
+        total: u32,
+    },
     Complete,
 }
 
+/// The checkpoint a session is waiting on, or its answer until the session picks it up.
+#[derive(Debug, Default)]
+enum GateState {
+    #[default]
+    Closed,
+    Open {
+        session_id: u64,
+        index: u32,
+        expected_sum: i64,
+        decimal_places: u32,
+        asked_at: Instant,
+    },
+    Answered(CheckpointResult),
+}
+
+/// What ended a [`CheckpointGate::wait`].
+#[derive(Debug)]
+enum GateWait {
+    Answered(CheckpointResult),
+    /// The session was paused; the checkpoint stays open.
+    Paused,
+    Stopped,
+}
+
+/// Hands checkpoint answers from the UI to the session thread waiting for them.
+#[derive(Debug, Default)]
+pub struct CheckpointGate {
+    state: Mutex<GateState>,
+    changed: Condvar,
+}
+
+impl CheckpointGate {
+    /// Check `provided_sum` against the checkpoint `session_id` is waiting on, and let the
+    /// session continue. Decimal sessions count units of 10^-decimal_places.
+    pub fn answer(
+        &self,
+        session_id: u64,
+        provided_sum: i64,
+    ) -> Result<CheckpointResult, FlashspanError> {
+        let mut state = recover_lock(&self.state, "checkpoint");
+        let GateState::Open {
+            session_id: open_id,
+            index,
+            expected_sum,
+            decimal_places,
+            asked_at,
+        } = *state
+        else {
+            return Err(FlashspanError::NoCheckpointPending { session_id });
+        };
+        if open_id != session_id {
+            return Err(FlashspanError::NoCheckpointPending { session_id });
+        }
+
+        let result = CheckpointResult {
+            index,
+            validation: ValidationResult::for_sum(expected_sum, provided_sum, decimal_places),
+            answer_ms: asked_at.elapsed().as_millis() as u64,
+        };
+        *state = GateState::Answered(result.clone());
+        self.changed.notify_all();
+        Ok(result)
+    }
+
+    /// Wake the waiting session so it notices a pause or stop. Taking the lock first means
+    /// a flag set just before this cannot slip in between the waiter's check and its wait.
+    fn wake(&self) {
+        let _state = recover_lock(&self.state, "checkpoint");
+        self.changed.notify_all();
+    }
+
+    fn open(&self, session_id: u64, index: u32, expected_sum: i64, decimal_places: u32) {
+        *recover_lock(&self.state, "checkpoint") = GateState::Open {
+            session_id,
+            index,
+            expected_sum,
+            decimal_places,
+            asked_at: Instant::now(),
+        };
+    }
+
+    /// Block until the open checkpoint is answered, the session is paused or it is stopped.
+    fn wait(&self, stop: &AtomicBool, paused: &AtomicBool) -> GateWait {
+        let mut state = recover_lock(&self.state, "checkpoint");
+        loop {
+            if stop.load(Ordering::SeqCst) {
+                *state = GateState::Closed;
+                return GateWait::Stopped;
+            }
+            if matches!(*state, GateState::Answered(_)) {
+                let GateState::Answered(result) = std::mem::take(&mut *state) else {
+                    unreachable!("just matched Answered");
+                };
+                return GateWait::Answered(result);
+            }
+            if paused.load(Ordering::SeqCst) {
+                return GateWait::Paused;
+            }
+            state = self.changed.wait(state).unwrap_or_else(|e| e.into_inner());
+        }
+    }
+}
+
+/// Delay (ms), rounds left after this one, config, seed, and the auto-repeat generation the
+/// next round must still match when it starts.
+pub type NextRound = (u64, u32, SessionConfig, u64, u64);
+
 pub struct SessionManager {
     state: Arc<Mutex<SessionState>>,
     worker: Mutex<Option<JoinHandle<()>>>,
     stop: Mutex<Option<Arc<AtomicBool>>>,
+    paused: Mutex<Option<Arc<AtomicBool>>>,
     next_session_id: AtomicU64,
     recent_results: Arc<Mutex<VecDeque<SessionComplete>>>,
+    recent_plans: Arc<Mutex<VecDeque<SessionPlan>>>,
+    timing_reports: Arc<Mutex<VecDeque<TimingReport>>>,
     auto_repeat_plan: Arc<Mutex<Option<AutoRepeatPlan>>>,
     auto_repeat_generation: AtomicU64,
+    checkpoints: Arc<CheckpointGate>,
 }
 
 impl Default for SessionManager {
@@ -58,15 +202,20 @@ impl Default for SessionManager {
             state: Arc::new(Mutex::new(SessionState::Idle)),
             worker: Mutex::new(None),
             stop: Mutex::new(None),
+            paused: Mutex::new(None),
             next_session_id: AtomicU64::new(1),
             recent_results: Arc::new(Mutex::new(VecDeque::new())),
+            recent_plans: Arc::new(Mutex::new(VecDeque::new())),
+            timing_reports: Arc::new(Mutex::new(VecDeque::new())),
             auto_repeat_plan: Arc::new(Mutex::new(None)),
             auto_repeat_generation: AtomicU64::new(1),
+            checkpoints: Arc::new(CheckpointGate::default()),
         }
     }
 }
 
-pub(crate) fn recover_lock<'a, T>(mutex: &'a Mutex<T>, name: &str) -> std::sync::MutexGuard<'a, T> {
+/// Lock `mutex`, taking the data back if a thread panicked while holding it.
+pub fn recover_lock<'a, T>(mutex: &'a Mutex<T>, name: &str) -> std::sync::MutexGuard<'a, T> {
     mutex.lock().unwrap_or_else(|e| {
         warn!("mutex {} poisoned, recovering", name);
         e.into_inner()
@@ -84,57 +233,115 @@ impl SessionManager {
             let handle = worker.take().expect("just checked Some");
             let _ = handle.join();
             *recover_lock(&self.stop, "stop") = None;
+            *recover_lock(&self.paused, "paused") = None;
         }
     }
 
+    /// Start a session generated from `config`. `seed` reproduces an earlier session; without
+    /// one a random seed is used, and either way it is reported in `SessionComplete`.
     pub fn start_with_emitter<E: SessionEmitter + Send + 'static>(
         &self,
         emitter: E,
         config: SessionConfig,
-    ) -> Result<u64, String> {
-        self.cleanup_finished_worker();
-
+        seed: Option<u64>,
+    ) -> Result<u64, FlashspanError> {
         validate_config(&config)?;
 
+        // Convert SessionConfig to SessionConfigEffective for plan generation.
+        let config_effective = SessionConfigEffective {
+            digits_per_number: config.digits_per_number,
+            number_duration_s: config.number_duration_ms as f64 / 1000.0,
+            delay_between_numbers_s: config.delay_between_numbers_ms as f64 / 1000.0,
+            total_numbers: config.total_numbers,
+            allow_negative_numbers: config.allow_negative_numbers,
+            formula_mode: config.formula_mode,
+            carry_policy: config.carry_policy,
+            operation: config.operation,
+            second_operand_digits: config.second_operand_digits,
+            decimal_places: config.decimal_places,
+            timing_schedule: config.timing_schedule.to_seconds(),
+            digit_range: config.digit_range,
+            digit_pattern: config.digit_pattern.clone(),
+            dictation: config.dictation,
+            checkpoints: config.checkpoints.clone(),
+        };
+
+        let total = config.total_numbers;
+        self.spawn_worker(emitter, total, move |session_id| {
+            build_session_plan(session_id, config, config_effective, seed)
+        })
+    }
+
+    /// Replay a loaded plan step for step. The plan is checked first and runs under a fresh
+    /// session id; nothing else about it changes.
+    pub fn start_plan_with_emitter<E: SessionEmitter + Send + 'static>(
+        &self,
+        emitter: E,
+        plan: SessionPlan,
+    ) -> Result<u64, FlashspanError> {
+        plan_file::verify(&plan)?;
+
+        let total = plan.config_snapshot.total_numbers;
+        self.spawn_worker(emitter, total, move |session_id| {
+            plan.with_session_id(session_id)
+        })
+    }
+
+    fn spawn_worker<E: SessionEmitter + Send + 'static>(
+        &self,
+        emitter: E,
+        total: u32,
+        make_plan: impl FnOnce(u64) -> SessionPlan + Send + 'static,
+    ) -> Result<u64, FlashspanError> {
+        self.cleanup_finished_worker();
+
         {
             let worker = recover_lock(&self.worker, "worker");
             if let Some(handle) = worker.as_ref()
                 && !handle.is_finished()
             {
-                return Err("session already running".to_string());
+                return Err(FlashspanError::SessionAlreadyRunning);
             }
         }
+        emitter.session_starting();
 
         let stop_flag = Arc::new(AtomicBool::new(false));
         *recover_lock(&self.stop, "stop") = Some(stop_flag.clone());
+        let paused_flag = Arc::new(AtomicBool::new(false));
+        *recover_lock(&self.paused, "paused") = Some(paused_flag.clone());
 
         {
             let mut state = recover_lock(&self.state, "state");
-            *state = SessionState::ShowingNumbers {
-                current: 0,
-                total: config.total_numbers,
-            };
+            *state = SessionState::ShowingNumbers { current: 0, total };
         }
 
         let session_id = self.next_session_id.fetch_add(1, Ordering::SeqCst);
 
         let state_arc = Arc::clone(&self.state);
         let recent_results_arc = Arc::clone(&self.recent_results);
+        let recent_plans_arc = Arc::clone(&self.recent_plans);
+        let timing_reports_arc = Arc::clone(&self.timing_reports);
         let plan_arc = Arc::clone(&self.auto_repeat_plan);
+        let checkpoints = Arc::clone(&self.checkpoints);
         let handle = std::thread::Builder::new()
             .name("session-worker".into())
             .spawn(move || {
                 run_session_loop(
                     emitter,
-                    config,
+                    make_plan(session_id),
                     state_arc,
                     stop_flag,
-                    session_id,
+                    paused_flag,
                     recent_results_arc,
+                    recent_plans_arc,
+                    timing_reports_arc,
                     plan_arc,
+                    checkpoints,
                 );
             })
-            .map_err(|e| format!("failed to spawn session worker: {}", e))?;
+            .map_err(|e| FlashspanError::SessionSpawnFailed {
+                detail: e.to_string(),
+            })?;
 
         *recover_lock(&self.worker, "worker") = Some(handle);
         Ok(session_id)
@@ -153,7 +360,7 @@ impl SessionManager {
         self.auto_repeat_generation.load(Ordering::SeqCst)
     }
 
-    pub fn result_for(&self, session_id: u64) -> Result<SessionComplete, String> {
+    pub fn result_for(&self, session_id: u64) -> Result<SessionComplete, FlashspanError> {
         let guard = recover_lock(&self.recent_results, "recent_results");
 
         for result in guard.iter().rev() {
@@ -162,16 +369,63 @@ impl SessionManager {
             }
         }
 
-        Err("session result not found".to_string())
+        Err(FlashspanError::ResultNotFound { session_id })
+    }
+
+    /// The exact plan a recent session ran, for saving as a plan file.
+    pub fn plan_for(&self, session_id: u64) -> Result<SessionPlan, FlashspanError> {
+        recover_lock(&self.recent_plans, "recent_plans")
+            .iter()
+            .rev()
+            .find(|plan| plan.session_id == session_id)
+            .cloned()
+            .ok_or(FlashspanError::ResultNotFound { session_id })
     }
 
+    /// Answer the checkpoint the running session is waiting on.
+    pub fn answer_checkpoint(
+        &self,
+        session_id: u64,
+        provided_sum: i64,
+    ) -> Result<CheckpointResult, FlashspanError> {
+        self.checkpoints.answer(session_id, provided_sum)
+    }
+
+    /// Planned vs measured step timings of a finished (or stopped) session.
+    pub fn timing_report_for(&self, session_id: u64) -> Result<TimingReport, FlashspanError> {
+        recover_lock(&self.timing_reports, "timing_reports")
+            .iter()
+            .rev()
+            .find(|report| report.session_id == session_id)
+            .cloned()
+            .ok_or(FlashspanError::TimingReportNotFound { session_id })
+    }
+
+    /// Feed a validated answer to the adaptive policy of the auto-repeat run waiting on
+    /// `session_id`, adjusting the config of the next round. `None` when no adaptive round
+    /// follows this session.
+    pub fn record_adaptive_outcome(
+        &self,
+        session_id: u64,
+        correct: bool,
+    ) -> Option<AdaptiveAdjustment> {
+        let mut plan_guard = recover_lock(&self.auto_repeat_plan, "auto_repeat_plan");
+        let plan = plan_guard.as_mut()?;
+        if plan.awaiting_validation_session_id != Some(session_id) || plan.remaining == 0 {
+            return None;
+        }
+        let run = plan.adaptive.as_mut()?;
+        Some(run.policy.apply(&mut run.state, &mut plan.config, correct))
+    }
+
+    /// Consume the answer the auto-repeat run is waiting on and schedule the next round.
     pub fn mark_validated_and_schedule_info(
         &self,
         session_id: u64,
-    ) -> Result<Option<(u64, u32, SessionConfig, u64)>, String> {
+    ) -> Result<Option<NextRound>, FlashspanError> {
         let generation = self.auto_repeat_generation.load(Ordering::SeqCst);
 
-        let (delay_ms, config, remaining_after_decrement) = {
+        let (delay_ms, config, seed, remaining_after_decrement) = {
             let mut plan_guard = recover_lock(&self.auto_repeat_plan, "auto_repeat_plan");
             let Some(plan) = plan_guard.as_mut() else {
                 warn!("[auto-repeat] mark_validated_and_schedule_info: plan is None");
@@ -197,23 +451,54 @@ impl SessionManager {
 
             plan.awaiting_validation_session_id = None;
             plan.remaining = plan.remaining.saturating_sub(1);
+            plan.seed = plan.seed_strategy.next_seed(plan.seed);
 
             warn!(
                 "[auto-repeat] mark_validated_and_schedule_info: success, remaining_after_decrement={}",
                 plan.remaining
             );
 
-            (plan.delay_ms, plan.config.clone(), plan.remaining)
+            (
+                plan.delay_ms,
+                plan.config.clone(),
+                plan.seed,
+                plan.remaining,
+            )
         };
 
         Ok(Some((
             delay_ms,
             remaining_after_decrement,
             config,
+            seed,
             generation,
         )))
     }
 
+    fn set_paused(&self, paused: bool) -> Result<(), FlashspanError> {
+        self.cleanup_finished_worker();
+
+        match recover_lock(&self.paused, "paused").as_ref() {
+            Some(flag) => {
+                flag.store(paused, Ordering::SeqCst);
+                self.checkpoints.wake();
+                Ok(())
+            }
+            None => Err(FlashspanError::NoSessionRunning),
+        }
+    }
+
+    /// Freeze the running session mid-step. The worker keeps the time left in the current
+    /// step and emits `session_paused`. Results and the auto-repeat plan are kept.
+    pub fn pause(&self) -> Result<(), FlashspanError> {
+        self.set_paused(true)
+    }
+
+    /// Continue a paused session from where it froze.
+    pub fn resume(&self) -> Result<(), FlashspanError> {
+        self.set_paused(false)
+    }
+
     pub fn stop(&self) {
         self.cleanup_finished_worker();
 
@@ -222,8 +507,11 @@ impl SessionManager {
         let stop_flag = recover_lock(&self.stop, "stop").take();
         if let Some(flag) = stop_flag {
             flag.store(true, Ordering::SeqCst);
+            self.checkpoints.wake();
         }
+        *recover_lock(&self.paused, "paused") = None;
         recover_lock(&self.recent_results, "recent_results").clear();
+        recover_lock(&self.recent_plans, "recent_plans").clear();
 
         if let Some(handle) = recover_lock(&self.worker, "worker").take() {
             let _ = handle.join();
@@ -234,40 +522,45 @@ impl SessionManager {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn run_session_loop<E: SessionEmitter + Send + 'static>(
     emitter: E,
-    config: SessionConfig,
+    plan: SessionPlan,
     state: Arc<Mutex<SessionState>>,
     stop: Arc<AtomicBool>,
-    session_id: u64,
+    paused: Arc<AtomicBool>,
     recent_results: Arc<Mutex<VecDeque<SessionComplete>>>,
+    recent_plans: Arc<Mutex<VecDeque<SessionPlan>>>,
+    timing_reports: Arc<Mutex<VecDeque<TimingReport>>>,
     auto_repeat_plan: Arc<Mutex<Option<AutoRepeatPlan>>>,
+    checkpoints: Arc<CheckpointGate>,
 ) {
-    // Convert SessionConfig to SessionConfigEffective for plan generation.
-    let config_effective = SessionConfigEffective {
-        digits_per_number: config.digits_per_number,
-        number_duration_s: config.number_duration_ms as f64 / 1000.0,
-        delay_between_numbers_s: config.delay_between_numbers_ms as f64 / 1000.0,
-        total_numbers: config.total_numbers,
-        allow_negative_numbers: config.allow_negative_numbers,
-    };
-
-    // Generate deterministic session plan.
-    let plan = build_session_plan(session_id, config, config_effective, None);
+    {
+        let mut guard = recover_lock(&*recent_plans, "recent_plans");
+        guard.push_back(plan.clone());
+        while guard.len() > SessionManager::MAX_RECENT_RESULTS {
+            guard.pop_front();
+        }
+    }
 
     // Execute plan using the new plan-based executor.
-    run_session_plan(
+    let report = run_session_plan(
         &emitter,
         plan,
         state,
         stop,
+        paused,
         recent_results,
         auto_repeat_plan,
-        || {
-            // Play beep for each number flash (non-blocking)
-            let _ = crate::audio::play_kind("beep");
-        },
+        checkpoints,
+        || emitter.beep(),
     );
+
+    let mut guard = recover_lock(&*timing_reports, "timing_reports");
+    guard.push_back(report);
+    while guard.len() > SessionManager::MAX_RECENT_RESULTS {
+        guard.pop_front();
+    }
 }
 
 fn sleep_until_interruptible(deadline: Instant, stop: &AtomicBool) {
@@ -283,41 +576,164 @@ fn sleep_until_interruptible(deadline: Instant, stop: &AtomicBool) {
     }
 }
 
+/// Like `sleep_until_interruptible`, but the clock stops while `paused` is set: the deadline
+/// moves back by however long the pause lasted. Returns the total time spent paused.
+fn sleep_until_pausable<E: SessionEmitter>(
+    emitter: &E,
+    session_id: u64,
+    deadline: Instant,
+    stop: &AtomicBool,
+    paused: &AtomicBool,
+    state: &Mutex<SessionState>,
+) -> Duration {
+    let mut deadline = deadline;
+    let mut paused_for = Duration::ZERO;
+    while Instant::now() < deadline {
+        if stop.load(Ordering::SeqCst) {
+            break;
+        }
+        if paused.load(Ordering::SeqCst) {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let span = wait_while_paused(emitter, session_id, remaining, stop, paused, state);
+            deadline += span;
+            paused_for += span;
+            continue;
+        }
+        let slice = Instant::now() + Duration::from_millis(10);
+        sleep_until_interruptible(deadline.min(slice), stop);
+    }
+    paused_for
+}
+
+/// Block while `paused` is set, mirroring the pause in `state` and emitting
+/// `session_paused`/`session_resumed`. `remaining` is the time left in the interrupted step.
+/// Returns how long the session was paused so the caller can push its deadline back.
+fn wait_while_paused<E: SessionEmitter>(
+    emitter: &E,
+    session_id: u64,
+    remaining: Duration,
+    stop: &AtomicBool,
+    paused: &AtomicBool,
+    state: &Mutex<SessionState>,
+) -> Duration {
+    if !paused.load(Ordering::SeqCst) || stop.load(Ordering::SeqCst) {
+        return Duration::ZERO;
+    }
+
+    let paused_at = Instant::now();
+    let previous = {
+        let mut st = recover_lock(state, "state");
+        let previous = st.clone();
+        if let SessionState::ShowingNumbers { current, total } = previous {
+            *st = SessionState::Paused { current, total };
+        }
+        previous
+    };
+    emitter.session_paused(SessionPaused {
+        session_id,
+        remaining_ms: remaining.as_millis() as u64,
+        emitted_at_ms: now_epoch_ms(),
+    });
+
+    while paused.load(Ordering::SeqCst) && !stop.load(Ordering::SeqCst) {
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    let paused_for = paused_at.elapsed();
+    if stop.load(Ordering::SeqCst) {
+        return paused_for;
+    }
+
+    {
+        let mut st = recover_lock(state, "state");
+        if matches!(*st, SessionState::Paused { .. }) {
+            *st = previous;
+        }
+    }
+    emitter.session_resumed(SessionResumed {
+        session_id,
+        paused_ms: paused_for.as_millis() as u64,
+        emitted_at_ms: now_epoch_ms(),
+    });
+    paused_for
+}
+
 /// Execute a deterministic session plan produced by the core.
 /// This function converts the immutable plan steps into runtime events and handles scheduling.
-fn run_session_plan<E: SessionEmitter>(
+///
+/// Every step is scheduled against an absolute timeline that starts when the plan starts, so
+/// emit and beep latency never accumulates across steps. Pauses and checkpoint answers shift
+/// the rest of the timeline back by however long they lasted. The returned report compares each step's
+/// planned and measured emission offsets.
+#[allow(clippy::too_many_arguments)]
+pub fn run_session_plan<E: SessionEmitter>(
     emitter: &E,
     plan: SessionPlan,
     state: Arc<Mutex<SessionState>>,
     stop: Arc<AtomicBool>,
+    paused: Arc<AtomicBool>,
     recent_results: Arc<Mutex<VecDeque<SessionComplete>>>,
     auto_repeat_plan: Arc<Mutex<Option<AutoRepeatPlan>>>,
+    checkpoints: Arc<CheckpointGate>,
     beep: impl Fn(),
-) {
+) -> TimingReport {
     const FIRST_FLASH_GRACE: Duration = Duration::from_millis(100);
 
-    // Iterate through steps and execute them with relative delays
+    let started_at = Instant::now();
+    let started_at_ms = now_epoch_ms();
+    // Offset of the next step on the plan's timeline, and the time spent paused or answering
+    // checkpoints so far.
+    let mut planned_offset = Duration::ZERO;
+    let mut paused_for = Duration::ZERO;
+    let mut answering_for = Duration::ZERO;
+    let mut checkpoint_results: Vec<CheckpointResult> = Vec::new();
+    let mut timings: Vec<StepTiming> = Vec::with_capacity(plan.steps.len());
+    let mut completed = true;
+    // Last number read aloud, for reading rules that depend on it.
+    let mut previous_value: Option<i64> = None;
+
     for (step_idx, step) in plan.steps.iter().enumerate() {
+        paused_for += sleep_until_pausable(
+            emitter,
+            plan.session_id,
+            started_at + planned_offset + paused_for + answering_for,
+            &stop,
+            &paused,
+            &state,
+        );
+        // A pause requested as a step comes due holds that step back.
+        paused_for += wait_while_paused(
+            emitter,
+            plan.session_id,
+            Duration::ZERO,
+            &stop,
+            &paused,
+            &state,
+        );
+
         // Check stop signal before processing each step
         if stop.load(Ordering::SeqCst) {
-            emitter.clear_screen(ClearScreen {
-                session_id: plan.session_id,
-                index: None,
-                emitted_at_ms: now_epoch_ms(),
-            });
-            let mut st = recover_lock(&*state, "state");
-            *st = SessionState::Idle;
-            return;
+            completed = false;
+            break;
         }
 
-        match step {
+        let actual_offset = started_at
+            .elapsed()
+            .saturating_sub(paused_for + answering_for);
+        timings.push(StepTiming {
+            step_index: step_idx as u32,
+            planned_offset_us: planned_offset.as_micros() as u64,
+            actual_offset_us: actual_offset.as_micros() as u64,
+            jitter_us: actual_offset.as_micros() as i64 - planned_offset.as_micros() as i64,
+        });
+
+        let delay_ms = match step {
             SessionStep::CountdownTick {
                 value,
                 delay_ms_before_next,
             } => {
                 emitter.countdown_tick(value.clone());
-                let delay = Duration::from_millis(*delay_ms_before_next);
-                sleep_until_interruptible(Instant::now() + delay, &stop);
+                *delay_ms_before_next
             }
 
             SessionStep::ShowNumber {
@@ -326,35 +742,67 @@ fn run_session_plan<E: SessionEmitter>(
                 total,
                 value,
                 running_sum,
+                carries,
+                decimal_places,
                 delay_ms_before_next,
             } => {
-                // Determine if this is the first flash to apply grace period.
-                // First flash comes after initial clear + 3 countdown ticks (step indices 0-3)
-                let is_first_flash = step_idx == 4;
-                let grace = if is_first_flash {
-                    FIRST_FLASH_GRACE
-                } else {
-                    Duration::from_millis(0)
-                };
-
                 emitter.show_number(ShowNumber {
                     session_id: *session_id,
                     index: *index,
                     total: *total,
                     value: *value,
                     running_sum: *running_sum,
+                    carries: *carries,
+                    display: format_fixed(*value, *decimal_places),
+                    decimal_places: *decimal_places,
                     emitted_at_ms: now_epoch_ms(),
                 });
-                beep();
+                match plan.config_snapshot.dictation {
+                    Some(language) => emitter.speak(SpokenPhrase::new(
+                        language,
+                        *value,
+                        *decimal_places,
+                        previous_value,
+                        *delay_ms_before_next,
+                    )),
+                    None => beep(),
+                }
+                previous_value = Some(*value);
 
-                let delay = Duration::from_millis(*delay_ms_before_next) + grace;
-                sleep_until_interruptible(Instant::now() + delay, &stop);
+                let mut st = recover_lock(&*state, "state");
+                *st = SessionState::ShowingNumbers {
+                    current: *index,
+                    total: *total,
+                };
+                *delay_ms_before_next
+            }
+
+            SessionStep::ShowPair {
+                session_id,
+                index,
+                total,
+                operation,
+                left,
+                right,
+                delay_ms_before_next,
+            } => {
+                emitter.show_pair(ShowPair {
+                    session_id: *session_id,
+                    index: *index,
+                    total: *total,
+                    operation: *operation,
+                    left: *left,
+                    right: *right,
+                    emitted_at_ms: now_epoch_ms(),
+                });
+                beep();
 
                 let mut st = recover_lock(&*state, "state");
                 *st = SessionState::ShowingNumbers {
                     current: *index,
                     total: *total,
                 };
+                *delay_ms_before_next
             }
 
             SessionStep::ClearScreen {
@@ -367,22 +815,69 @@ fn run_session_plan<E: SessionEmitter>(
                     index: *index,
                     emitted_at_ms: now_epoch_ms(),
                 });
+                *delay_ms_before_next
+            }
 
-                let delay = Duration::from_millis(*delay_ms_before_next);
-                if delay > Duration::from_millis(0) {
-                    sleep_until_interruptible(Instant::now() + delay, &stop);
+            SessionStep::Checkpoint {
+                session_id,
+                index,
+                total,
+                expected_sum,
+                decimal_places,
+                delay_ms_before_next,
+            } => {
+                let asked_at = Instant::now();
+                checkpoints.open(*session_id, *index, *expected_sum, *decimal_places);
+                emitter.checkpoint(Checkpoint {
+                    session_id: *session_id,
+                    index: *index,
+                    total: *total,
+                    decimal_places: *decimal_places,
+                    emitted_at_ms: now_epoch_ms(),
+                });
+                loop {
+                    match checkpoints.wait(&stop, &paused) {
+                        GateWait::Answered(result) => {
+                            checkpoint_results.push(result);
+                            break;
+                        }
+                        GateWait::Paused => {
+                            wait_while_paused(
+                                emitter,
+                                *session_id,
+                                Duration::ZERO,
+                                &stop,
+                                &paused,
+                                &state,
+                            );
+                        }
+                        GateWait::Stopped => break,
+                    }
                 }
+                answering_for += asked_at.elapsed();
+                *delay_ms_before_next
             }
 
             SessionStep::Complete {
                 session_id,
                 numbers,
                 sum,
+                decimal_places,
+                pairs,
             } => {
                 let result = SessionComplete {
                     session_id: *session_id,
                     numbers: numbers.clone(),
                     sum: *sum,
+                    decimal_places: *decimal_places,
+                    pairs: pairs.clone(),
+                    started_at_ms,
+                    completed_at_ms: now_epoch_ms(),
+                    completed_at: Some(Instant::now()),
+                    config: plan.config_snapshot.clone(),
+                    profile_id: emitter.profile_id(),
+                    seed: plan.seed,
+                    checkpoints: std::mem::take(&mut checkpoint_results),
                 };
 
                 {
@@ -413,35 +908,88 @@ fn run_session_plan<E: SessionEmitter>(
                         );
                     }
                 }
+                0
             }
-        }
+        };
+
+        // Determine if this is the first flash to apply grace period.
+        // First flash comes after initial clear + 3 countdown ticks (step indices 0-3)
+        let grace = if step_idx == 4 {
+            FIRST_FLASH_GRACE
+        } else {
+            Duration::ZERO
+        };
+        planned_offset += Duration::from_millis(delay_ms) + grace;
 
         // Check for stop signal after each step
         if stop.load(Ordering::SeqCst) {
-            emitter.clear_screen(ClearScreen {
-                session_id: plan.session_id,
-                index: None,
-                emitted_at_ms: now_epoch_ms(),
-            });
-            let mut st = recover_lock(&*state, "state");
-            *st = SessionState::Idle;
-            return;
+            completed = false;
+            break;
         }
     }
 
+    if !completed {
+        emitter.clear_screen(ClearScreen {
+            session_id: plan.session_id,
+            index: None,
+            emitted_at_ms: now_epoch_ms(),
+        });
+    }
     let mut st = recover_lock(&*state, "state");
-    *st = SessionState::Complete;
+    *st = if completed {
+        SessionState::Complete
+    } else {
+        SessionState::Idle
+    };
+
+    timing_report(plan.session_id, timings, paused_for, completed)
+}
+
+/// Summarise per-step timings into a report.
+fn timing_report(
+    session_id: u64,
+    steps: Vec<StepTiming>,
+    paused_for: Duration,
+    completed: bool,
+) -> TimingReport {
+    let max_abs_jitter_us = steps
+        .iter()
+        .map(|s| s.jitter_us.unsigned_abs())
+        .max()
+        .unwrap_or(0);
+    let mean_abs_jitter_us = if steps.is_empty() {
+        0
+    } else {
+        steps
+            .iter()
+            .map(|s| s.jitter_us.unsigned_abs())
+            .sum::<u64>()
+            / steps.len() as u64
+    };
+
+    TimingReport {
+        session_id,
+        steps,
+        max_abs_jitter_us,
+        mean_abs_jitter_us,
+        paused_ms: paused_for.as_millis() as u64,
+        completed,
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::dictation::DictationLanguage;
     use crate::core::generate::{
         random_fixed_digits_no_leading_zero, random_fixed_digits_no_leading_zero_capped,
         random_number_with_constraints,
     };
-    use crate::core::types::{SessionConfig, SessionConfigInput};
-    use crate::core::validate::normalize_session_config;
+    use crate::core::types::{
+        AdaptivePolicyInput, AdaptiveRun, AdaptiveState, SeedStrategy, SessionConfig,
+        SessionConfigInput,
+    };
+    use crate::core::validate::{normalize_adaptive_policy, normalize_session_config};
     use rand::rng;
     use std::sync::Arc;
 
@@ -516,6 +1064,7 @@ mod tests {
             delay_between_numbers_s: f64::NAN,
             total_numbers: -5,
             allow_negative_numbers: true,
+            ..Default::default()
         };
 
         let (cfg, eff) = normalize_session_config(input);
@@ -600,6 +1149,7 @@ mod tests {
             delay_between_numbers_s: 0.0,
             total_numbers: 1,
             allow_negative_numbers: false,
+            ..Default::default()
         });
 
         let initial_gen = manager.auto_repeat_generation();
@@ -609,6 +1159,9 @@ mod tests {
             delay_ms: 1500,
             config: config.clone(),
             awaiting_validation_session_id: Some(42),
+            adaptive: None,
+            seed_strategy: SeedStrategy::Incrementing,
+            seed: 41,
         };
 
         manager.configure_auto_repeat(Some(plan));
@@ -617,8 +1170,9 @@ mod tests {
         // mark_validated should return scheduling info for session_id 42
         let res = manager.mark_validated_and_schedule_info(42).unwrap();
         assert!(res.is_some());
-        let (delay_ms, remaining_after, cfg, generation) = res.unwrap();
+        let (delay_ms, remaining_after, cfg, seed, generation) = res.unwrap();
         assert_eq!(delay_ms, 1500);
+        assert_eq!(seed, 42, "the next round uses the incremented seed");
         assert_eq!(remaining_after, 2);
         assert_eq!(cfg.digits_per_number, config.digits_per_number);
         assert_eq!(generation, manager.auto_repeat_generation());
@@ -637,6 +1191,7 @@ mod tests {
             delay_between_numbers_s: 0.0,
             total_numbers: 1,
             allow_negative_numbers: false,
+            ..Default::default()
         });
 
         // plan without awaiting_validation_session_id -> should return None
@@ -645,6 +1200,9 @@ mod tests {
             delay_ms: 1000,
             config: config.clone(),
             awaiting_validation_session_id: None,
+            adaptive: None,
+            seed_strategy: SeedStrategy::Fixed,
+            seed: 0,
         };
         manager.configure_auto_repeat(Some(plan));
         let res = manager.mark_validated_and_schedule_info(123).unwrap();
@@ -656,12 +1214,54 @@ mod tests {
             delay_ms: 1000,
             config: config.clone(),
             awaiting_validation_session_id: Some(123),
+            adaptive: None,
+            seed_strategy: SeedStrategy::Fixed,
+            seed: 0,
         };
         manager.configure_auto_repeat(Some(plan2));
         let res2 = manager.mark_validated_and_schedule_info(123).unwrap();
         assert!(res2.is_none());
     }
 
+    #[test]
+    fn adaptive_outcome_adjusts_next_round_once() {
+        let manager = SessionManager::default();
+        let (config, _eff) = normalize_session_config(SessionConfigInput {
+            digits_per_number: 2,
+            number_duration_s: 1.0,
+            delay_between_numbers_s: 0.0,
+            total_numbers: 5,
+            ..Default::default()
+        });
+        manager.configure_auto_repeat(Some(AutoRepeatPlan {
+            remaining: 3,
+            delay_ms: 1000,
+            config,
+            awaiting_validation_session_id: Some(7),
+            adaptive: Some(AdaptiveRun {
+                policy: normalize_adaptive_policy(&AdaptivePolicyInput {
+                    ease_after_wrong: 1,
+                    ..Default::default()
+                }),
+                state: AdaptiveState::default(),
+            }),
+            seed_strategy: SeedStrategy::Fixed,
+            seed: 0,
+        }));
+
+        assert!(manager.record_adaptive_outcome(8, false).is_none());
+        let adj = manager.record_adaptive_outcome(7, false).unwrap();
+        assert_eq!(adj.next.number_duration_ms, 1100);
+
+        let (_, _, cfg, _, _) = manager
+            .mark_validated_and_schedule_info(7)
+            .unwrap()
+            .unwrap();
+        assert_eq!(cfg.number_duration_ms, 1100);
+        // The round is no longer awaiting validation.
+        assert!(manager.record_adaptive_outcome(7, false).is_none());
+    }
+
     #[test]
     fn result_for_and_stop_clears_state_and_results() {
         let manager = SessionManager::default();
@@ -671,6 +1271,15 @@ mod tests {
             session_id: 99,
             numbers: vec![1, 2, 3],
             sum: 6,
+            decimal_places: 0,
+            pairs: vec![],
+            started_at_ms: 0,
+            completed_at_ms: 0,
+            completed_at: None,
+            config: Default::default(),
+            profile_id: None,
+            seed: 0,
+            checkpoints: Vec::new(),
         };
 
         {
@@ -688,6 +1297,7 @@ mod tests {
             delay_between_numbers_s: 0.0,
             total_numbers: 1,
             allow_negative_numbers: false,
+            ..Default::default()
         });
 
         manager.configure_auto_repeat(Some(AutoRepeatPlan {
@@ -695,6 +1305,9 @@ mod tests {
             delay_ms: 10,
             config: config.clone(),
             awaiting_validation_session_id: None,
+            adaptive: None,
+            seed_strategy: SeedStrategy::Fixed,
+            seed: 0,
         }));
 
         // set a stop flag and spawn a short-lived thread as worker
@@ -770,6 +1383,7 @@ mod tests {
             delay_between_numbers_ms: 0,
             total_numbers: 0,
             allow_negative_numbers: false,
+            ..Default::default()
         };
         assert!(validate_config(&bad).is_err());
 
@@ -779,6 +1393,7 @@ mod tests {
             delay_between_numbers_ms: 0,
             total_numbers: 1,
             allow_negative_numbers: false,
+            ..Default::default()
         };
         assert!(validate_config(&too_many_digits).is_err());
 
@@ -788,6 +1403,7 @@ mod tests {
             delay_between_numbers_ms: 0,
             total_numbers: 20_000,
             allow_negative_numbers: false,
+            ..Default::default()
         };
         assert!(validate_config(&too_many_total).is_err());
 
@@ -797,6 +1413,7 @@ mod tests {
             delay_between_numbers_ms: 0,
             total_numbers: 1,
             allow_negative_numbers: false,
+            ..Default::default()
         };
         assert!(validate_config(&too_long).is_err());
     }
@@ -809,6 +1426,7 @@ mod tests {
             delay_between_numbers_s: 120.0,
             total_numbers: 100_000,
             allow_negative_numbers: false,
+            ..Default::default()
         };
 
         let (cfg, eff) = normalize_session_config(input);
@@ -867,6 +1485,7 @@ mod tests {
             delay_between_numbers_s: 0.0,
             total_numbers: 1,
             allow_negative_numbers: false,
+            ..Default::default()
         });
 
         let base_gen = manager.auto_repeat_generation();
@@ -877,6 +1496,9 @@ mod tests {
                 delay_ms: 100,
                 config: config.clone(),
                 awaiting_validation_session_id: None,
+                adaptive: None,
+                seed_strategy: SeedStrategy::Fixed,
+                seed: 0,
             };
             manager.configure_auto_repeat(Some(plan));
             assert!(
@@ -902,6 +1524,7 @@ mod tests {
             delay_between_numbers_s: 0.0,
             total_numbers: 1,
             allow_negative_numbers: false,
+            ..Default::default()
         });
 
         let plan = AutoRepeatPlan {
@@ -909,6 +1532,9 @@ mod tests {
             delay_ms: 500,
             config,
             awaiting_validation_session_id: Some(42),
+            adaptive: None,
+            seed_strategy: SeedStrategy::Fixed,
+            seed: 0,
         };
         manager.configure_auto_repeat(Some(plan));
 
@@ -926,8 +1552,12 @@ mod tests {
                 delay_between_numbers_ms: 0,
                 total_numbers: 1,
                 allow_negative_numbers: false,
+                ..Default::default()
             },
             awaiting_validation_session_id: None,
+            adaptive: None,
+            seed_strategy: SeedStrategy::Fixed,
+            seed: 0,
         };
         manager.configure_auto_repeat(Some(plan2));
         let res2 = manager.mark_validated_and_schedule_info(42).unwrap();
@@ -962,9 +1592,36 @@ mod tests {
                 .unwrap()
                 .push(format!("show_number({})", payload.value));
         }
+        fn show_pair(&self, payload: ShowPair) {
+            self.calls
+                .lock()
+                .unwrap()
+                .push(format!("show_pair({},{})", payload.left, payload.right));
+        }
         fn session_complete(&self, _payload: SessionComplete) {
             self.calls.lock().unwrap().push("session_complete".into());
         }
+        fn session_paused(&self, _payload: SessionPaused) {
+            self.calls.lock().unwrap().push("session_paused".into());
+        }
+        fn session_resumed(&self, _payload: SessionResumed) {
+            self.calls.lock().unwrap().push("session_resumed".into());
+        }
+        fn session_starting(&self) {
+            self.calls.lock().unwrap().push("session_starting".into());
+        }
+        fn checkpoint(&self, payload: Checkpoint) {
+            self.calls
+                .lock()
+                .unwrap()
+                .push(format!("checkpoint({})", payload.index));
+        }
+        fn speak(&self, phrase: SpokenPhrase) {
+            self.calls
+                .lock()
+                .unwrap()
+                .push(format!("speak({})", phrase.clips.join(" ")));
+        }
     }
 
     fn make_sample_plan(session_id: u64) -> SessionPlan {
@@ -976,6 +1633,7 @@ mod tests {
                 delay_between_numbers_s: 0.0,
                 total_numbers: 2,
                 allow_negative_numbers: false,
+                ..Default::default()
             },
             steps: vec![
                 SessionStep::ClearScreen {
@@ -1001,6 +1659,8 @@ mod tests {
                     total: 2,
                     value: 5,
                     running_sum: 5,
+                    carries: 0,
+                    decimal_places: 0,
                     delay_ms_before_next: 0,
                 },
                 SessionStep::ClearScreen {
@@ -1014,6 +1674,8 @@ mod tests {
                     total: 2,
                     value: 3,
                     running_sum: 8,
+                    carries: 0,
+                    decimal_places: 0,
                     delay_ms_before_next: 0,
                 },
                 SessionStep::ClearScreen {
@@ -1030,11 +1692,15 @@ mod tests {
                     session_id,
                     numbers: vec![5, 3],
                     sum: 8,
+                    decimal_places: 0,
+                    pairs: vec![],
                 },
             ],
             total_duration_ms: 0,
             numbers_generated: vec![5, 3],
             expected_sum: 8,
+            pairs_generated: vec![],
+            seed: 0,
         }
     }
 
@@ -1059,8 +1725,10 @@ mod tests {
             plan,
             state,
             stop,
+            Arc::new(AtomicBool::new(false)),
             recent_results,
             auto_repeat_plan,
+            Arc::new(CheckpointGate::default()),
             beep,
         );
 
@@ -1084,6 +1752,33 @@ mod tests {
         assert_eq!(beep_count.load(std::sync::atomic::Ordering::SeqCst), 2);
     }
 
+    #[test]
+    fn dictation_speaks_numbers_instead_of_beeping() {
+        let emitter = TestEmitter::new();
+        let mut plan = make_sample_plan(5);
+        plan.config_snapshot.dictation = Some(DictationLanguage::English);
+
+        let beeped = AtomicBool::new(false);
+        run_session_plan(
+            &emitter,
+            plan,
+            Arc::new(Mutex::new(SessionState::Idle)),
+            Arc::new(AtomicBool::new(false)),
+            Arc::new(AtomicBool::new(false)),
+            Arc::new(Mutex::new(VecDeque::new())),
+            Arc::new(Mutex::new(None)),
+            Arc::new(CheckpointGate::default()),
+            || beeped.store(true, Ordering::SeqCst),
+        );
+
+        let calls = emitter.calls.lock().unwrap();
+        assert!(!beeped.load(Ordering::SeqCst));
+        let spoken: Vec<&String> = calls.iter().filter(|c| c.starts_with("speak")).collect();
+        assert_eq!(spoken, ["speak(five)", "speak(three)"]);
+        let show = calls.iter().position(|c| c == "show_number(5)").unwrap();
+        assert_eq!(calls[show + 1], "speak(five)");
+    }
+
     #[test]
     fn run_session_plan_stop_before_start() {
         let emitter = TestEmitter::new();
@@ -1098,8 +1793,10 @@ mod tests {
             plan,
             Arc::clone(&state),
             stop,
+            Arc::new(AtomicBool::new(false)),
             recent_results,
             auto_repeat_plan,
+            Arc::new(CheckpointGate::default()),
             || {},
         );
 
@@ -1123,8 +1820,10 @@ mod tests {
             plan,
             Arc::clone(&state),
             stop,
+            Arc::new(AtomicBool::new(false)),
             Arc::clone(&recent_results),
             auto_repeat_plan,
+            Arc::new(CheckpointGate::default()),
             || {},
         );
 
@@ -1155,8 +1854,12 @@ mod tests {
                 delay_between_numbers_ms: 0,
                 total_numbers: 1,
                 allow_negative_numbers: false,
+                ..Default::default()
             },
             awaiting_validation_session_id: None,
+            adaptive: None,
+            seed_strategy: SeedStrategy::Fixed,
+            seed: 0,
         })));
 
         let plan = make_sample_plan(42);
@@ -1165,8 +1868,10 @@ mod tests {
             plan,
             state,
             stop,
+            Arc::new(AtomicBool::new(false)),
             recent_results,
             Arc::clone(&auto_repeat_plan),
+            Arc::new(CheckpointGate::default()),
             || {},
         );
 
@@ -1191,8 +1896,12 @@ mod tests {
                 delay_between_numbers_ms: 0,
                 total_numbers: 1,
                 allow_negative_numbers: false,
+                ..Default::default()
             },
             awaiting_validation_session_id: None,
+            adaptive: None,
+            seed_strategy: SeedStrategy::Fixed,
+            seed: 0,
         })));
 
         let plan = make_sample_plan(42);
@@ -1201,8 +1910,10 @@ mod tests {
             plan,
             state,
             stop,
+            Arc::new(AtomicBool::new(false)),
             recent_results,
             Arc::clone(&auto_repeat_plan),
+            Arc::new(CheckpointGate::default()),
             || {},
         );
 
@@ -1267,7 +1978,7 @@ mod tests {
     fn result_for_not_found() {
         let manager = SessionManager::default();
         let err = manager.result_for(999).unwrap_err();
-        assert_eq!(err, "session result not found");
+        assert_eq!(err, FlashspanError::ResultNotFound { session_id: 999 });
     }
 
     #[test]
@@ -1293,6 +2004,7 @@ mod tests {
                 delay_between_numbers_s: 0.0,
                 total_numbers: 2,
                 allow_negative_numbers: true,
+                ..Default::default()
             },
             steps: vec![
                 SessionStep::ClearScreen {
@@ -1318,6 +2030,8 @@ mod tests {
                     total: 2,
                     value: -5,
                     running_sum: -5,
+                    carries: 0,
+                    decimal_places: 0,
                     delay_ms_before_next: 0,
                 },
                 SessionStep::ClearScreen {
@@ -1331,6 +2045,8 @@ mod tests {
                     total: 2,
                     value: 3,
                     running_sum: -2,
+                    carries: 0,
+                    decimal_places: 0,
                     delay_ms_before_next: 0,
                 },
                 SessionStep::ClearScreen {
@@ -1347,11 +2063,15 @@ mod tests {
                     session_id: 77,
                     numbers: vec![-5, 3],
                     sum: -2,
+                    decimal_places: 0,
+                    pairs: vec![],
                 },
             ],
             total_duration_ms: 0,
             numbers_generated: vec![-5, 3],
             expected_sum: -2,
+            pairs_generated: vec![],
+            seed: 0,
         };
 
         run_session_plan(
@@ -1359,8 +2079,10 @@ mod tests {
             plan,
             Arc::clone(&state),
             stop,
+            Arc::new(AtomicBool::new(false)),
             Arc::clone(&recent_results),
             auto_repeat_plan,
+            Arc::new(CheckpointGate::default()),
             beep,
         );
 
@@ -1408,6 +2130,7 @@ mod tests {
                 delay_between_numbers_s: 0.0,
                 total_numbers: 0,
                 allow_negative_numbers: false,
+                ..Default::default()
             },
             steps: vec![
                 SessionStep::ClearScreen {
@@ -1436,11 +2159,15 @@ mod tests {
                     session_id: 100,
                     numbers: vec![],
                     sum: 0,
+                    decimal_places: 0,
+                    pairs: vec![],
                 },
             ],
             total_duration_ms: 3100,
             numbers_generated: vec![],
             expected_sum: 0,
+            pairs_generated: vec![],
+            seed: 0,
         };
 
         run_session_plan(
@@ -1448,8 +2175,10 @@ mod tests {
             plan,
             Arc::clone(&state),
             stop,
+            Arc::new(AtomicBool::new(false)),
             Arc::clone(&recent_results),
             auto_repeat_plan,
+            Arc::new(CheckpointGate::default()),
             || {},
         );
 
@@ -1492,6 +2221,7 @@ mod tests {
                 delay_between_numbers_s: 0.0,
                 total_numbers: 1,
                 allow_negative_numbers: false,
+                ..Default::default()
             },
             steps: vec![
                 SessionStep::ClearScreen {
@@ -1517,6 +2247,8 @@ mod tests {
                     total: 1,
                     value: 42,
                     running_sum: 42,
+                    carries: 0,
+                    decimal_places: 0,
                     delay_ms_before_next: 0,
                 },
                 SessionStep::ClearScreen {
@@ -1533,11 +2265,15 @@ mod tests {
                     session_id: 50,
                     numbers: vec![42],
                     sum: 42,
+                    decimal_places: 0,
+                    pairs: vec![],
                 },
             ],
             total_duration_ms: 0,
             numbers_generated: vec![42],
             expected_sum: 42,
+            pairs_generated: vec![],
+            seed: 0,
         };
 
         run_session_plan(
@@ -1545,8 +2281,10 @@ mod tests {
             plan,
             Arc::clone(&state),
             stop,
+            Arc::new(AtomicBool::new(false)),
             Arc::clone(&recent_results),
             auto_repeat_plan,
+            Arc::new(CheckpointGate::default()),
             || {},
         );
 
@@ -1596,8 +2334,10 @@ mod tests {
             plan,
             Arc::clone(&state),
             Arc::clone(&stop),
+            Arc::new(AtomicBool::new(false)),
             recent_results,
             auto_repeat_plan,
+            Arc::new(CheckpointGate::default()),
             beep,
         );
 
@@ -1633,6 +2373,7 @@ mod tests {
             delay_between_numbers_s: 0.0,
             total_numbers: 2,
             allow_negative_numbers: false,
+            ..Default::default()
         });
 
         // Step 1: Configure auto-repeat with remaining=1 via SessionManager
@@ -1641,6 +2382,9 @@ mod tests {
             delay_ms: 500,
             config: config.clone(),
             awaiting_validation_session_id: None,
+            adaptive: None,
+            seed_strategy: SeedStrategy::Fixed,
+            seed: 0,
         }));
 
         // Step 2: Run session plan using the SAME auto_repeat_plan Arc
@@ -1650,8 +2394,10 @@ mod tests {
             plan,
             Arc::clone(&state),
             stop,
+            Arc::new(AtomicBool::new(false)),
             recent_results,
             Arc::clone(&manager.auto_repeat_plan),
+            Arc::new(CheckpointGate::default()),
             || {},
         );
 
@@ -1666,7 +2412,7 @@ mod tests {
         // Step 3: Call mark_validated_and_schedule_info to consume it
         let result = manager.mark_validated_and_schedule_info(42).unwrap();
         assert!(result.is_some());
-        let (_delay_ms, remaining, _cfg, _gen) = result.unwrap();
+        let (_delay_ms, remaining, _cfg, _seed, _gen) = result.unwrap();
         assert_eq!(remaining, 0, "remaining should be 0 after consuming");
 
         // Subsequent call should return None (awaiting already cleared)
@@ -1683,10 +2429,11 @@ mod tests {
             delay_between_numbers_s: 0.0,
             total_numbers: 1,
             allow_negative_numbers: false,
+            ..Default::default()
         });
 
         let emitter = TestEmitter::new();
-        let result = manager.start_with_emitter(emitter, config);
+        let result = manager.start_with_emitter(emitter, config, None);
         assert!(result.is_ok());
         let session_id = result.unwrap();
         assert_eq!(session_id, 1, "first session should have id 1");
@@ -1711,6 +2458,349 @@ mod tests {
         }
     }
 
+    #[test]
+    fn start_plan_with_emitter_replays_plan_under_new_id() {
+        let manager = SessionManager::default();
+
+        let mut tampered = make_sample_plan(99);
+        tampered.expected_sum = 9;
+        assert!(matches!(
+            manager.start_plan_with_emitter(TestEmitter::new(), tampered),
+            Err(FlashspanError::InvalidPlan { .. })
+        ));
+
+        let emitter = TestEmitter::new();
+        let calls = Arc::clone(&emitter.calls);
+        let session_id = manager
+            .start_plan_with_emitter(emitter, make_sample_plan(99))
+            .unwrap();
+        assert_eq!(session_id, 1);
+        if let Some(handle) = manager.worker.lock().unwrap().take() {
+            handle.join().unwrap();
+        }
+
+        assert!(
+            calls
+                .lock()
+                .unwrap()
+                .contains(&"show_number(3)".to_string())
+        );
+        let result = manager.result_for(session_id).unwrap();
+        // Answer times are measured from this instant, not from `completed_at_ms`.
+        assert!(result.completed_at.is_some_and(|at| at <= Instant::now()));
+        assert_eq!((result.numbers, result.sum), (vec![5, 3], 8));
+        let plan = manager.plan_for(session_id).unwrap();
+        assert_eq!(plan.session_id, session_id);
+        assert!(matches!(
+            plan.steps.last(),
+            Some(SessionStep::Complete { session_id: 1, .. })
+        ));
+        assert!(manager.plan_for(99).is_err());
+    }
+
+    #[test]
+    fn run_session_plan_pause_keeps_remaining_step_time() {
+        let emitter = TestEmitter::new();
+        let state = Arc::new(Mutex::new(SessionState::ShowingNumbers {
+            current: 0,
+            total: 2,
+        }));
+        let stop = Arc::new(AtomicBool::new(false));
+        let paused = Arc::new(AtomicBool::new(false));
+
+        let mut plan = make_sample_plan(7);
+        if let Some(SessionStep::ShowNumber {
+            delay_ms_before_next,
+            ..
+        }) = plan.steps.get_mut(4)
+        {
+            *delay_ms_before_next = 300;
+        }
+
+        let pauser = {
+            let paused = Arc::clone(&paused);
+            let state = Arc::clone(&state);
+            thread::spawn(move || {
+                thread::sleep(Duration::from_millis(100));
+                paused.store(true, Ordering::SeqCst);
+                thread::sleep(Duration::from_millis(150));
+                let was_paused = matches!(
+                    *state.lock().unwrap(),
+                    SessionState::Paused { total: 2, .. }
+                );
+                thread::sleep(Duration::from_millis(150));
+                paused.store(false, Ordering::SeqCst);
+                was_paused
+            })
+        };
+
+        let started = Instant::now();
+        run_session_plan(
+            &emitter,
+            plan,
+            Arc::clone(&state),
+            stop,
+            paused,
+            Arc::new(Mutex::new(VecDeque::new())),
+            Arc::new(Mutex::new(None)),
+            Arc::new(CheckpointGate::default()),
+            || {},
+        );
+        let elapsed = started.elapsed();
+
+        assert!(
+            pauser.join().unwrap(),
+            "state should be Paused while paused"
+        );
+        // 300ms flash + 100ms first-flash grace + 300ms paused.
+        assert!(
+            elapsed >= Duration::from_millis(650),
+            "pause should not eat into the step: {:?}",
+            elapsed
+        );
+        let calls = emitter.calls.lock().unwrap();
+        assert_eq!(
+            calls[4..8],
+            [
+                "show_number(5)",
+                "session_paused",
+                "session_resumed",
+                "clear_screen"
+            ]
+        );
+        assert!(matches!(*state.lock().unwrap(), SessionState::Complete));
+    }
+
+    fn make_flash_plan(session_id: u64, flashes: u32, flash_ms: u64) -> SessionPlan {
+        let mut steps: Vec<SessionStep> = (1..=flashes)
+            .map(|index| SessionStep::ShowNumber {
+                session_id,
+                index,
+                total: flashes,
+                value: index as i64,
+                running_sum: 0,
+                carries: 0,
+                decimal_places: 0,
+                delay_ms_before_next: flash_ms,
+            })
+            .collect();
+        steps.push(SessionStep::Complete {
+            session_id,
+            numbers: vec![],
+            sum: 0,
+            decimal_places: 0,
+            pairs: vec![],
+        });
+        SessionPlan {
+            session_id,
+            config_snapshot: SessionConfigEffective::default(),
+            steps,
+            total_duration_ms: flashes as u64 * flash_ms,
+            numbers_generated: vec![],
+            expected_sum: 0,
+            pairs_generated: vec![],
+            seed: 0,
+        }
+    }
+
+    #[test]
+    fn checkpoints_hold_the_session_until_answered() {
+        let emitter = TestEmitter::new();
+        let calls = Arc::clone(&emitter.calls);
+        let mut plan = make_flash_plan(5, 3, 10);
+        plan.steps.insert(
+            2,
+            SessionStep::Checkpoint {
+                session_id: 5,
+                index: 2,
+                total: 3,
+                expected_sum: 3,
+                decimal_places: 0,
+                delay_ms_before_next: 10,
+            },
+        );
+        let gate = Arc::new(CheckpointGate::default());
+        let recent_results = Arc::new(Mutex::new(VecDeque::new()));
+        let worker = {
+            let gate = Arc::clone(&gate);
+            let recent_results = Arc::clone(&recent_results);
+            thread::spawn(move || {
+                run_session_plan(
+                    &emitter,
+                    plan,
+                    Arc::new(Mutex::new(SessionState::Idle)),
+                    Arc::new(AtomicBool::new(false)),
+                    Arc::new(AtomicBool::new(false)),
+                    recent_results,
+                    Arc::new(Mutex::new(None)),
+                    gate,
+                    || {},
+                )
+            })
+        };
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !calls.lock().unwrap().contains(&"checkpoint(2)".to_string()) {
+            assert!(Instant::now() < deadline, "no checkpoint");
+            thread::sleep(Duration::from_millis(5));
+        }
+        assert!(matches!(
+            gate.answer(6, 3),
+            Err(FlashspanError::NoCheckpointPending { session_id: 6 })
+        ));
+        thread::sleep(Duration::from_millis(150));
+        assert_eq!(
+            calls.lock().unwrap().last().map(String::as_str),
+            Some("checkpoint(2)"),
+            "the session should wait for the answer"
+        );
+
+        let answer = gate.answer(5, 4).unwrap();
+        assert_eq!(answer.index, 2);
+        assert_eq!(answer.validation.delta, 1);
+        assert!(answer.answer_ms >= 150);
+        assert!(gate.answer(5, 3).is_err(), "one answer per checkpoint");
+
+        let report = worker.join().unwrap();
+        assert!(report.completed);
+        // The time spent answering moves the rest of the timeline back instead of
+        // showing up as lateness.
+        assert!(
+            report.max_abs_jitter_us < 100_000,
+            "jitter {}us",
+            report.max_abs_jitter_us
+        );
+        let result = recent_results.lock().unwrap().pop_back().unwrap();
+        assert_eq!(result.checkpoints.len(), 1);
+        assert!(!result.checkpoints[0].validation.correct);
+    }
+
+    #[test]
+    fn pause_and_stop_reach_a_session_waiting_at_a_checkpoint() {
+        let emitter = TestEmitter::new();
+        let calls = Arc::clone(&emitter.calls);
+        let mut plan = make_flash_plan(5, 3, 10);
+        plan.steps.insert(
+            2,
+            SessionStep::Checkpoint {
+                session_id: 5,
+                index: 2,
+                total: 3,
+                expected_sum: 3,
+                decimal_places: 0,
+                delay_ms_before_next: 10,
+            },
+        );
+        let gate = Arc::new(CheckpointGate::default());
+        let (stop, paused) = (
+            Arc::new(AtomicBool::new(false)),
+            Arc::new(AtomicBool::new(false)),
+        );
+        let worker = {
+            let (gate, stop, paused) = (Arc::clone(&gate), Arc::clone(&stop), Arc::clone(&paused));
+            thread::spawn(move || {
+                run_session_plan(
+                    &emitter,
+                    plan,
+                    Arc::new(Mutex::new(SessionState::Idle)),
+                    stop,
+                    paused,
+                    Arc::new(Mutex::new(VecDeque::new())),
+                    Arc::new(Mutex::new(None)),
+                    gate,
+                    || {},
+                )
+            })
+        };
+        let wait_for = |call: &str| {
+            let deadline = Instant::now() + Duration::from_secs(5);
+            while !calls.lock().unwrap().iter().any(|c| c == call) {
+                assert!(Instant::now() < deadline, "no {}", call);
+                thread::sleep(Duration::from_millis(5));
+            }
+        };
+
+        wait_for("checkpoint(2)");
+        paused.store(true, Ordering::SeqCst);
+        gate.wake();
+        wait_for("session_paused");
+
+        stop.store(true, Ordering::SeqCst);
+        gate.wake();
+        let report = worker.join().unwrap();
+        assert!(!report.completed);
+        assert!(
+            gate.answer(5, 3).is_err(),
+            "a stopped session closes its checkpoint"
+        );
+    }
+
+    #[test]
+    fn run_session_plan_does_not_accumulate_emit_latency() {
+        let emitter = TestEmitter::new();
+        let plan = make_flash_plan(3, 40, 10);
+        let started = Instant::now();
+        let report = run_session_plan(
+            &emitter,
+            plan,
+            Arc::new(Mutex::new(SessionState::Idle)),
+            Arc::new(AtomicBool::new(false)),
+            Arc::new(AtomicBool::new(false)),
+            Arc::new(Mutex::new(VecDeque::new())),
+            Arc::new(Mutex::new(None)),
+            Arc::new(CheckpointGate::default()),
+            // A slow beep: relative delays would add 40 × 4ms on top of the plan.
+            || thread::sleep(Duration::from_millis(4)),
+        );
+        let elapsed = started.elapsed();
+
+        // 400ms of flashes plus the 100ms first-flash grace at step index 4.
+        assert!(
+            elapsed < Duration::from_millis(500 + 60),
+            "latency accumulated: {:?}",
+            elapsed
+        );
+        assert!(report.completed);
+        assert_eq!(report.steps.len(), 41);
+        assert_eq!(report.steps[40].planned_offset_us, 500_000);
+        assert!(
+            report
+                .steps
+                .windows(2)
+                .all(|w| w[0].planned_offset_us < w[1].planned_offset_us),
+            "planned offsets should increase"
+        );
+    }
+
+    #[test]
+    fn timing_report_marks_stopped_sessions() {
+        let emitter = TestEmitter::new();
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop_clone = Arc::clone(&stop);
+        let report = run_session_plan(
+            &emitter,
+            make_flash_plan(4, 5, 0),
+            Arc::new(Mutex::new(SessionState::Idle)),
+            stop,
+            Arc::new(AtomicBool::new(false)),
+            Arc::new(Mutex::new(VecDeque::new())),
+            Arc::new(Mutex::new(None)),
+            Arc::new(CheckpointGate::default()),
+            move || stop_clone.store(true, Ordering::SeqCst),
+        );
+
+        assert!(!report.completed);
+        assert_eq!(report.steps.len(), 1);
+        assert_eq!(report.session_id, 4);
+    }
+
+    #[test]
+    fn pause_and_resume_require_a_running_session() {
+        let manager = SessionManager::default();
+        assert!(manager.pause().is_err());
+        assert!(manager.resume().is_err());
+    }
+
     #[test]
     fn start_with_emitter_rejects_concurrent() {
         let manager = SessionManager::default();
@@ -1720,18 +2810,25 @@ mod tests {
             delay_between_numbers_s: 0.0,
             total_numbers: 1,
             allow_negative_numbers: false,
+            ..Default::default()
         });
 
         // First start succeeds
-        let result1 = manager.start_with_emitter(TestEmitter::new(), config.clone());
+        let emitter1 = TestEmitter::new();
+        let calls1 = Arc::clone(&emitter1.calls);
+        let result1 = manager.start_with_emitter(emitter1, config.clone(), None);
         assert!(result1.is_ok());
+        assert_eq!(calls1.lock().unwrap()[0], "session_starting");
 
-        // Second start should fail while first is running
-        let result2 = manager.start_with_emitter(TestEmitter::new(), config.clone());
+        // Second start should fail while first is running, without cutting its sounds
+        let emitter2 = TestEmitter::new();
+        let calls2 = Arc::clone(&emitter2.calls);
+        let result2 = manager.start_with_emitter(emitter2, config.clone(), None);
+        assert!(calls2.lock().unwrap().is_empty());
         assert!(result2.is_err());
         assert_eq!(
             result2.unwrap_err(),
-            "session already running",
+            FlashspanError::SessionAlreadyRunning,
             "concurrent start should be rejected"
         );
 
@@ -1739,7 +2836,7 @@ mod tests {
         manager.stop();
 
         // After stop, start should succeed again
-        let result3 = manager.start_with_emitter(TestEmitter::new(), config);
+        let result3 = manager.start_with_emitter(TestEmitter::new(), config, None);
         assert!(result3.is_ok());
 
         manager.stop();
@@ -1823,6 +2920,7 @@ mod tests {
             delay_between_numbers_s: 0.0,
             total_numbers: 1,
             allow_negative_numbers: false,
+            ..Default::default()
         });
 
         // Configure auto-repeat with awaiting validation
@@ -1831,6 +2929,9 @@ mod tests {
             delay_ms: 500,
             config: config.clone(),
             awaiting_validation_session_id: Some(42),
+            adaptive: None,
+            seed_strategy: SeedStrategy::Fixed,
+            seed: 0,
         }));
 
         // Consume the awaiting via mark_validated_and_schedule_info
@@ -1844,6 +2945,15 @@ mod tests {
                 session_id: 42,
                 numbers: vec![1, 2, 3],
                 sum: 6,
+                decimal_places: 0,
+                pairs: vec![],
+                started_at_ms: 0,
+                completed_at_ms: 0,
+                completed_at: None,
+                config: Default::default(),
+                profile_id: None,
+                seed: 0,
+                checkpoints: Vec::new(),
             });
         }
 
@@ -0,0 +1,380 @@
+//! App settings persisted to `settings.json` in the active profile's config dir.
+//!
+//! The file carries a `version`. Each field is read on its own, so a missing or unrecognised
+//! field falls back to its default without discarding the rest. A file that is not valid JSON
+//! is moved aside to `settings.json.corrupt` and replaced with defaults. A file from a newer
+//! version is read for its known fields and then moved aside the same way, so saving cannot
+//! drop the fields this build does not know.
+
+use crate::audio::AudioLevels;
+use crate::core::error::FlashspanError;
+use crate::core::scoring::ScoringConfig;
+use crate::session::recover_lock;
+use log::warn;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+pub const SETTINGS_FILE_NAME: &str = "settings.json";
+/// Current settings file version. Bump when a field changes meaning, and convert the older
+/// layout in [`SettingsFile::parse`]. Unversioned files (version 0) share the version 1 layout.
+pub const SETTINGS_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColorScheme {
+    #[default]
+    Midnight,
+    Ivory,
+    Crimson,
+    Aqua,
+    Violet,
+    Amber,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThemeMode {
+    #[default]
+    Dark,
+    Light,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct AppSettings {
+    pub color_scheme: ColorScheme,
+    pub theme_mode: ThemeMode,
+}
+
+/// On-disk form of the settings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SettingsFile {
+    pub version: u32,
+    pub color_scheme: ColorScheme,
+    pub theme_mode: ThemeMode,
+    pub sound_enabled: bool,
+    /// Id of the selected sound pack; `None` plays the built-in sounds.
+    pub sound_pack: Option<String>,
+    pub audio_levels: AudioLevels,
+    pub scoring: ScoringConfig,
+}
+
+impl Default for SettingsFile {
+    fn default() -> Self {
+        Self {
+            version: SETTINGS_VERSION,
+            color_scheme: ColorScheme::default(),
+            theme_mode: ThemeMode::default(),
+            sound_enabled: true,
+            sound_pack: None,
+            audio_levels: AudioLevels::default(),
+            scoring: ScoringConfig::default(),
+        }
+    }
+}
+
+impl SettingsFile {
+    /// Parse settings text. Only a non-object document is an error; bad fields are defaulted.
+    /// `version` is the file's own when it is newer than [`SETTINGS_VERSION`].
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let value: serde_json::Value = serde_json::from_str(text).map_err(|e| e.to_string())?;
+        let serde_json::Value::Object(fields) = value else {
+            return Err("settings file is not a JSON object".to_string());
+        };
+
+        // Files written before versioning have no `version` field; they read as the current one.
+        let version = fields.get("version").and_then(|v| v.as_u64()).unwrap_or(0) as u32;
+
+        let defaults = Self::default();
+        Ok(Self {
+            version: version.max(SETTINGS_VERSION),
+            color_scheme: field(&fields, "color_scheme").unwrap_or(defaults.color_scheme),
+            theme_mode: field(&fields, "theme_mode").unwrap_or(defaults.theme_mode),
+            sound_enabled: field(&fields, "sound_enabled").unwrap_or(defaults.sound_enabled),
+            sound_pack: field(&fields, "sound_pack").unwrap_or(defaults.sound_pack),
+            audio_levels: field(&fields, "audio_levels")
+                .map(AudioLevels::clamped)
+                .unwrap_or(defaults.audio_levels),
+            scoring: field(&fields, "scoring")
+                .map(ScoringConfig::clamped)
+                .unwrap_or(defaults.scoring),
+        })
+    }
+
+    /// Load from `path`, falling back to defaults when the file is missing or unreadable. A
+    /// newer file's known fields are kept, and the file itself is moved aside.
+    pub fn read(path: &Path) -> Self {
+        let text = match fs::read_to_string(path) {
+            Ok(t) => t,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Self::default(),
+            Err(e) => {
+                warn!("failed to read {}: {}", path.display(), e);
+                return Self::default();
+            }
+        };
+
+        match Self::parse(&text) {
+            Ok(settings) if settings.version > SETTINGS_VERSION => {
+                let e = FlashspanError::UnsupportedVersion {
+                    found: settings.version,
+                    supported: SETTINGS_VERSION,
+                };
+                move_corrupt_aside(path, &e.to_string());
+                Self {
+                    version: SETTINGS_VERSION,
+                    ..settings
+                }
+            }
+            Ok(settings) => settings,
+            Err(e) => {
+                move_corrupt_aside(path, &e);
+                Self::default()
+            }
+        }
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), String> {
+        let text = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        write_atomic(path, &text).map_err(|e| format!("settings write: {}", e))
+    }
+}
+
+/// Rename an unparseable file to `<name>.corrupt` so it is kept for inspection but not reread.
+pub(crate) fn move_corrupt_aside(path: &Path, error: &str) {
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(".corrupt");
+    warn!(
+        "{} is corrupt ({}); moving it to {}",
+        path.display(),
+        error,
+        backup.to_string_lossy()
+    );
+    if let Err(e) = fs::rename(path, &backup) {
+        warn!("failed to move {} aside: {}", path.display(), e);
+    }
+}
+
+/// Write `text` to `path` via a temporary file so a crash never leaves a half-written file.
+pub(crate) fn write_atomic(path: &Path, text: &str) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    fs::write(&tmp, text)?;
+    fs::rename(&tmp, path)
+}
+
+fn field<T: DeserializeOwned>(
+    fields: &serde_json::Map<String, serde_json::Value>,
+    name: &str,
+) -> Option<T> {
+    let value = fields.get(name)?;
+    match serde_json::from_value(value.clone()) {
+        Ok(v) => Some(v),
+        Err(e) => {
+            warn!("ignoring settings field {}: {}", name, e);
+            None
+        }
+    }
+}
+
+/// Settings shared with the commands; saved to the active profile's file after every change.
+pub struct SettingsState {
+    inner: Mutex<LoadedSettings>,
+}
+
+struct LoadedSettings {
+    settings: AppSettings,
+    scoring: ScoringConfig,
+    path: PathBuf,
+}
+
+impl LoadedSettings {
+    /// Read `path` and apply the saved sound preference, levels and sound pack.
+    fn read(path: PathBuf) -> Self {
+        let file = SettingsFile::read(&path);
+        crate::audio::set_enabled(file.sound_enabled);
+        crate::audio::set_levels(file.audio_levels);
+        crate::sound_packs::restore(file.sound_pack.as_deref());
+        Self {
+            settings: AppSettings {
+                color_scheme: file.color_scheme,
+                theme_mode: file.theme_mode,
+            },
+            scoring: file.scoring,
+            path,
+        }
+    }
+
+    /// A failed save is logged; the change still applies for this run.
+    fn save(&self) {
+        let file = SettingsFile {
+            version: SETTINGS_VERSION,
+            color_scheme: self.settings.color_scheme,
+            theme_mode: self.settings.theme_mode,
+            sound_enabled: crate::audio::is_enabled(),
+            sound_pack: crate::audio::sound_pack_id(),
+            audio_levels: crate::audio::levels(),
+            scoring: self.scoring,
+        };
+        if let Err(e) = file.write(&self.path) {
+            warn!("failed to save settings: {}", e);
+        }
+    }
+}
+
+impl SettingsState {
+    pub fn load(path: PathBuf) -> Self {
+        Self {
+            inner: Mutex::new(LoadedSettings::read(path)),
+        }
+    }
+
+    pub fn get(&self) -> AppSettings {
+        recover_lock(&self.inner, "settings").settings.clone()
+    }
+
+    /// Apply `change`, save, and return the updated settings.
+    pub fn update(&self, change: impl FnOnce(&mut AppSettings)) -> AppSettings {
+        // Saving under the lock keeps concurrent updates from writing out of order.
+        let mut guard = recover_lock(&self.inner, "settings");
+        change(&mut guard.settings);
+        guard.save();
+        guard.settings.clone()
+    }
+
+    pub fn scoring(&self) -> ScoringConfig {
+        recover_lock(&self.inner, "settings").scoring
+    }
+
+    /// Replace the scoring weights and save.
+    pub fn set_scoring(&self, scoring: ScoringConfig) {
+        let mut guard = recover_lock(&self.inner, "settings");
+        guard.scoring = scoring;
+        guard.save();
+    }
+
+    /// Save the current settings, e.g. after the sound preference changes.
+    pub fn persist(&self) {
+        recover_lock(&self.inner, "settings").save();
+    }
+
+    /// Replace the settings with those saved at `path`, e.g. after switching profiles.
+    pub fn switch_to(&self, path: PathBuf) -> AppSettings {
+        let mut guard = recover_lock(&self.inner, "settings");
+        *guard = LoadedSettings::read(path);
+        guard.settings.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::scratch_dir;
+
+    fn temp_path(name: &str) -> PathBuf {
+        scratch_dir(&format!("settings-{}", name)).join(SETTINGS_FILE_NAME)
+    }
+
+    #[test]
+    fn round_trips_through_file() {
+        let path = temp_path("round-trip");
+        let file = SettingsFile {
+            color_scheme: ColorScheme::Aqua,
+            theme_mode: ThemeMode::Light,
+            sound_enabled: false,
+            sound_pack: Some("arcade".to_string()),
+            audio_levels: AudioLevels {
+                master: 60,
+                beep: 30,
+                feedback: 90,
+            },
+            scoring: ScoringConfig {
+                base_points: 250,
+                latency_half_life_ms: 0,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        file.write(&path).unwrap();
+        assert_eq!(SettingsFile::read(&path), file);
+    }
+
+    #[test]
+    fn missing_and_unknown_fields_default_individually() {
+        // Unversioned file from before sound was persisted, with a scheme this build lacks.
+        let parsed =
+            SettingsFile::parse(r#"{"color_scheme":"neon","theme_mode":"light","extra":1}"#)
+                .unwrap();
+        assert_eq!(
+            parsed,
+            SettingsFile {
+                theme_mode: ThemeMode::Light,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn newer_version_keeps_known_fields() {
+        let parsed = SettingsFile::parse(
+            r#"{"version":9,"color_scheme":"amber","theme_mode":"dark","sound_enabled":false}"#,
+        )
+        .unwrap();
+        assert_eq!(parsed.color_scheme, ColorScheme::Amber);
+        assert!(!parsed.sound_enabled);
+        assert_eq!(parsed.version, 9);
+    }
+
+    #[test]
+    fn saving_after_a_newer_file_keeps_the_original() {
+        let path = temp_path("newer");
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let original = r#"{"version":9,"color_scheme":"amber","haptics":{"strength":3}}"#;
+        fs::write(&path, original).unwrap();
+
+        let loaded = SettingsFile::read(&path);
+        assert_eq!(loaded.color_scheme, ColorScheme::Amber);
+        assert_eq!(loaded.version, SETTINGS_VERSION);
+        loaded.write(&path).unwrap();
+
+        let kept = path.with_extension("json.corrupt");
+        assert_eq!(fs::read_to_string(&kept).unwrap(), original);
+        assert_eq!(SettingsFile::read(&path), loaded);
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn audio_levels_default_per_field_and_are_capped() {
+        let parsed =
+            SettingsFile::parse(r#"{"audio_levels":{"master":40,"feedback":250}}"#).unwrap();
+        assert_eq!(
+            parsed.audio_levels,
+            AudioLevels {
+                master: 40,
+                beep: 100,
+                feedback: 100,
+            }
+        );
+    }
+
+    #[test]
+    fn corrupt_file_is_moved_aside() {
+        let path = temp_path("corrupt");
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, "{\"color_scheme\": \"aq").unwrap();
+
+        assert_eq!(SettingsFile::read(&path), SettingsFile::default());
+        assert!(!path.exists());
+        assert!(path.with_extension("json.corrupt").exists());
+    }
+
+    #[test]
+    fn non_object_is_rejected() {
+        assert!(SettingsFile::parse("[1, 2]").is_err());
+        assert!(SettingsFile::parse("").is_err());
+    }
+}
@@ -0,0 +1,389 @@
+//! Sound packs: replacement sounds for the kinds in [`SOUND_KINDS`].
+//!
+//! A pack is a directory under `sound-packs/` in the app data directory, named by its id,
+//! holding a `manifest.json` and the files it lists:
+//!
+//! ```json
+//! { "name": "Arcade", "sounds": { "beep": "blip.ogg", "buzzer": "miss.flac" } }
+//! ```
+//!
+//! Files may be WAV, OGG or FLAC. Installing a pack decodes each file once; listing and
+//! selecting an installed pack only check that each file opens as one of those formats. A
+//! kind whose file is missing or fails the check plays the built-in sound instead and is
+//! listed in [`SoundPackInfo::problems`]. The selected pack is saved in the active profile's
+//! settings.
+
+use crate::audio::SOUND_KINDS;
+use crate::core::error::FlashspanError;
+use log::warn;
+use rodio::Decoder;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::{Cursor, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+
+pub const MANIFEST_FILE_NAME: &str = "manifest.json";
+const SOUND_EXTENSIONS: [&str; 3] = ["wav", "ogg", "flac"];
+
+static PACKS_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Where packs are installed. Set once at startup, before the settings are loaded.
+pub fn set_packs_dir(dir: PathBuf) {
+    let _ = PACKS_DIR.set(dir);
+}
+
+fn packs_dir() -> Result<&'static Path, FlashspanError> {
+    PACKS_DIR
+        .get()
+        .map(PathBuf::as_path)
+        .ok_or_else(|| FlashspanError::storage("sound pack directory is not set"))
+}
+
+/// How thoroughly [`load_sound`] checks a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SoundCheck {
+    /// The file opens as a supported format; enough for packs that were checked on install.
+    Header,
+    /// The whole file decodes to some audio.
+    Decode,
+}
+
+#[derive(Debug, Deserialize)]
+struct Manifest {
+    #[serde(default)]
+    name: Option<String>,
+    /// Kind to file name within the pack directory.
+    sounds: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SoundPackInfo {
+    pub id: String,
+    pub name: String,
+    /// Kinds this pack replaces; the rest play the built-in sounds.
+    pub sounds: Vec<&'static str>,
+    /// Manifest entries that were skipped, with the reason.
+    pub problems: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SoundPackList {
+    pub selected_id: Option<String>,
+    pub packs: Vec<SoundPackInfo>,
+}
+
+/// A loaded pack: the file contents of every sound that decoded, by kind.
+pub struct SoundPack {
+    pub id: String,
+    name: String,
+    sounds: HashMap<&'static str, Arc<[u8]>>,
+    problems: Vec<String>,
+}
+
+impl SoundPack {
+    /// Read the manifest in `dir` and each file it lists, checking that the files open as
+    /// audio. Only a missing or malformed manifest is an error; bad entries are recorded in
+    /// `problems`.
+    pub fn load(dir: &Path) -> Result<Self, FlashspanError> {
+        Self::load_checked(dir, SoundCheck::Header)
+    }
+
+    fn load_checked(dir: &Path, check: SoundCheck) -> Result<Self, FlashspanError> {
+        let id = dir
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let manifest = read_manifest(dir)?;
+
+        let mut sounds = HashMap::new();
+        let mut problems = Vec::new();
+        for (kind, file) in &manifest.sounds {
+            let Some(kind) = SOUND_KINDS.iter().copied().find(|k| k == kind) else {
+                problems.push(format!("{}: not a sound kind", kind));
+                continue;
+            };
+            match load_sound(dir, file, check) {
+                Ok(data) => {
+                    sounds.insert(kind, data);
+                }
+                Err(reason) => problems.push(format!("{}: {}", kind, reason)),
+            }
+        }
+        for problem in &problems {
+            warn!("sound pack {}: {}", id, problem);
+        }
+
+        let name = manifest
+            .name
+            .map(|n| n.trim().to_string())
+            .filter(|n| !n.is_empty())
+            .unwrap_or_else(|| id.clone());
+        Ok(Self {
+            id,
+            name,
+            sounds,
+            problems,
+        })
+    }
+
+    /// The pack's file for `kind`, if it has one that decoded.
+    pub fn sound(&self, kind: &str) -> Option<Arc<[u8]>> {
+        self.sounds.get(kind).cloned()
+    }
+
+    pub fn info(&self) -> SoundPackInfo {
+        SoundPackInfo {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            sounds: SOUND_KINDS
+                .iter()
+                .copied()
+                .filter(|kind| self.sounds.contains_key(kind))
+                .collect(),
+            problems: self.problems.clone(),
+        }
+    }
+}
+
+fn read_manifest(dir: &Path) -> Result<Manifest, FlashspanError> {
+    let path = dir.join(MANIFEST_FILE_NAME);
+    let invalid = |e: &dyn std::fmt::Display| FlashspanError::InvalidFile {
+        detail: format!("{}: {}", path.display(), e),
+    };
+    let text = fs::read_to_string(&path).map_err(|e| invalid(&e))?;
+    serde_json::from_str(&text).map_err(|e| invalid(&e))
+}
+
+/// A bare file name, so a manifest or pack id cannot point outside its directory.
+fn is_plain_file_name(name: &str) -> bool {
+    Path::new(name).file_name() == Some(name.as_ref())
+}
+
+/// Read `file` from the pack and check that rodio recognises it, decoding it fully for
+/// [`SoundCheck::Decode`].
+fn load_sound(dir: &Path, file: &str, check: SoundCheck) -> Result<Arc<[u8]>, String> {
+    if !is_plain_file_name(file) {
+        return Err(format!("\"{}\" is not a file name in the pack", file));
+    }
+    let extension = Path::new(file)
+        .extension()
+        .map(|e| e.to_string_lossy().to_ascii_lowercase());
+    if !extension.is_some_and(|e| SOUND_EXTENSIONS.contains(&e.as_str())) {
+        return Err(format!("{} is not a WAV, OGG or FLAC file", file));
+    }
+
+    let data: Arc<[u8]> = fs::read(dir.join(file))
+        .map_err(|e| format!("{}: {}", file, e))?
+        .into();
+    let decoder = Decoder::try_from(Cursor::new(Arc::clone(&data)))
+        .map_err(|e| format!("{}: {}", file, e))?;
+    if check == SoundCheck::Decode && decoder.count() == 0 {
+        return Err(format!("{}: no audio", file));
+    }
+    Ok(data)
+}
+
+fn load_installed(packs: &Path, id: &str) -> Result<SoundPack, FlashspanError> {
+    let dir = packs.join(id);
+    if !is_plain_file_name(id) || !dir.join(MANIFEST_FILE_NAME).is_file() {
+        return Err(FlashspanError::SoundPackNotFound { id: id.to_string() });
+    }
+    SoundPack::load(&dir)
+}
+
+fn list_in(packs: &Path) -> Result<Vec<SoundPackInfo>, FlashspanError> {
+    let entries = match fs::read_dir(packs) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(FlashspanError::storage(e)),
+    };
+    let mut list: Vec<SoundPackInfo> = entries
+        .filter_map(Result::ok)
+        // Skips the staging copies left by an interrupted install.
+        .filter(|entry| !entry.file_name().to_string_lossy().starts_with('.'))
+        .filter(|entry| entry.path().join(MANIFEST_FILE_NAME).is_file())
+        .filter_map(|entry| match SoundPack::load(&entry.path()) {
+            Ok(pack) => Some(pack.info()),
+            Err(e) => {
+                warn!("skipping sound pack: {}", e);
+                None
+            }
+        })
+        .collect();
+    list.sort_by_key(|pack| pack.name.to_lowercase());
+    Ok(list)
+}
+
+/// Copy the pack at `source` into `packs`, keeping its directory name as the id and
+/// replacing an older copy. Only the manifest and the sounds that decoded are copied.
+fn install_into(packs: &Path, source: &Path) -> Result<SoundPack, FlashspanError> {
+    let pack = SoundPack::load_checked(source, SoundCheck::Decode)?;
+    if !is_plain_file_name(&pack.id) || pack.id.starts_with('.') {
+        return Err(FlashspanError::InvalidName {
+            reason: format!("\"{}\" cannot be used as a sound pack id", pack.id),
+        });
+    }
+    if pack.sounds.is_empty() {
+        return Err(FlashspanError::InvalidFile {
+            detail: format!(
+                "{}: no sound in the pack could be decoded",
+                source.display()
+            ),
+        });
+    }
+
+    // Stage the copy so a failed install leaves any previous version in place.
+    let manifest = read_manifest(source)?;
+    let staging = packs.join(format!(".{}.tmp", pack.id));
+    let _ = fs::remove_dir_all(&staging);
+    fs::create_dir_all(&staging).map_err(FlashspanError::storage)?;
+    let mut files = vec![MANIFEST_FILE_NAME];
+    files.extend(
+        manifest
+            .sounds
+            .iter()
+            .filter(|(kind, _)| pack.sounds.contains_key(kind.as_str()))
+            .map(|(_, file)| file.as_str()),
+    );
+    for file in files {
+        fs::copy(source.join(file), staging.join(file)).map_err(FlashspanError::storage)?;
+    }
+
+    let target = packs.join(&pack.id);
+    if target.exists() {
+        fs::remove_dir_all(&target).map_err(FlashspanError::storage)?;
+    }
+    fs::rename(&staging, &target).map_err(FlashspanError::storage)?;
+    // The copied files are the ones just decoded, so the loaded pack stands for the installed one.
+    Ok(pack)
+}
+
+/// Play sounds from the installed pack `id`, or the built-in sounds for `None`.
+pub fn select(id: Option<&str>) -> Result<Option<SoundPackInfo>, FlashspanError> {
+    let pack = match id {
+        Some(id) => Some(load_installed(packs_dir()?, id)?),
+        None => None,
+    };
+    let info = pack.as_ref().map(SoundPack::info);
+    crate::audio::set_sound_pack(pack);
+    Ok(info)
+}
+
+/// Select the pack saved in a profile's settings, falling back to the built-in sounds.
+pub fn restore(id: Option<&str>) {
+    if let Err(e) = select(id) {
+        warn!("sound pack unavailable, using built-in sounds: {}", e);
+        crate::audio::set_sound_pack(None);
+    }
+}
+
+#[tauri::command]
+pub fn list_sound_packs() -> Result<SoundPackList, FlashspanError> {
+    Ok(SoundPackList {
+        selected_id: crate::audio::sound_pack_id(),
+        packs: list_in(packs_dir()?)?,
+    })
+}
+
+/// Install the pack in `source_dir`. Reinstalling the selected pack reloads it.
+#[tauri::command]
+pub fn install_sound_pack(source_dir: String) -> Result<SoundPackInfo, FlashspanError> {
+    let pack = install_into(packs_dir()?, Path::new(&source_dir))?;
+    let info = pack.info();
+    if crate::audio::sound_pack_id().as_deref() == Some(pack.id.as_str()) {
+        crate::audio::set_sound_pack(Some(pack));
+    }
+    Ok(info)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::scratch_dir;
+
+    const BEEP: &[u8] = include_bytes!("../../src/assets/beep.wav");
+
+    fn temp_dir(name: &str) -> PathBuf {
+        scratch_dir(&format!("sound-packs-{}", name))
+    }
+
+    fn write_pack(dir: &Path, manifest: &str, files: &[(&str, &[u8])]) {
+        fs::create_dir_all(dir).unwrap();
+        fs::write(dir.join(MANIFEST_FILE_NAME), manifest).unwrap();
+        for (name, data) in files {
+            fs::write(dir.join(name), data).unwrap();
+        }
+    }
+
+    #[test]
+    fn load_keeps_decodable_sounds_and_reports_the_rest() {
+        let root = temp_dir("load");
+        let dir = root.join("arcade");
+        write_pack(
+            &dir,
+            r#"{"name":"Arcade","sounds":{"beep":"blip.wav","applause":"missing.ogg",
+                "buzzer":"junk.flac","fanfare":"blip.wav"}}"#,
+            &[("blip.wav", BEEP), ("junk.flac", b"not audio")],
+        );
+
+        let pack = SoundPack::load(&dir).unwrap();
+        let info = pack.info();
+        assert_eq!((info.id.as_str(), info.name.as_str()), ("arcade", "Arcade"));
+        assert_eq!(info.sounds, vec!["beep"]);
+        assert_eq!(info.problems.len(), 3);
+        assert_eq!(pack.sound("beep").as_deref(), Some(BEEP));
+        assert!(pack.sound("buzzer").is_none());
+
+        assert!(matches!(
+            SoundPack::load(&root),
+            Err(FlashspanError::InvalidFile { .. })
+        ));
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn install_copies_listed_sounds_and_replaces_older_copy() {
+        let root = temp_dir("install");
+        let (packs, source) = (root.join("packs"), root.join("retro"));
+        write_pack(
+            &source,
+            r#"{"sounds":{"beep":"blip.wav","buzzer":"../escape.wav"}}"#,
+            &[("blip.wav", BEEP), ("notes.txt", b"readme")],
+        );
+
+        let installed = install_into(&packs, &source).unwrap().info();
+        assert_eq!(
+            (installed.id.as_str(), installed.name.as_str()),
+            ("retro", "retro")
+        );
+        assert_eq!(installed.problems.len(), 1);
+        assert!(packs.join("retro").join("blip.wav").is_file());
+        assert!(!packs.join("retro").join("notes.txt").exists());
+
+        fs::write(
+            source.join(MANIFEST_FILE_NAME),
+            r#"{"name":"Retro","sounds":{"beep":"blip.wav"}}"#,
+        )
+        .unwrap();
+        install_into(&packs, &source).unwrap();
+        let list = list_in(&packs).unwrap();
+        assert_eq!(list.len(), 1);
+        assert_eq!(
+            (list[0].name.as_str(), list[0].problems.len()),
+            ("Retro", 0)
+        );
+
+        assert!(matches!(
+            load_installed(&packs, "../retro"),
+            Err(FlashspanError::SoundPackNotFound { .. })
+        ));
+        write_pack(&source, r#"{"sounds":{"beep":"notes.txt"}}"#, &[]);
+        assert!(matches!(
+            install_into(&packs, &source),
+            Err(FlashspanError::InvalidFile { .. })
+        ));
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,11 @@
+//! Helpers shared by the desktop modules' unit tests.
+
+use std::fs;
+use std::path::PathBuf;
+
+/// An empty scratch directory under the system temp dir, unique to `name` and this process.
+pub(crate) fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("flashspan-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
@@ -1,6 +1,8 @@
+use crate::core::analytics::{SessionSample, analyze};
 use crate::core::engine::build_session_plan;
+use crate::core::error::FlashspanError;
 use crate::core::types::{SessionConfigEffective, SessionConfigInput};
-use crate::core::validate::normalize_session_config;
+use crate::core::validate::{normalize_session_config, validate_config};
 use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::*;
 
@@ -10,8 +12,24 @@ pub struct WasmNormalizedSessionConfig {
     pub effective: SessionConfigEffective,
 }
 
-fn js_error(message: impl Into<String>) -> JsValue {
-    JsValue::from_str(&message.into())
+/// Errors reach JS as the same `{ code, message, ... }` object the Tauri commands reject with.
+fn js_error(error: FlashspanError) -> JsValue {
+    error
+        .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+        .unwrap_or_else(|_| JsValue::from_str(&error.to_string()))
+}
+
+fn decode_config(input: JsValue) -> Result<SessionConfigInput, JsValue> {
+    serde_wasm_bindgen::from_value(input).map_err(|err| {
+        js_error(FlashspanError::invalid_args(format!(
+            "failed to decode SessionConfigInput: {err}"
+        )))
+    })
+}
+
+fn encode(value: &impl Serialize, what: &str) -> Result<JsValue, JsValue> {
+    serde_wasm_bindgen::to_value(value)
+        .map_err(|err| js_error(FlashspanError::encode(format!("{what}: {err}"))))
 }
 
 #[wasm_bindgen]
@@ -26,12 +44,9 @@ pub fn wasm_version() -> String {
 
 #[wasm_bindgen]
 pub fn normalize_session_config_wasm(input: JsValue) -> Result<JsValue, JsValue> {
-    let input: SessionConfigInput = serde_wasm_bindgen::from_value(input)
-        .map_err(|err| js_error(format!("failed to decode SessionConfigInput: {err}")))?;
-    let (config, effective) = normalize_session_config(input);
+    let (config, effective) = normalize_session_config(decode_config(input)?);
     let output = WasmNormalizedSessionConfig { config, effective };
-    serde_wasm_bindgen::to_value(&output)
-        .map_err(|err| js_error(format!("failed to encode normalized config: {err}")))
+    encode(&output, "normalized config")
 }
 
 #[wasm_bindgen]
@@ -40,12 +55,27 @@ pub fn build_session_plan_wasm(
     input: JsValue,
     seed: Option<u64>,
 ) -> Result<JsValue, JsValue> {
-    let input: SessionConfigInput = serde_wasm_bindgen::from_value(input)
-        .map_err(|err| js_error(format!("failed to decode SessionConfigInput: {err}")))?;
-    let (config, effective) = normalize_session_config(input);
+    let (config, effective) = normalize_session_config(decode_config(input)?);
+    // Same check as a native start, so both runtimes reject a config the same way.
+    validate_config(&config).map_err(js_error)?;
     let plan = build_session_plan(session_id, config, effective, seed);
-    serde_wasm_bindgen::to_value(&plan)
-        .map_err(|err| js_error(format!("failed to encode SessionPlan: {err}")))
+    encode(&plan, "SessionPlan")
+}
+
+/// Statistics over the results the web build kept, in the same shape as desktop history
+/// records.
+#[wasm_bindgen]
+pub fn analyze_sessions_wasm(
+    samples: JsValue,
+    utc_offset_minutes: i32,
+) -> Result<JsValue, JsValue> {
+    let samples: Vec<SessionSample> = serde_wasm_bindgen::from_value(samples).map_err(|err| {
+        js_error(FlashspanError::invalid_args(format!(
+            "failed to decode session samples: {err}"
+        )))
+    })?;
+    let report = analyze(&samples, utc_offset_minutes).map_err(js_error)?;
+    encode(&report, "AnalyticsReport")
 }
 
 #[cfg(all(test, target_arch = "wasm32"))]
@@ -67,6 +97,7 @@ mod tests {
             delay_between_numbers_s: 0.2,
             total_numbers: 4,
             allow_negative_numbers: true,
+            ..Default::default()
         };
 
         let input_value = serde_wasm_bindgen::to_value(&input).expect("encode input");
@@ -3,6 +3,7 @@ mod wasm_tests {
     use crate::core::types::SessionConfigInput;
     use crate::{build_session_plan_wasm, normalize_session_config_wasm, ping, wasm_version};
     use serde_wasm_bindgen::to_value;
+    use wasm_bindgen::JsValue;
     use wasm_bindgen_test::*;
 
     wasm_bindgen_test_configure!(run_in_browser);
@@ -35,6 +36,7 @@ mod wasm_tests {
             delay_between_numbers_s: 0.5,
             total_numbers: 10,
             allow_negative_numbers: false,
+            ..Default::default()
         };
 
         let input_value = to_value(&input).unwrap();
@@ -54,6 +56,7 @@ mod wasm_tests {
             delay_between_numbers_s: 0.0,
             total_numbers: 5,
             allow_negative_numbers: false,
+            ..Default::default()
         };
 
         let input_value = to_value(&input).unwrap();
@@ -70,6 +73,7 @@ mod wasm_tests {
             delay_between_numbers_s: 0.0,
             total_numbers: 5,
             allow_negative_numbers: false,
+            ..Default::default()
         };
 
         let input_value = to_value(&input).unwrap();
@@ -85,6 +89,7 @@ mod wasm_tests {
             delay_between_numbers_s: 0.5,
             total_numbers: 5,
             allow_negative_numbers: false,
+            ..Default::default()
         };
 
         let input_value = to_value(&input).unwrap();
@@ -103,6 +108,7 @@ mod wasm_tests {
             delay_between_numbers_s: 0.0,
             total_numbers: 3,
             allow_negative_numbers: false,
+            ..Default::default()
         };
 
         let input_value = to_value(&input).unwrap();
@@ -130,6 +136,7 @@ mod wasm_tests {
             delay_between_numbers_s: 0.0,
             total_numbers: 4,
             allow_negative_numbers: true,
+            ..Default::default()
         };
 
         let input_value = to_value(&input).unwrap();
@@ -168,6 +175,7 @@ mod wasm_tests {
             delay_between_numbers_s: 0.0,
             total_numbers: 2,
             allow_negative_numbers: false,
+            ..Default::default()
         };
 
         let input_value = to_value(&input).unwrap();
@@ -195,6 +203,7 @@ mod wasm_tests {
             delay_between_numbers_s: 0.0,
             total_numbers: 5,
             allow_negative_numbers: false,
+            ..Default::default()
         };
 
         let input_value = to_value(&input).unwrap();
@@ -206,4 +215,18 @@ mod wasm_tests {
         // Convert back to check structure - plan should have steps property
         assert!(!plan_value.is_null());
     }
+
+    #[wasm_bindgen_test]
+    fn test_build_session_plan_wasm_rejects_with_error_code() {
+        let err = build_session_plan_wasm(1, JsValue::from_str("not a config"), None)
+            .expect_err("a string is not a SessionConfigInput");
+        let err: serde_json::Value = serde_wasm_bindgen::from_value(err).unwrap();
+        assert_eq!(err["code"], "InvalidArgs");
+        assert!(
+            err["message"]
+                .as_str()
+                .unwrap()
+                .contains("SessionConfigInput")
+        );
+    }
 }
@@ -0,0 +1,213 @@
+//! Race mode end to end: a host and two players on loopback, each player running the round
+//! through its own `SessionManager`.
+
+use app_lib::core::answer::Submission;
+use app_lib::core::engine::build_session_plan;
+use app_lib::core::error::FlashspanError;
+use app_lib::core::race::Leaderboard;
+use app_lib::core::types::{
+    Checkpoint, CheckpointSchedule, ClearScreen, SessionComplete, SessionConfigInput,
+    SessionPaused, SessionPlan, SessionResumed, ShowNumber, ShowPair,
+};
+use app_lib::core::validate::normalize_session_config;
+use app_lib::race::{RaceClient, RaceEvent, RaceHost};
+use app_lib::session::{SessionEmitter, SessionManager};
+use std::sync::Arc;
+use std::sync::mpsc::{Receiver, Sender, channel};
+use std::time::Duration;
+
+const TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Reports each finished session and checkpoint; everything else is ignored.
+struct CompleteSignal {
+    completed: Sender<SessionComplete>,
+    checkpoints: Sender<Checkpoint>,
+}
+
+impl SessionEmitter for CompleteSignal {
+    fn clear_screen(&self, _payload: ClearScreen) {}
+    fn countdown_tick(&self, _value: String) {}
+    fn show_number(&self, _payload: ShowNumber) {}
+    fn show_pair(&self, _payload: ShowPair) {}
+    fn session_complete(&self, payload: SessionComplete) {
+        let _ = self.completed.send(payload);
+    }
+    fn session_paused(&self, _payload: SessionPaused) {}
+    fn session_resumed(&self, _payload: SessionResumed) {}
+    fn checkpoint(&self, payload: Checkpoint) {
+        let _ = self.checkpoints.send(payload);
+    }
+}
+
+struct Player {
+    client: RaceClient,
+    manager: Arc<SessionManager>,
+    completed: Receiver<SessionComplete>,
+    checkpoints: Receiver<Checkpoint>,
+    events: Receiver<RaceEvent>,
+}
+
+fn join(address: &str, name: &str) -> Player {
+    let manager = Arc::new(SessionManager::default());
+    let (done_tx, completed) = channel();
+    let (checkpoint_tx, checkpoints) = channel();
+    let (event_tx, events) = channel();
+    let runner = Arc::clone(&manager);
+    let client = RaceClient::connect(
+        address,
+        name,
+        move |plan| {
+            let signal = CompleteSignal {
+                completed: done_tx.clone(),
+                checkpoints: checkpoint_tx.clone(),
+            };
+            runner.start_plan_with_emitter(signal, plan)
+        },
+        move |event| {
+            let _ = event_tx.send(event);
+        },
+    )
+    .unwrap();
+    Player {
+        client,
+        manager,
+        completed,
+        checkpoints,
+        events,
+    }
+}
+
+fn wait_for<T>(rx: &Receiver<T>, wanted: impl Fn(&T) -> bool) -> T {
+    loop {
+        let value = rx.recv_timeout(TIMEOUT).expect("timed out waiting");
+        if wanted(&value) {
+            return value;
+        }
+    }
+}
+
+fn quick_plan() -> SessionPlan {
+    plan_with_checkpoints(CheckpointSchedule::Off)
+}
+
+fn plan_with_checkpoints(checkpoints: CheckpointSchedule) -> SessionPlan {
+    let (config, effective) = normalize_session_config(SessionConfigInput {
+        digits_per_number: 1,
+        number_duration_s: 0.1,
+        delay_between_numbers_s: 0.0,
+        total_numbers: 3,
+        allow_negative_numbers: false,
+        checkpoints,
+        ..Default::default()
+    });
+    build_session_plan(0, config, effective, Some(7))
+}
+
+fn sum_answer(sum: i64) -> Submission {
+    Submission {
+        provided_sum: Some(sum),
+        provided_answers: None,
+    }
+}
+
+#[test]
+fn loopback_players_race_a_round() {
+    let (board_tx, boards) = channel::<Leaderboard>();
+    let host = RaceHost::bind("127.0.0.1:0", move |board: &Leaderboard| {
+        let _ = board_tx.send(board.clone());
+    })
+    .unwrap();
+    let address = host.local_addr().to_string();
+
+    let ada = join(&address, "Ada");
+    let bo = join(&address, "Bo");
+    assert_ne!(ada.client.player_id(), bo.client.player_id());
+    let taken = RaceClient::connect(&address, "ADA", |_| Ok(0), |_| {});
+    assert!(matches!(taken, Err(FlashspanError::Race { .. })));
+    assert_eq!(host.leaderboard().standings.len(), 2);
+
+    let round = host.start_round(quick_plan()).unwrap();
+    assert_eq!(round, 1);
+
+    // An answer sent before the numbers could have been flashed is refused.
+    let started = wait_for(&ada.events, |e| matches!(e, RaceEvent::Round { .. }));
+    let RaceEvent::Round { session_id, .. } = started else {
+        unreachable!()
+    };
+    assert!(ada.client.submit(session_id, &sum_answer(0)));
+    wait_for(&ada.events, |e| matches!(e, RaceEvent::Error { .. }));
+
+    // Each player runs the plan locally, then answers: Ada correctly, Bo one off.
+    let ada_done = ada.completed.recv_timeout(TIMEOUT).unwrap();
+    let bo_done = bo.completed.recv_timeout(TIMEOUT).unwrap();
+    assert_eq!(ada_done.numbers, bo_done.numbers);
+    assert!(
+        ada.client
+            .submit(ada_done.session_id, &sum_answer(ada_done.sum))
+    );
+    assert!(
+        bo.client
+            .submit(bo_done.session_id, &sum_answer(bo_done.sum + 1))
+    );
+    assert!(!ada.client.submit(ada_done.session_id + 100, &sum_answer(0)));
+
+    let board = wait_for(&boards, |b| {
+        b.standings.len() == 2 && b.standings.iter().all(|s| s.answer.is_some())
+    });
+    let ranked: Vec<(&str, bool, u32)> = board
+        .standings
+        .iter()
+        .map(|s| (s.name.as_str(), s.answer.unwrap().correct, s.rounds_correct))
+        .collect();
+    assert_eq!(ranked, [("Ada", true, 1), ("Bo", false, 0)]);
+
+    let verdict = wait_for(&ada.events, |e| matches!(e, RaceEvent::Verdict { .. }));
+    let RaceEvent::Verdict {
+        round: 1,
+        validation,
+        ..
+    } = verdict
+    else {
+        panic!("unexpected verdict {:?}", verdict);
+    };
+    assert!(validation.correct);
+    wait_for(
+        &bo.events,
+        |e| matches!(e, RaceEvent::Leaderboard { leaderboard } if leaderboard.standings == board.standings),
+    );
+
+    // One answer per round.
+    assert!(
+        ada.client
+            .submit(ada_done.session_id, &sum_answer(ada_done.sum))
+    );
+    wait_for(&ada.events, |e| matches!(e, RaceEvent::Error { .. }));
+
+    drop(bo);
+    wait_for(&boards, |b| b.standings.len() == 1);
+    drop(host);
+    wait_for(&ada.events, |e| matches!(e, RaceEvent::Disconnected));
+}
+
+#[test]
+fn checkpoint_waits_do_not_count_as_answer_time() {
+    let host = RaceHost::bind("127.0.0.1:0", |_: &Leaderboard| {}).unwrap();
+    let ada = join(&host.local_addr().to_string(), "Ada");
+
+    host.start_round(plan_with_checkpoints(CheckpointSchedule::After {
+        positions: vec![1],
+    }))
+    .unwrap();
+    let asked = ada.checkpoints.recv_timeout(TIMEOUT).unwrap();
+    std::thread::sleep(Duration::from_millis(600));
+    let answered = ada.manager.answer_checkpoint(asked.session_id, 0).unwrap();
+    assert!(ada.client.checkpoint(asked.session_id, answered.index));
+
+    let done = ada.completed.recv_timeout(TIMEOUT).unwrap();
+    assert!(ada.client.submit(done.session_id, &sum_answer(done.sum)));
+    let verdict = wait_for(&ada.events, |e| matches!(e, RaceEvent::Verdict { .. }));
+    let RaceEvent::Verdict { answer_ms, .. } = verdict else {
+        unreachable!()
+    };
+    assert!(answer_ms < 400, "checkpoint wait counted: {} ms", answer_ms);
+}
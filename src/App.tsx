@@ -10,14 +10,24 @@ import "./App.css";
 import {
 	type AutoRepeatWaitingPayload,
 	type ColorScheme,
+	errorMessage,
+	type OperandPair,
 	type Phase,
 	runtime,
 	type SessionConfigInput,
+	type ShowNumber,
+	type ShowPair,
 	type StartSessionResponse,
 	type SubmitAnswerResponse,
 	type ThemeMode,
 } from "./runtime";
 
+/** A multiplication or division problem as flashed, e.g. `12 × 3`. */
+function formatPair(pair: Pick<OperandPair, "operation" | "left" | "right">) {
+	const symbol = pair.operation === "division" ? "÷" : "×";
+	return `${pair.left} ${symbol} ${pair.right}`;
+}
+
 function isEscapeKey(event: KeyboardEvent): boolean {
 	return event.key === "Escape";
 }
@@ -138,6 +148,13 @@ export default function App() {
 
 	const [sessionId, setSessionId] = createSignal<number | null>(null);
 	const [numbers, setNumbers] = createSignal<number[]>([]);
+	const [pairs, setPairs] = createSignal<OperandPair[]>([]);
+
+	// Pair sessions list each problem with its answer instead of the flashed numbers.
+	const answerRows = (): string[] =>
+		pairs().length > 0
+			? pairs().map((pair) => `${formatPair(pair)} = ${pair.answer}`)
+			: numbers().map(String);
 
 	// Scale UI elements proportionally to window size relative to startup dimensions.
 	onMount(() => {
@@ -304,7 +321,7 @@ export default function App() {
 			applySubmitAnswerResponse(resp);
 		} catch (e) {
 			setHasValidated(false);
-			setValidationSummary(String(e));
+			setValidationSummary(errorMessage(e));
 		}
 	};
 
@@ -343,16 +360,24 @@ export default function App() {
 			if (value === "3") void setFullscreen(true);
 		});
 
-		const unlistenFlash = await runtime.onShowNumber((payload) => {
+		const showFlash = (payload: ShowNumber | ShowPair, text: string) => {
 			resetForIncomingSessionIfComplete();
 			setSessionId(payload.session_id);
 			setPhase("flashing");
-			setDisplayText(String(payload.value));
+			setDisplayText(text);
 			setCurrentShown({
 				session_id: payload.session_id,
 				index: payload.index,
 				emitted_at_ms: payload.emitted_at_ms,
 			});
+		};
+
+		const unlistenPair = await runtime.onShowPair((payload) => {
+			showFlash(payload, formatPair(payload));
+		});
+
+		const unlistenFlash = await runtime.onShowNumber((payload) => {
+			showFlash(payload, String(payload.value));
 
 			// Debug-only: measure backend->UI delivery latency for first flash.
 			if (
@@ -412,6 +437,7 @@ export default function App() {
 			setCurrentShown(null);
 			setShowAnswer(false);
 			setNumbers(payload.numbers);
+			setPairs(payload.pairs ?? []);
 			setAnswerSum(payload.sum);
 			setTypedAnswer("");
 			setValidationSummary("");
@@ -439,6 +465,7 @@ export default function App() {
 			window.removeEventListener("keydown", onKeyDown);
 			unlistenCountdown();
 			unlistenFlash();
+			unlistenPair();
 			unlistenClear();
 			unlistenAutoRepeatWaiting();
 			unlistenAutoRepeatTick();
@@ -478,6 +505,7 @@ export default function App() {
 
 		setSessionId(null);
 		setNumbers([]);
+		setPairs([]);
 
 		const config: SessionConfigInput = {
 			digits_per_number: Math.trunc(digitsPerNumber()),
@@ -511,7 +539,7 @@ export default function App() {
 		} catch (e) {
 			setPhase("idle");
 			void setFullscreen(false);
-			setErrorText(String(e));
+			setErrorText(errorMessage(e));
 		}
 	};
 
@@ -534,6 +562,7 @@ export default function App() {
 			setAutoRepeatSecondsLeft(null);
 			setSessionId(null);
 			setNumbers([]);
+			setPairs([]);
 			void setFullscreen(false);
 		}
 	};
@@ -1068,7 +1097,7 @@ export default function App() {
 													await runtime.setSoundEnabled(true);
 												} catch (e) {
 													setSoundEnabled(false);
-													setErrorText(String(e));
+													setErrorText(errorMessage(e));
 												}
 											}}
 										/>
@@ -1087,7 +1116,7 @@ export default function App() {
 													await runtime.setSoundEnabled(false);
 												} catch (e) {
 													setSoundEnabled(true);
-													setErrorText(String(e));
+													setErrorText(errorMessage(e));
 												}
 											}}
 										/>
@@ -1137,7 +1166,7 @@ export default function App() {
 																await runtime.acknowledgeComplete(sid);
 															if (waiting) applyAutoRepeatWaiting(waiting);
 														} catch (e) {
-															setErrorText(String(e));
+															setErrorText(errorMessage(e));
 														}
 													}
 												}}
@@ -1149,16 +1178,22 @@ export default function App() {
 										<>
 											<div class="sumCard">
 												<div class="sumLabel">Correct answer</div>
-												<div class="sumValue">{answerSum()}</div>
+												<div class="sumValue">
+													{pairs().length > 0
+														? pairs()
+																.map((pair) => pair.answer)
+																.join(", ")
+														: answerSum()}
+												</div>
 											</div>
 
 											{hasValidated() && showNumbersList() ? (
 												<div class="answerNumbers">
-													<For each={numbers()}>
-														{(n, idx) => (
+													<For each={answerRows()}>
+														{(row, idx) => (
 															<div class="answerRow">
 																<div class="answerIndex">{idx() + 1}</div>
-																<div class="answerValue">{n}</div>
+																<div class="answerValue">{row}</div>
 															</div>
 														)}
 													</For>
@@ -1264,11 +1299,11 @@ export default function App() {
 
 									{hasValidated() && showNumbersList() ? (
 										<div class="answerNumbers">
-											<For each={numbers()}>
-												{(n, idx) => (
+											<For each={answerRows()}>
+												{(row, idx) => (
 													<div class="answerRow">
 														<div class="answerIndex">{idx() + 1}</div>
-														<div class="answerValue">{n}</div>
+														<div class="answerValue">{row}</div>
 													</div>
 												)}
 											</For>
@@ -34,4 +34,35 @@ describe("App flow tests", () => {
 		mock.emitSessionComplete({ session_id: 1, numbers: [7], sum: 7 });
 		expect(await screen.findByText("Session complete")).toBeTruthy();
 	});
+
+	it("flashes multiplication and division problems", async () => {
+		const mock = createMockRuntime();
+		initializeRuntime(mock);
+
+		render(() => <App />);
+		await new Promise((r) => setTimeout(r, 0));
+		window.dispatchEvent(new KeyboardEvent("keydown", { key: " " }));
+
+		mock.emitShowPair({
+			session_id: 1,
+			index: 1,
+			total: 2,
+			operation: "multiplication",
+			left: 12,
+			right: 3,
+			emitted_at_ms: Date.now(),
+		});
+		expect(await screen.findByText("12 × 3")).toBeTruthy();
+
+		mock.emitShowPair({
+			session_id: 1,
+			index: 2,
+			total: 2,
+			operation: "division",
+			left: 84,
+			right: 7,
+			emitted_at_ms: Date.now(),
+		});
+		expect(await screen.findByText("84 ÷ 7")).toBeTruthy();
+	});
 });
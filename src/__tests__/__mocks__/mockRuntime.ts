@@ -6,6 +6,7 @@ import type {
 	Runtime,
 	SessionComplete,
 	ShowNumber,
+	ShowPair,
 } from "../../runtime";
 
 type Listener<T> = (payload: T) => void;
@@ -14,6 +15,7 @@ export function createMockRuntime() {
 	const listeners = {
 		countdownTick: new Set<Listener<string>>(),
 		showNumber: new Set<Listener<ShowNumber>>(),
+		showPair: new Set<Listener<ShowPair>>(),
 		clearScreen: new Set<Listener<ClearScreen>>(),
 		autoRepeatWaiting: new Set<Listener<AutoRepeatWaitingPayload>>(),
 		autoRepeatTick: new Set<Listener<AutoRepeatTickPayload>>(),
@@ -96,6 +98,10 @@ export function createMockRuntime() {
 			listeners.showNumber.add(handler);
 			return () => listeners.showNumber.delete(handler);
 		},
+		async onShowPair(handler: (p: ShowPair) => void) {
+			listeners.showPair.add(handler);
+			return () => listeners.showPair.delete(handler);
+		},
 		async onClearScreen(handler: (p: ClearScreen) => void) {
 			listeners.clearScreen.add(handler);
 			return () => listeners.clearScreen.delete(handler);
@@ -124,6 +130,9 @@ export function createMockRuntime() {
 		emitShowNumber(payload: ShowNumber) {
 			for (const h of [...listeners.showNumber]) h(payload);
 		},
+		emitShowPair(payload: ShowPair) {
+			for (const h of [...listeners.showPair]) h(payload);
+		},
 		emitClearScreen(payload: ClearScreen) {
 			for (const h of [...listeners.clearScreen]) h(payload);
 		},
@@ -133,6 +142,7 @@ export function createMockRuntime() {
 	} as Runtime & {
 		emitCountdown: (v: string) => void;
 		emitShowNumber: (p: ShowNumber) => void;
+		emitShowPair: (p: ShowPair) => void;
 		emitClearScreen: (p: ClearScreen) => void;
 		emitSessionComplete: (p: SessionComplete) => void;
 	};
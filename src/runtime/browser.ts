@@ -9,7 +9,7 @@ import applauseUrl from "../assets/applause.wav?url";
 import beepUrl from "../assets/beep.wav?url";
 import buzzerUrl from "../assets/buzzer.wav?url";
 import { getWasmCoreBridge } from "../wasm/coreBridge";
-import type { Runtime, UnlistenFn } from "./index";
+import { errorMessage, type Runtime, type UnlistenFn } from "./index";
 import type {
 	AppSettings,
 	AutoRepeatConfig,
@@ -22,6 +22,7 @@ import type {
 	SessionConfigEffective,
 	SessionConfigInput,
 	ShowNumber,
+	ShowPair,
 	StartSessionResponse,
 	SubmitAnswerResponse,
 	ThemeMode,
@@ -65,6 +66,8 @@ const FIRST_FLASH_GRACE_MS = 100;
 const listeners = {
 	countdownTick: new Set<Listener<string>>(),
 	showNumber: new Set<Listener<ShowNumber>>(),
+	// The JS planner only builds running-sum sessions, so nothing emits pairs yet.
+	showPair: new Set<Listener<ShowPair>>(),
 	clearScreen: new Set<Listener<ClearScreen>>(),
 	autoRepeatWaiting: new Set<Listener<AutoRepeatWaitingPayload>>(),
 	autoRepeatTick: new Set<Listener<AutoRepeatTickPayload>>(),
@@ -364,10 +367,12 @@ function buildBridgeSeed(): number {
 async function resolvePlannedSessionData(
 	sessionId: number,
 	config: SessionConfigInput,
+	seed: number,
 ): Promise<{
 	config: SessionConfigEffective;
 	numbers: number[];
 	sum: number;
+	seed: number;
 } | null> {
 	const bridge = getWasmCoreBridge();
 	if (!bridge) {
@@ -387,8 +392,9 @@ async function resolvePlannedSessionData(
 
 	let plan: Awaited<ReturnType<typeof bridge.buildSessionPlan>>;
 	try {
-		plan = await bridge.buildSessionPlan(sessionId, config, buildBridgeSeed());
-	} catch {
+		plan = await bridge.buildSessionPlan(sessionId, config, seed);
+	} catch (err) {
+		console.warn("[runtime/browser] WASM planner error:", errorMessage(err));
 		if (!hasLoggedWasmPlannerFallback) {
 			hasLoggedWasmPlannerFallback = true;
 			console.info("[runtime/browser] WASM planner failed; using JS planner");
@@ -407,6 +413,7 @@ async function resolvePlannedSessionData(
 		config: plan.config_snapshot,
 		numbers: plan.numbers_generated.slice(),
 		sum: plan.expected_sum,
+		seed,
 	};
 }
 
@@ -519,6 +526,7 @@ function finishSession(session: BrowserSession): void {
 async function startSessionImpl(
 	config: SessionConfigInput,
 	autoRepeat?: AutoRepeatConfig | null,
+	seed?: number | null,
 ): Promise<StartSessionResponse> {
 	if (sessionStarting) {
 		throw new Error("A session is already starting");
@@ -529,6 +537,7 @@ async function startSessionImpl(
 		const plannedSession = await resolvePlannedSessionData(
 			nextSessionId,
 			config,
+			seed ?? buildBridgeSeed(),
 		);
 		const effectiveConfig =
 			plannedSession?.config ?? normalizeSessionConfig(config);
@@ -630,6 +639,7 @@ async function startSessionImpl(
 			session_id: sessionId,
 			effective_config: effectiveConfig,
 			effective_auto_repeat: effectiveAutoRepeat,
+			seed: plannedSession?.seed,
 		};
 	} finally {
 		sessionStarting = false;
@@ -837,6 +847,10 @@ export const browserRuntime: Runtime = {
 		return addListener(listeners.showNumber, handler);
 	},
 
+	async onShowPair(handler: (payload: ShowPair) => void): Promise<UnlistenFn> {
+		return addListener(listeners.showPair, handler);
+	},
+
 	async onClearScreen(
 		handler: (payload: ClearScreen) => void,
 	): Promise<UnlistenFn> {
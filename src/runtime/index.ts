@@ -22,11 +22,15 @@ export type {
 	AutoRepeatWaitingPayload,
 	ClearScreen,
 	ColorScheme,
+	FlashspanError,
+	FlashspanErrorCode,
+	OperandPair,
 	Phase,
 	SessionComplete,
 	SessionConfigEffective,
 	SessionConfigInput,
 	ShowNumber,
+	ShowPair,
 	StartSessionResponse,
 	SubmitAnswerResponse,
 	ThemeMode,
@@ -34,6 +38,24 @@ export type {
 	ValidationResult,
 } from "./types";
 
+/** Whether `e` is an error object rejected by a native command or the wasm bridge. */
+export function isFlashspanError(e: unknown): e is FlashspanError {
+	return (
+		typeof e === "object" &&
+		e !== null &&
+		typeof (e as { code?: unknown }).code === "string" &&
+		typeof (e as { message?: unknown }).message === "string"
+	);
+}
+
+/** Text to show for a caught error, whichever runtime threw it. */
+export function errorMessage(e: unknown): string {
+	if (isFlashspanError(e) || e instanceof Error) {
+		return e.message;
+	}
+	return String(e);
+}
+
 // --- Runtime instance singleton ---
 let _runtime: Runtime | null = null;
 
@@ -50,6 +72,7 @@ export interface Runtime {
 	startSession(
 		config: SessionConfigInput,
 		autoRepeat?: AutoRepeatConfig | null,
+		seed?: number | null,
 	): Promise<StartSessionResponse>;
 	stopSession(): Promise<void>;
 	cancelAutoRepeat(): Promise<void>;
@@ -72,6 +95,8 @@ export interface Runtime {
 	// --- Event listeners ---
 	onCountdownTick(handler: (value: string) => void): Promise<UnlistenFn>;
 	onShowNumber(handler: (payload: ShowNumber) => void): Promise<UnlistenFn>;
+	/** Multiplication and division sessions flash one problem at a time. */
+	onShowPair(handler: (payload: ShowPair) => void): Promise<UnlistenFn>;
 	onClearScreen(handler: (payload: ClearScreen) => void): Promise<UnlistenFn>;
 	onAutoRepeatWaiting(
 		handler: (payload: AutoRepeatWaitingPayload) => void,
@@ -95,9 +120,11 @@ import type {
 	AutoRepeatWaitingPayload,
 	ClearScreen,
 	ColorScheme,
+	FlashspanError,
 	SessionComplete,
 	SessionConfigInput,
 	ShowNumber,
+	ShowPair,
 	StartSessionResponse,
 	SubmitAnswerResponse,
 	ThemeMode,
@@ -155,8 +182,11 @@ export const runtime = {
 		return (mode: ThemeMode) => getRuntime().setThemeMode(mode);
 	},
 	get startSession() {
-		return (config: SessionConfigInput, autoRepeat?: AutoRepeatConfig | null) =>
-			getRuntime().startSession(config, autoRepeat);
+		return (
+			config: SessionConfigInput,
+			autoRepeat?: AutoRepeatConfig | null,
+			seed?: number | null,
+		) => getRuntime().startSession(config, autoRepeat, seed);
 	},
 	get stopSession() {
 		return () => getRuntime().stopSession();
@@ -196,6 +226,10 @@ export const runtime = {
 		return (handler: (payload: ShowNumber) => void) =>
 			getRuntime().onShowNumber(handler);
 	},
+	get onShowPair() {
+		return (handler: (payload: ShowPair) => void) =>
+			getRuntime().onShowPair(handler);
+	},
 	get onClearScreen() {
 		return (handler: (payload: ClearScreen) => void) =>
 			getRuntime().onClearScreen(handler);
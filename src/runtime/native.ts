@@ -25,6 +25,7 @@ import type {
 	SessionComplete,
 	SessionConfigInput,
 	ShowNumber,
+	ShowPair,
 	StartSessionResponse,
 	SubmitAnswerResponse,
 	ThemeMode,
@@ -66,14 +67,13 @@ export const nativeRuntime: Runtime = {
 	async startSession(
 		config: SessionConfigInput,
 		autoRepeat?: AutoRepeatConfig | null,
+		seed?: number | null,
 	): Promise<StartSessionResponse> {
-		if (autoRepeat) {
-			return invoke<StartSessionResponse>("start_session", {
-				config,
-				autoRepeat: autoRepeat,
-			});
-		}
-		return invoke<StartSessionResponse>("start_session", { config });
+		return invoke<StartSessionResponse>("start_session", {
+			config,
+			autoRepeat: autoRepeat ?? null,
+			seed: seed ?? null,
+		});
 	},
 
 	async stopSession(): Promise<void> {
@@ -164,6 +164,15 @@ export const nativeRuntime: Runtime = {
 		return unlisten as UnlistenFn;
 	},
 
+	async onShowPair(
+		handler: (payload: ShowPair) => void,
+	): Promise<UnlistenFn> {
+		const unlisten = await listen<ShowPair>("show_pair", (event) => {
+			handler(event.payload);
+		});
+		return unlisten as UnlistenFn;
+	},
+
 	async onClearScreen(
 		handler: (payload: ClearScreen) => void,
 	): Promise<UnlistenFn> {
@@ -15,6 +15,61 @@ export type ColorScheme =
 
 export type ThemeMode = "dark" | "light";
 
+export type FormulaMode =
+	| "any"
+	| "direct"
+	| "five_complement"
+	| "ten_complement"
+	| "mixed";
+
+export type CarryPolicy =
+	| { kind: "any" }
+	| { kind: "no_carries" }
+	| { kind: "at_most"; max: number }
+	| { kind: "in_column"; column: number };
+
+export type OperationMode = "addition" | "multiplication" | "division";
+
+/** Reading rules for dictation sessions. */
+export type DictationLanguage = "english" | "japanese";
+
+/** Result of `list_voices`: dictation needs every clip of a language. */
+export interface VoiceStatus {
+	language: DictationLanguage;
+	missing: string[];
+}
+
+export interface OperandPair {
+	operation: OperationMode;
+	left: number;
+	right: number;
+	answer: number;
+}
+
+export interface DigitRange {
+	min: number;
+	max: number;
+}
+
+export type TimingSchedule =
+	| { kind: "constant" }
+	| { kind: "linear_ramp"; end_duration_s: number }
+	| { kind: "steps"; every: number; change_s: number }
+	| { kind: "per_index"; durations_s: number[] };
+
+/** `TimingSchedule` in milliseconds, as the core generates plans from it. */
+export type TimingScheduleMs =
+	| { kind: "constant" }
+	| { kind: "linear_ramp"; end_duration_ms: number }
+	| { kind: "steps"; every: number; change_ms: number }
+	| { kind: "per_index"; durations_ms: number[] };
+
+/** Where a running-sum session stops to ask for the subtotal so far (addition only). */
+export type CheckpointSchedule =
+	| { kind: "off" }
+	| { kind: "every"; every: number }
+	| { kind: "after"; positions: number[] };
+
 export type UnlistenFn = () => void;
 
 export interface AppSettings {
@@ -22,12 +77,48 @@ export interface AppSettings {
 	theme_mode: ThemeMode;
 }
 
+/**
+ * Playback levels in percent (0–100). `master` scales every sound; `beep` scales the flash
+ * cue (or dictation) and `feedback` the applause and buzzer.
+ */
+export interface AudioLevels {
+	master: number;
+	beep: number;
+	feedback: number;
+}
+
+/** An installed sound pack; kinds it does not replace play the built-in sounds. */
+export interface SoundPackInfo {
+	id: string;
+	name: string;
+	sounds: Array<"beep" | "applause" | "buzzer">;
+	/** Manifest entries that were skipped, with the reason. */
+	problems: string[];
+}
+
+/** Result of `list_sound_packs`; `selected_id` is null when the built-in sounds play. */
+export interface SoundPackList {
+	selected_id: string | null;
+	packs: SoundPackInfo[];
+}
+
 export interface SessionConfigInput {
 	digits_per_number: number;
 	number_duration_s: number;
 	delay_between_numbers_s: number;
 	total_numbers: number;
 	allow_negative_numbers: boolean;
+	formula_mode?: FormulaMode;
+	carry_policy?: CarryPolicy;
+	operation?: OperationMode;
+	second_operand_digits?: number;
+	decimal_places?: number;
+	timing_schedule?: TimingSchedule;
+	digit_range?: DigitRange | null;
+	digit_pattern?: number[];
+	/** Read numbers aloud instead of beeping (addition only). */
+	dictation?: DictationLanguage | null;
+	checkpoints?: CheckpointSchedule;
 }
 
 export interface SessionConfigEffective {
@@ -36,24 +127,85 @@ export interface SessionConfigEffective {
 	delay_between_numbers_s: number;
 	total_numbers: number;
 	allow_negative_numbers: boolean;
+	formula_mode?: FormulaMode;
+	carry_policy?: CarryPolicy;
+	operation?: OperationMode;
+	second_operand_digits?: number;
+	decimal_places?: number;
+	timing_schedule?: TimingSchedule;
+	digit_range?: DigitRange | null;
+	digit_pattern?: number[];
+	dictation?: DictationLanguage | null;
+	checkpoints?: CheckpointSchedule;
 }
 
+/** How auto-repeat seeds each round after the first (default `random`). */
+export type SeedStrategy = "fixed" | "incrementing" | "random";
+
 export interface AutoRepeatConfig {
 	enabled: boolean;
 	repeats: number;
 	delay_s: number;
+	adaptive?: AdaptivePolicyInput | null;
+	seed_strategy?: SeedStrategy;
 }
 
 export interface AutoRepeatEffective {
 	enabled: boolean;
 	repeats: number;
 	delay_s: number;
+	adaptive?: AdaptivePolicy | null;
+	seed_strategy?: SeedStrategy;
+}
+
+export interface AdaptivePolicyInput {
+	harden_after_correct?: number;
+	ease_after_wrong?: number;
+	duration_step_s?: number;
+	min_duration_s?: number;
+	max_duration_s?: number;
+	digits_step?: number;
+	min_digits?: number;
+	max_digits?: number;
+	total_numbers_step?: number;
+	min_total_numbers?: number;
+	max_total_numbers?: number;
+}
+
+export interface AdaptivePolicy {
+	harden_after_correct: number;
+	ease_after_wrong: number;
+	duration_step_ms: number;
+	min_duration_ms: number;
+	max_duration_ms: number;
+	digits_step: number;
+	min_digits: number;
+	max_digits: number;
+	total_numbers_step: number;
+	min_total_numbers: number;
+	max_total_numbers: number;
+}
+
+export interface AdaptiveLevel {
+	number_duration_ms: number;
+	digits_per_number: number;
+	total_numbers: number;
+}
+
+export interface AdaptiveAdjustment {
+	correct: boolean;
+	direction: "harder" | "easier" | "hold";
+	state: { correct_streak: number; wrong_streak: number };
+	previous: AdaptiveLevel;
+	next: AdaptiveLevel;
 }
 
 export interface StartSessionResponse {
 	session_id: number;
 	effective_config: SessionConfigEffective;
 	effective_auto_repeat: AutoRepeatEffective | null;
+	/** Pass back to `startSession` to replay the same numbers. */
+	seed?: number;
 }
 
 export interface ShowNumber {
@@ -62,6 +214,19 @@ export interface ShowNumber {
 	total: number;
 	value: number;
 	running_sum: number;
+	carries?: number;
+	display?: string;
+	decimal_places?: number;
+	emitted_at_ms: number;
+}
+
+export interface ShowPair {
+	session_id: number;
+	index: number;
+	total: number;
+	operation: OperationMode;
+	left: number;
+	right: number;
 	emitted_at_ms: number;
 }
 
@@ -75,12 +240,234 @@ export interface SessionComplete {
 	session_id: number;
 	numbers: number[];
 	sum: number;
+	decimal_places?: number;
+	pairs?: OperandPair[];
+	started_at_ms?: number;
+	completed_at_ms?: number;
+	config?: SessionConfigEffective;
+	profile_id?: string | null;
+	seed?: number;
+	/** Subtotals asked for during the session, in order. */
+	checkpoints?: CheckpointResult[];
+}
+
+/** Asks for the sum of the first `index` numbers; answer with `answer_checkpoint`. */
+export interface Checkpoint {
+	session_id: number;
+	index: number;
+	total: number;
+	decimal_places: number;
+	emitted_at_ms: number;
+}
+
+export interface CheckpointResult {
+	index: number;
+	validation: ValidationResult;
+	/** From the checkpoint being asked to the answer. */
+	answer_ms: number;
+}
+
+export interface HistoryAnswer {
+	provided_sum: number;
+	provided_answers?: number[];
+	correct: boolean;
+	delta: number;
+	submitted_at_ms: number;
+	answer_ms?: number;
+	/** Points under the scoring weights in effect when the answer was given. */
+	score?: number;
+}
+
+/**
+ * Weights for scoring answers, saved per profile (`get_scoring` / `set_scoring`). A correct
+ * answer earns `base_points`, raised by the percent bonuses per extra digit and number and by
+ * flashing faster than `reference_pace_ms` per number; past `latency_grace_ms` the points
+ * halve every `latency_half_life_ms` (0 ignores answer time).
+ */
+export interface ScoringConfig {
+	base_points: number;
+	digit_bonus_pct: number;
+	number_bonus_pct: number;
+	reference_pace_ms: number;
+	latency_grace_ms: number;
+	latency_half_life_ms: number;
+}
+
+export interface HistoryRecord {
+	session_id: number;
+	started_at_ms: number;
+	completed_at_ms: number;
+	config: SessionConfigEffective;
+	numbers: number[];
+	pairs?: OperandPair[];
+	decimal_places: number;
+	expected_sum: number;
+	profile_id?: string | null;
+	seed?: number;
+	checkpoints?: CheckpointResult[];
+	answer: HistoryAnswer | null;
+}
+
+export interface Preset {
+	name: string;
+	config: SessionConfigInput;
+	auto_repeat?: AutoRepeatConfig | null;
+}
+
+export interface PresetView {
+	name: string;
+	config: SessionConfigEffective;
+	auto_repeat: AutoRepeatEffective | null;
+	saved_at_ms: number;
+}
+
+export interface PresetImportSummary {
+	imported: string[];
+	replaced: string[];
+	skipped: string[];
+}
+
+export interface Profile {
+	id: string;
+	name: string;
+	created_at_ms: number;
+}
+
+export interface ProfileList {
+	active_id: string;
+	profiles: Profile[];
+}
+
+export interface HistoryFilter {
+	from_ms?: number;
+	to_ms?: number;
+	correct?: boolean;
+	digits_per_number?: number;
+	total_numbers?: number;
+	operation?: OperationMode;
+	decimal_places?: number;
+	allow_negative_numbers?: boolean;
+	limit?: number;
+	/** `score` ranks the highest scores first (default `newest`). */
+	order?: "newest" | "score";
+}
+
+/** One session as analytics sees it; a `HistoryRecord` already has this shape. */
+export interface SessionSample {
+	completed_at_ms: number;
+	config: SessionConfigEffective;
+	answer?: Pick<HistoryAnswer, "correct" | "delta" | "submitted_at_ms"> | null;
+}
+
+/** Everything that groups sessions except flash speed. */
+export interface ConfigKey {
+	operation: OperationMode;
+	digits_per_number: number;
+	total_numbers: number;
+	decimal_places: number;
+	allow_negative_numbers: boolean;
+	formula_mode: FormulaMode;
+	carry_policy: CarryPolicy;
+	digit_range: DigitRange | null;
+	digit_pattern: number[];
+	timing_schedule: TimingScheduleMs;
+	dictation: DictationLanguage | null;
+}
+
+export interface SessionStats {
+	completed_at_ms: number;
+	config: ConfigKey;
+	number_duration_ms: number;
+	correct: boolean | null;
+	/** In display units, not fixed-point. */
+	abs_delta: number | null;
+	answer_ms: number | null;
+}
+
+export interface TimeBucket {
+	from_ms: number;
+	/** Exclusive; `null` for the last, open-ended bucket. */
+	to_ms: number | null;
+	count: number;
+}
+
+export interface TimeDistribution {
+	buckets: TimeBucket[];
+	median_ms: number | null;
+	p90_ms: number | null;
+}
+
+export interface DurationStats {
+	number_duration_ms: number;
+	answered: number;
+	accuracy: number;
+}
+
+export interface ConfigStats {
+	config: ConfigKey;
+	sessions: number;
+	answered: number;
+	/** 0 to 1; `null` until a session is answered. */
+	accuracy: number | null;
+	mean_abs_delta: number | null;
+	answer_time: TimeDistribution;
+	/** Fastest flash first. */
+	by_duration: DurationStats[];
+	/** Shortest flash passed at 80% accuracy or better over at least 3 answers. */
+	fastest_passed_duration_ms: number | null;
+}
+
+export interface TrendPoint {
+	/** Local midnight starting the day or week (Monday). */
+	start_ms: number;
+	sessions: number;
+	answered: number;
+	accuracy: number | null;
+	mean_abs_delta: number | null;
+	median_answer_ms: number | null;
+}
+
+/** Result of `get_analytics` / `analyze_sessions_wasm`. Trend series have no gaps. */
+export interface AnalyticsReport {
+	sessions: SessionStats[];
+	configs: ConfigStats[];
+	daily: TrendPoint[];
+	weekly: TrendPoint[];
+}
+
+export interface StepTiming {
+	step_index: number;
+	planned_offset_us: number;
+	actual_offset_us: number;
+	jitter_us: number;
+}
+
+export interface TimingReport {
+	session_id: number;
+	steps: StepTiming[];
+	max_abs_jitter_us: number;
+	mean_abs_jitter_us: number;
+	paused_ms: number;
+	completed: boolean;
+}
+
+export interface SessionPaused {
+	session_id: number;
+	remaining_ms: number;
+	emitted_at_ms: number;
+}
+
+export interface SessionResumed {
+	session_id: number;
+	paused_ms: number;
+	emitted_at_ms: number;
 }
 
 export interface AutoRepeatWaitingPayload {
 	session_id: number;
 	next_start_at_ms: number;
 	remaining: number;
+	adjustment?: AdaptiveAdjustment;
 }
 
 export interface AutoRepeatTickPayload {
@@ -94,10 +481,142 @@ export interface ValidationResult {
 	provided_sum: number;
 	correct: boolean;
 	delta: number;
+	decimal_places?: number;
+	pairs?: PairValidation[];
+	/** From the session's last step to the answer. Desktop only. */
+	answer_ms?: number;
+}
+
+export interface PairValidation {
+	index: number;
+	operation: OperationMode;
+	left: number;
+	right: number;
+	expected: number;
+	provided: number;
+	correct: boolean;
+	delta: number;
+}
+
+export type MistakeKind =
+	| "skipped_number"
+	| "doubled_number"
+	| "sign_flip"
+	| "column_slip"
+	| "transposition";
+
+/** A likely cause of a wrong running sum. */
+export interface MistakeCandidate {
+	kind: MistakeKind;
+	/** Indices into the session's `numbers`. */
+	terms?: number[];
+	/** Digit positions counted from the smallest unit: in the number when `terms` is set, otherwise in the answer. */
+	digits?: number[];
+	/** 0 to 1. */
+	likelihood: number;
 }
 
 export interface SubmitAnswerResponse {
 	validation: ValidationResult;
 	auto_repeat_waiting: AutoRepeatWaitingPayload | null;
 	message: string;
+	/** Most likely first; empty for correct answers. Desktop only. */
+	diagnosis?: MistakeCandidate[];
+	/** Desktop only. */
+	score?: number;
+}
+
+export interface RaceStanding {
+	player_id: number;
+	name: string;
+	/** Answer to the current round; null while the player is still thinking. */
+	answer: { correct: boolean; answer_ms: number; score: number } | null;
+	rounds_correct: number;
+	total_score: number;
+}
+
+/** Race players, ranked for the current round (`race_leaderboard` event on the host). */
+export interface Leaderboard {
+	round: number | null;
+	standings: RaceStanding[];
+}
+
+/** Result of `race_host_start`. */
+export interface RaceHostInfo {
+	port: number;
+	leaderboard: Leaderboard;
+}
+
+/** Result of `race_start_round`. */
+export interface RaceRoundResponse {
+	round: number;
+	effective_config: SessionConfigEffective;
+	seed: number;
+}
+
+/** Payload of the `race_event` event on a joined player. */
+export type RaceEvent =
+	| { type: "round"; round: number; session_id: number }
+	| {
+			type: "verdict";
+			round: number;
+			validation: ValidationResult;
+			answer_ms: number;
+			score: number;
+	  }
+	| { type: "leaderboard"; leaderboard: Leaderboard }
+	| { type: "error"; reason: string }
+	| { type: "disconnected" };
+
+/** Stable codes of the errors native commands and the wasm bridge reject with. */
+export type FlashspanErrorCode =
+	| "SessionAlreadyRunning"
+	| "NoSessionRunning"
+	| "ResultNotFound"
+	| "TimingReportNotFound"
+	| "NoCheckpointPending"
+	| "SessionSpawnFailed"
+	| "ConfigOutOfRange"
+	| "ConfigConflict"
+	| "InvalidAnswer"
+	| "AnswerCountMismatch"
+	| "InvalidArgs"
+	| "Encode"
+	| "ProfileNotFound"
+	| "PresetNotFound"
+	| "InvalidName"
+	| "NameTaken"
+	| "LimitReached"
+	| "ProfileProtected"
+	| "InvalidFile"
+	| "UnsupportedVersion"
+	| "InvalidPlan"
+	| "Storage"
+	| "Race"
+	| "UnknownSound"
+	| "SoundPackNotFound"
+	| "Audio";
+
+/**
+ * Error object from the Rust side. `message` is ready to show; the remaining fields depend
+ * on `code` (e.g. `field`, `min` and `max` for `ConfigOutOfRange`).
+ */
+export interface FlashspanError {
+	code: FlashspanErrorCode;
+	message: string;
+	session_id?: number;
+	field?: string;
+	min?: number;
+	max?: number;
+	hint?: string;
+	expected?: number;
+	provided?: number;
+	detail?: string;
+	id?: string;
+	name?: string;
+	what?: string;
+	found?: number;
+	supported?: number;
+	reason?: string;
+	kind?: string;
 }
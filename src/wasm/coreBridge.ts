@@ -1,6 +1,8 @@
 import type {
+	AnalyticsReport,
 	SessionConfigEffective,
 	SessionConfigInput,
+	SessionSample,
 } from "../runtime/types";
 
 export interface WasmSessionConfig {
@@ -34,6 +36,10 @@ export interface WasmCoreBridge {
 		input: SessionConfigInput,
 		seed?: number | null,
 	): Promise<WasmSessionPlan>;
+	analyzeSessions(
+		samples: SessionSample[],
+		utcOffsetMinutes: number,
+	): Promise<AnalyticsReport>;
 }
 
 let wasmCoreBridge: WasmCoreBridge | null = null;
@@ -1,4 +1,8 @@
-import type { SessionConfigInput } from "../runtime/types";
+import type {
+	AnalyticsReport,
+	SessionConfigInput,
+	SessionSample,
+} from "../runtime/types";
 import {
 	registerWasmCoreBridge,
 	type WasmCoreBridge,
@@ -46,9 +50,18 @@ export async function loadWasmCoreBridge(): Promise<boolean> {
 			[number, SessionConfigInput, number | null | undefined],
 			WasmSessionPlan
 		>(wasmModule.build_session_plan_wasm);
+		const analyzeSessionsWasm = asFunction<
+			[SessionSample[], number],
+			AnalyticsReport
+		>(wasmModule.analyze_sessions_wasm);
 		const wasmVersion = asFunction<[], string>(wasmModule.wasm_version);
 
-		if (!init || !normalizeSessionConfigWasm || !buildSessionPlanWasm) {
+		if (
+			!init ||
+			!normalizeSessionConfigWasm ||
+			!buildSessionPlanWasm ||
+			!analyzeSessionsWasm
+		) {
 			console.info(
 				"[wasm] Generated module exports are incomplete; skipping WASM bridge",
 			);
@@ -70,6 +83,12 @@ export async function loadWasmCoreBridge(): Promise<boolean> {
 			) {
 				return buildSessionPlanWasm(sessionId, input, seed ?? null);
 			},
+			async analyzeSessions(
+				samples: SessionSample[],
+				utcOffsetMinutes: number,
+			) {
+				return analyzeSessionsWasm(samples, utcOffsetMinutes);
+			},
 		};
 
 		registerWasmCoreBridge(bridge);